@@ -0,0 +1,279 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Baselines of accepted alerts, so only new issues are reported
+//!
+//! Alerts are identified by a [`fingerprint`] of their file, type and
+//! whitespace-normalized snippet - not their line number - so a baseline
+//! survives code being added or removed elsewhere in the file. Editing the
+//! lines inside an alert's snippet makes it a new alert.
+//!
+//! Baselines are saved as JSON or, more compactly, as a sorted list of
+//! fingerprints one per line (see [`BaselineFormat`]); loading accepts either.
+
+use crate::error::{DetectError, Result};
+use crate::BullshitAlert;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// FNV-1a parameters; unlike `DefaultHasher` the output is stable across
+/// Rust releases, which a committed baseline needs
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Stable, line-independent identifier for an alert
+pub fn fingerprint(alert: &BullshitAlert) -> String {
+    let snippet = alert
+        .context_snippet
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let key = format!(
+        "{}\0{}\0{}",
+        alert.file_path.as_deref().unwrap_or_default(),
+        alert.issue_type,
+        snippet
+    );
+
+    let hash = key.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+/// How a baseline file is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BaselineFormat {
+    /// `{"fingerprints": [...]}`, pretty-printed
+    #[default]
+    Json,
+    /// One fingerprint per line, sorted
+    Hashes,
+}
+
+impl FromStr for BaselineFormat {
+    type Err = DetectError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(BaselineFormat::Json),
+            "hashes" => Ok(BaselineFormat::Hashes),
+            other => Err(DetectError::InvalidConfig(format!(
+                "Unknown baseline format {} (expected json or hashes)",
+                other
+            ))),
+        }
+    }
+}
+
+/// A set of accepted alert fingerprints
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub fingerprints: BTreeSet<String>,
+}
+
+/// What [`Baseline::update`] changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaselineUpdate {
+    /// Fingerprints newly accepted into the baseline
+    pub added: Vec<String>,
+    /// Fingerprints already in the baseline and still present in the code
+    pub kept: usize,
+    /// Fingerprints in the baseline that no scan produced any more
+    pub stale: Vec<String>,
+}
+
+impl Baseline {
+    /// Baseline accepting every alert in `alerts`
+    pub fn from_alerts(alerts: &[BullshitAlert]) -> Self {
+        Self {
+            fingerprints: alerts.iter().map(fingerprint).collect(),
+        }
+    }
+
+    /// Load a baseline written by [`Baseline::save`] or [`Baseline::save_as`],
+    /// in either format
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid =
+            |msg: String| DetectError::InvalidConfig(format!("{}: {}", path.display(), msg));
+
+        if contents.trim_start().starts_with('{') {
+            return serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()));
+        }
+
+        let mut fingerprints = BTreeSet::new();
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            if line.len() != 16 || !line.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(invalid(format!("not a fingerprint: {}", line)));
+            }
+            fingerprints.insert(line.to_ascii_lowercase());
+        }
+        Ok(Self { fingerprints })
+    }
+
+    /// Write the baseline as pretty-printed JSON, fingerprints sorted
+    pub fn save(&self, path: &Path) -> Result<()> {
+        self.save_as(path, BaselineFormat::Json)
+    }
+
+    /// Write the baseline in `format`, fingerprints sorted
+    pub fn save_as(&self, path: &Path, format: BaselineFormat) -> Result<()> {
+        let contents = match format {
+            BaselineFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(io::Error::from)? + "\n"
+            }
+            BaselineFormat::Hashes => self
+                .fingerprints
+                .iter()
+                .map(|fingerprint| format!("{}\n", fingerprint))
+                .collect(),
+        };
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Whether `alert` has been accepted
+    pub fn contains(&self, alert: &BullshitAlert) -> bool {
+        self.fingerprints.contains(&fingerprint(alert))
+    }
+
+    /// Drop accepted alerts
+    pub fn suppress(&self, alerts: &mut Vec<BullshitAlert>) {
+        alerts.retain(|alert| !self.contains(alert));
+    }
+
+    /// Accept every fingerprint in `current` (all fingerprints a scan produced),
+    /// keeping existing entries even when they've gone stale
+    pub fn update(&mut self, current: &BTreeSet<String>) -> BaselineUpdate {
+        let stale = self.fingerprints.difference(current).cloned().collect();
+        let added: Vec<String> = current.difference(&self.fingerprints).cloned().collect();
+        let kept = current.len() - added.len();
+
+        self.fingerprints.extend(added.iter().cloned());
+        BaselineUpdate { added, kept, stale }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scan_code, BullshitType, DetectConfig};
+
+    /// Keeps neighbouring functions out of each other's snippets
+    const SPACER: &str =
+        "\n// ----------------------------------------------------------------\n\n";
+
+    fn scan(code: &str) -> Vec<BullshitAlert> {
+        let mut alerts = scan_code(code, &DetectConfig::default()).unwrap();
+        for alert in &mut alerts {
+            alert.file_path = Some("src/lib.rs".to_string());
+        }
+        alerts
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_moves() {
+        let before = scan(&format!(
+            "{}fn f() {{\n    let v = x.clone();\n}}\n",
+            SPACER
+        ));
+        let after = scan(&format!(
+            "fn added() {{}}{}fn f() {{\n    let v = x.clone();\n}}\n",
+            SPACER
+        ));
+        let clone = |alerts: &[BullshitAlert]| {
+            alerts
+                .iter()
+                .find(|a| a.issue_type == BullshitType::CloneAbuse)
+                .map(fingerprint)
+                .unwrap()
+        };
+
+        assert_eq!(clone(&before), clone(&after));
+    }
+
+    #[test]
+    fn test_update_adds_keeps_and_reports_stale() {
+        let f = "fn f() {\n    let v = x.clone();\n}\n";
+        let old = scan(&format!("{}{}fn g() {{\n    a.unwrap();\n}}\n", f, SPACER));
+        let mut baseline = Baseline::from_alerts(&old);
+        let unwrap = old
+            .iter()
+            .find(|a| a.issue_type == BullshitType::UnwrapAbuse)
+            .map(fingerprint)
+            .unwrap();
+
+        // g() is fixed and h() introduces a new issue
+        let mut current = scan(&format!(
+            "{}{}fn h() {{\n    b.lock().unwrap();\n}}\n",
+            f, SPACER
+        ));
+        let fingerprints: BTreeSet<String> = current.iter().map(fingerprint).collect();
+        let update = baseline.update(&fingerprints);
+
+        assert_eq!(update.kept, 1);
+        assert_eq!(update.stale, vec![unwrap.clone()]);
+        assert!(!update.added.is_empty());
+        assert!(
+            baseline.fingerprints.contains(&unwrap),
+            "stale entries stay"
+        );
+        assert!(fingerprints.is_subset(&baseline.fingerprints));
+
+        baseline.suppress(&mut current);
+        assert!(current.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+    }
+
+    #[test]
+    fn test_both_formats_round_trip_and_suppress_alike() {
+        let code = format!(
+            "fn f() {{\n    let v = x.clone();\n}}\n{}fn g() {{\n    a.unwrap();\n}}\n",
+            SPACER
+        );
+        let alerts = scan(&code);
+        let baseline = Baseline::from_alerts(&alerts[..1]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("baseline.json");
+        let hashes = dir.path().join("baseline.txt");
+        baseline.save_as(&json, BaselineFormat::Json).unwrap();
+        baseline.save_as(&hashes, BaselineFormat::Hashes).unwrap();
+
+        let hashes_text = fs::read_to_string(&hashes).unwrap();
+        assert_eq!(hashes_text, format!("{}\n", fingerprint(&alerts[0])));
+        assert!(hashes_text.len() < fs::read_to_string(&json).unwrap().len());
+
+        let from_json = Baseline::load(&json).unwrap();
+        let from_hashes = Baseline::load(&hashes).unwrap();
+        assert_eq!(from_json, baseline);
+        assert_eq!(from_hashes, baseline);
+
+        let mut a = alerts.clone();
+        let mut b = alerts.clone();
+        from_json.suppress(&mut a);
+        from_hashes.suppress(&mut b);
+        assert_eq!(a.len(), alerts.len() - 1);
+        assert_eq!(
+            a.iter().map(fingerprint).collect::<Vec<_>>(),
+            b.iter().map(fingerprint).collect::<Vec<_>>()
+        );
+
+        fs::write(&hashes, "not-a-hash\n").unwrap();
+        assert!(Baseline::load(&hashes).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Git blame lookups for filtering alerts by age
+//!
+//! Lines without blame info (uncommitted, or files git doesn't track) count
+//! as the newest code and are always kept.
+
+use crate::error::Result;
+use crate::BullshitAlert;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// Sha git blame reports for lines that aren't committed yet
+const UNCOMMITTED_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// Parse `git blame --porcelain` output into commit dates keyed by line number
+///
+/// Uncommitted lines are left out of the map.
+pub fn parse_blame(output: &str) -> HashMap<usize, DateTime<Utc>> {
+    let mut dates = HashMap::new();
+    // Porcelain only prints a commit's headers the first time it appears
+    let mut commit_times: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    let mut current: Option<(&str, usize)> = None;
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            if let Some((sha, line_no)) = current.take() {
+                if let Some(date) = commit_times.get(sha) {
+                    dates.insert(line_no, *date);
+                }
+            }
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some("committer-time"), Some(secs), None) => {
+                let Some((sha, _)) = current else { continue };
+                if sha == UNCOMMITTED_SHA {
+                    continue;
+                }
+                if let Some(date) = secs
+                    .parse()
+                    .ok()
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                {
+                    commit_times.insert(sha, date);
+                }
+            }
+            (Some(sha), Some(_), Some(final_line)) if is_sha(sha) => {
+                if let Ok(line_no) = final_line.parse() {
+                    current = Some((sha, line_no));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    dates
+}
+
+/// Commit dates for every line of `path`
+///
+/// Files git can't blame (untracked, or outside a repository) give an empty
+/// map; failing to run `git` at all is an error.
+pub fn blame_file(path: &Path) -> Result<HashMap<usize, DateTime<Utc>>> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path.file_name().unwrap_or(path.as_os_str());
+
+    let output = Command::new("git")
+        .args(["blame", "--porcelain", "--"])
+        .arg(file_name)
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Ok(HashMap::new());
+    }
+
+    Ok(parse_blame(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Keep alerts whose line was committed at or after `cutoff`, or not committed at all
+pub fn retain_since(
+    alerts: &mut Vec<BullshitAlert>,
+    dates: &HashMap<usize, DateTime<Utc>>,
+    cutoff: DateTime<Utc>,
+) {
+    alerts.retain(|alert| {
+        dates
+            .get(&alert.location.line)
+            .is_none_or(|date| *date >= cutoff)
+    });
+}
+
+fn is_sha(field: &str) -> bool {
+    field.len() == 40 && field.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+4a1f7e2c9d8b3a6f5e4d3c2b1a0f9e8d7c6b5a49 1 1 2
+author Jane Doe
+author-mail <jane@example.com>
+author-time 1700000000
+author-tz +0000
+committer Jane Doe
+committer-mail <jane@example.com>
+committer-time 1700000100
+committer-tz +0000
+summary Initial commit
+filename src/lib.rs
+\tfn main() {
+4a1f7e2c9d8b3a6f5e4d3c2b1a0f9e8d7c6b5a49 2 2
+\t    let x = 0.7;
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-time 1800000000
+committer Not Committed Yet
+committer-time 1800000000
+summary Version of src/lib.rs from src/lib.rs
+filename src/lib.rs
+\t    let y = 0.8;
+b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5 4 4 1
+author John Roe
+author-time 1710000000
+committer John Roe
+committer-time 1710000500
+summary Close main
+filename src/lib.rs
+\t}
+";
+
+    #[test]
+    fn test_parse_blame_maps_lines_to_commit_dates() {
+        let dates = parse_blame(SAMPLE);
+
+        let initial = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+        let later = DateTime::from_timestamp(1_710_000_500, 0).unwrap();
+        assert_eq!(dates.get(&1), Some(&initial));
+        assert_eq!(dates.get(&2), Some(&initial));
+        assert_eq!(dates.get(&3), None, "uncommitted lines have no date");
+        assert_eq!(dates.get(&4), Some(&later));
+        assert_eq!(dates.len(), 3);
+    }
+}
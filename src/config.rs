@@ -0,0 +1,1003 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Configuration loading - `.bullshitdetector.toml` files and environment overrides
+//!
+//! Layering order is defaults, then the config file, then `NIODOO_DETECT_*`
+//! environment variables, then command line flags. `[[overrides]]` for
+//! matching paths are applied last, per file.
+
+use crate::error::{DetectError, Result};
+use crate::limits::compile_regex;
+use crate::magic_numbers::MagicNumberConfig;
+use crate::{BullshitAlert, BullshitType, DetectConfig, SeverityLevel};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config file names looked up in a directory, in priority order
+pub const CONFIG_FILE_NAMES: &[&str] = &[".bullshitdetector.toml", "bsd.toml"];
+
+/// Parsed contents of a config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    pub detect: DetectSection,
+    pub magic: MagicSection,
+    /// `[[overrides]]` tables
+    pub overrides: Vec<OverrideSection>,
+    /// `[gating]`: path glob to the lowest severity that fails the run there,
+    /// or `"never"`
+    pub gating: BTreeMap<String, String>,
+}
+
+/// The `[detect]` table - every key is optional and only overrides when present
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DetectSection {
+    pub confidence_threshold: Option<f32>,
+    pub max_snippet_length: Option<usize>,
+    pub enable_regex_fallback: Option<bool>,
+    pub max_params: Option<usize>,
+    pub max_nesting: Option<usize>,
+    pub snippet_context_bytes: Option<usize>,
+    pub max_function_lines: Option<usize>,
+    pub max_scan_millis: Option<u64>,
+    pub duplicate_window: Option<usize>,
+    pub max_public_items: Option<usize>,
+    pub allow_print_macros: Option<bool>,
+    pub min_severity: Option<f32>,
+    pub max_chain_len: Option<usize>,
+    pub max_impl_methods: Option<usize>,
+    pub max_returns: Option<usize>,
+    pub max_type_params: Option<usize>,
+    pub max_string_literal_len: Option<usize>,
+    pub include_generated: Option<bool>,
+    /// `TypeName = confidence`, e.g. `CloneAbuse = 0.4`
+    pub confidence_overrides: HashMap<String, f32>,
+    /// Type names that are never reported, e.g. `["CloneAbuse"]`
+    pub disabled_types: Vec<String>,
+    /// Opt-in type names to report, e.g. `["MixedErrorHandling"]`
+    pub enabled_types: Vec<String>,
+    /// Paths where print macros are expected output, added to the defaults
+    pub print_allowed_paths: Vec<String>,
+    /// Glob import paths that aren't flagged, added to the defaults
+    pub glob_import_allowlist: Vec<String>,
+}
+
+/// An `[[overrides]]` table: `[detect]` keys for files matching `path`
+///
+/// ```toml
+/// [[overrides]]
+/// path = "src/experiments/**"
+/// confidence_threshold = 0.9
+/// disabled_types = ["CloneAbuse"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OverrideSection {
+    pub path: String,
+    #[serde(flatten)]
+    pub detect: DetectSection,
+}
+
+/// A compiled path glob (`*`, `**` and `?` wildcards)
+///
+/// A relative glob matches at any directory boundary, and a glob naming a
+/// directory matches everything under it.
+#[derive(Debug, Clone)]
+pub struct PathGlob {
+    /// The glob as written in the config file
+    pub glob: String,
+    pattern: Regex,
+    /// Literal (non-wildcard) characters in the glob; more is more specific
+    specificity: usize,
+}
+
+impl PathGlob {
+    pub fn new(glob: &str) -> Result<Self> {
+        let mut pattern = String::from(if glob.starts_with('/') {
+            "^"
+        } else {
+            "^(?:.*/)?"
+        });
+        let mut specificity = 0;
+        let mut rest = glob.trim_start_matches("./").trim_end_matches('/');
+        while let Some(ch) = rest.chars().next() {
+            let (regex, len) = if rest.starts_with("**/") {
+                ("(?:.*/)?".to_string(), 3)
+            } else if rest.starts_with("**") {
+                (".*".to_string(), 2)
+            } else if ch == '*' {
+                ("[^/]*".to_string(), 1)
+            } else if ch == '?' {
+                ("[^/]".to_string(), 1)
+            } else {
+                specificity += 1;
+                (regex::escape(&ch.to_string()), ch.len_utf8())
+            };
+            pattern.push_str(&regex);
+            rest = &rest[len..];
+        }
+        pattern.push_str("(?:/.*)?$");
+
+        Ok(Self {
+            glob: glob.to_string(),
+            pattern: compile_regex(&pattern)?,
+            specificity,
+        })
+    }
+
+    /// Whether the glob matches `path`
+    pub fn matches(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        self.pattern.is_match(path.trim_start_matches("./"))
+    }
+}
+
+/// A compiled `[[overrides]]` entry
+#[derive(Debug, Clone)]
+pub struct PathOverride {
+    /// The glob as written in the config file
+    pub glob: String,
+    path: PathGlob,
+    detect: DetectSection,
+}
+
+impl PathOverride {
+    /// Compile `glob` (see [`PathGlob`]) for the given settings
+    pub fn new(glob: &str, detect: DetectSection) -> Result<Self> {
+        // Reject bad type names now so applying per file can't fail
+        detect.apply_to(&mut DetectConfig::default())?;
+
+        Ok(Self {
+            glob: glob.to_string(),
+            path: PathGlob::new(glob)?,
+            detect,
+        })
+    }
+
+    /// Whether this override applies to `path`
+    pub fn matches(&self, path: &Path) -> bool {
+        self.path.matches(path)
+    }
+}
+
+/// A compiled `[gating]` entry: alerts under `path` fail the run from
+/// `fail_on` up, or never when it's `None`
+#[derive(Debug, Clone)]
+pub struct GatingRule {
+    pub path: PathGlob,
+    pub fail_on: Option<SeverityLevel>,
+}
+
+impl GatingRule {
+    /// Compile a `glob = "critical" | "high" | "medium" | "never"` entry
+    pub fn new(glob: &str, fail_on: &str) -> Result<Self> {
+        let fail_on = if fail_on.trim().eq_ignore_ascii_case("never") {
+            None
+        } else {
+            Some(fail_on.parse()?)
+        };
+        Ok(Self {
+            path: PathGlob::new(glob)?,
+            fail_on,
+        })
+    }
+}
+
+/// Decides whether reported alerts fail the run
+///
+/// The most specific `[gating]` rule matching an alert's file sets the
+/// severity that fails; files no rule matches use `fail_on` (`--fail-on`).
+/// Without either, nothing fails.
+#[derive(Debug, Clone, Default)]
+pub struct Gate {
+    pub fail_on: Option<SeverityLevel>,
+    pub rules: Vec<GatingRule>,
+}
+
+impl Gate {
+    /// Whether `alert` is severe enough to fail the run where it was found
+    pub fn fails(&self, alert: &BullshitAlert) -> bool {
+        let rule = alert.file_path.as_deref().and_then(|file| {
+            self.rules
+                .iter()
+                .filter(|rule| rule.path.matches(Path::new(file)))
+                .max_by_key(|rule| rule.path.specificity)
+        });
+        let fail_on = match rule {
+            Some(rule) => rule.fail_on,
+            None => self.fail_on,
+        };
+        fail_on.is_some_and(|min| alert.severity_level().is_at_least(min))
+    }
+}
+
+/// The `[magic]` table for `scan-magic`
+///
+/// Whitelists are additions to the defaults rather than replacements, so a
+/// workspace member's file can extend the root's. `no_default_whitelist`
+/// drops the built-in values first, leaving only the listed ones.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MagicSection {
+    pub confidence_threshold: Option<f32>,
+    pub min_severity: Option<f32>,
+    pub scan_config_files: Option<bool>,
+    pub scan_const_components: Option<bool>,
+    pub allow_power_of_two_buffers: Option<bool>,
+    pub max_float_digits: Option<usize>,
+    pub no_default_whitelist: Option<bool>,
+    pub whitelist_paths: Vec<String>,
+    pub whitelist_values: Vec<String>,
+    pub ignore_literal_arg_functions: Vec<String>,
+}
+
+impl ConfigFile {
+    /// Parse a config file from TOML text
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        toml::from_str(contents).map_err(|e| DetectError::InvalidConfig(e.to_string()))
+    }
+
+    /// Read and parse a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_toml_str(&contents).map_err(|e| match e {
+            DetectError::InvalidConfig(msg) => {
+                DetectError::InvalidConfig(format!("{}: {}", path.display(), msg))
+            }
+            other => other,
+        })
+    }
+
+    /// Find the first known config file in `dir`
+    pub fn discover(dir: &Path) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Apply the values present in this file on top of `config`
+    pub fn apply_to(&self, config: &mut DetectConfig) -> Result<()> {
+        self.detect.apply_to(config)?;
+        for section in &self.overrides {
+            config
+                .path_overrides
+                .push(PathOverride::new(&section.path, section.detect.clone())?);
+        }
+        for (glob, fail_on) in &self.gating {
+            config.gating.push(GatingRule::new(glob, fail_on)?);
+        }
+        Ok(())
+    }
+
+    /// Apply the `[magic]` values present in this file on top of `config`
+    pub fn apply_magic_to(&self, config: &mut MagicNumberConfig) {
+        let magic = &self.magic;
+
+        if let Some(threshold) = magic.confidence_threshold {
+            config.confidence_threshold = threshold.clamp(0.0, 1.0);
+        }
+        if let Some(min_severity) = magic.min_severity {
+            config.min_severity = min_severity.clamp(0.0, 1.0);
+        }
+        if let Some(scan_config_files) = magic.scan_config_files {
+            config.scan_config_files = scan_config_files;
+        }
+        if let Some(scan_const_components) = magic.scan_const_components {
+            config.scan_const_components = scan_const_components;
+        }
+        if let Some(allow) = magic.allow_power_of_two_buffers {
+            config.allow_power_of_two_buffers = allow;
+        }
+        if let Some(max_float_digits) = magic.max_float_digits {
+            config.max_float_digits = max_float_digits;
+        }
+        if magic.no_default_whitelist == Some(true) {
+            config.clear_default_whitelist();
+        }
+        for path in &magic.whitelist_paths {
+            if !config.whitelist_paths.contains(path) {
+                config.whitelist_paths.push(path.clone());
+            }
+        }
+        config
+            .whitelist_values
+            .extend(magic.whitelist_values.iter().cloned());
+        config
+            .ignore_literal_arg_functions
+            .extend(magic.ignore_literal_arg_functions.iter().cloned());
+    }
+}
+
+impl DetectSection {
+    /// Apply the values present in this table on top of `config`
+    pub fn apply_to(&self, config: &mut DetectConfig) -> Result<()> {
+        let detect = self;
+
+        if let Some(threshold) = detect.confidence_threshold {
+            config.confidence_threshold = threshold.clamp(0.0, 1.0);
+        }
+        if let Some(max_snippet_length) = detect.max_snippet_length {
+            config.max_snippet_length = max_snippet_length;
+        }
+        if let Some(enable) = detect.enable_regex_fallback {
+            config.enable_regex_fallback = enable;
+        }
+        if let Some(max_params) = detect.max_params {
+            config.max_params = max_params;
+        }
+        if let Some(max_nesting) = detect.max_nesting {
+            config.max_nesting = max_nesting;
+        }
+        if let Some(snippet_context_bytes) = detect.snippet_context_bytes {
+            config.snippet_context_bytes = snippet_context_bytes;
+        }
+        if let Some(max_function_lines) = detect.max_function_lines {
+            config.max_function_lines = max_function_lines;
+        }
+        if let Some(max_scan_millis) = detect.max_scan_millis {
+            config.max_scan_millis = max_scan_millis;
+        }
+        if let Some(duplicate_window) = detect.duplicate_window {
+            config.duplicate_window = duplicate_window;
+        }
+        if let Some(max_public_items) = detect.max_public_items {
+            config.max_public_items = max_public_items;
+        }
+        if let Some(allow_print_macros) = detect.allow_print_macros {
+            config.allow_print_macros = allow_print_macros;
+        }
+        if let Some(min_severity) = detect.min_severity {
+            config.min_severity = min_severity.clamp(0.0, 1.0);
+        }
+        if let Some(max_chain_len) = detect.max_chain_len {
+            config.max_chain_len = max_chain_len;
+        }
+        if let Some(max_impl_methods) = detect.max_impl_methods {
+            config.max_impl_methods = max_impl_methods;
+        }
+        if let Some(max_returns) = detect.max_returns {
+            config.max_returns = max_returns;
+        }
+        if let Some(max_type_params) = detect.max_type_params {
+            config.max_type_params = max_type_params;
+        }
+        if let Some(max_string_literal_len) = detect.max_string_literal_len {
+            config.max_string_literal_len = max_string_literal_len;
+        }
+        if let Some(include_generated) = detect.include_generated {
+            config.include_generated = include_generated;
+        }
+        for (name, confidence) in &detect.confidence_overrides {
+            let bs_type: BullshitType = name.parse()?;
+            config
+                .confidence_overrides
+                .insert(bs_type, confidence.clamp(0.0, 1.0));
+        }
+        for name in &detect.disabled_types {
+            config.disabled_types.insert(name.parse()?);
+        }
+        for name in &detect.enabled_types {
+            config.enabled_types.insert(name.parse()?);
+        }
+        for path in &detect.print_allowed_paths {
+            if !config.print_allowed_paths.contains(path) {
+                config.print_allowed_paths.push(path.clone());
+            }
+        }
+        for path in &detect.glob_import_allowlist {
+            if !config.glob_import_allowlist.contains(path) {
+                config.glob_import_allowlist.push(path.clone());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DetectConfig {
+    /// Build configuration from environment overrides while preserving sane defaults.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        config.apply_env();
+        config
+    }
+
+    /// This config for `path`: print macros allowed if it's in `print_allowed_paths`,
+    /// then every `[[overrides]]` entry matching it applied, least specific
+    /// first so the most specific match wins
+    pub fn effective_config_for(&self, path: &Path) -> DetectConfig {
+        let mut config = self.clone();
+        if self.allows_print_in(path) {
+            config.allow_print_macros = true;
+        }
+
+        let mut matching: Vec<&PathOverride> = self
+            .path_overrides
+            .iter()
+            .filter(|o| o.matches(path))
+            .collect();
+        matching.sort_by_key(|o| o.path.specificity);
+        for path_override in matching {
+            path_override
+                .detect
+                .apply_to(&mut config)
+                .expect("override type names are checked when it's built");
+        }
+        config
+    }
+
+    /// Whether `path` matches `print_allowed_paths`: a pattern ending in `/` names
+    /// a directory anywhere in the path, anything else a file name or path suffix
+    fn allows_print_in(&self, path: &Path) -> bool {
+        let path = format!("/{}", path.to_string_lossy().replace('\\', "/"));
+        self.print_allowed_paths.iter().any(|pattern| {
+            let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+            if pattern.ends_with('/') {
+                path.contains(&format!("/{}", pattern))
+            } else {
+                path.ends_with(&format!("/{}", pattern))
+            }
+        })
+    }
+
+    /// Apply `NIODOO_DETECT_*` environment overrides on top of the current values
+    pub fn apply_env(&mut self) {
+        self.apply_env_from(|name| env::var(name).ok());
+    }
+
+    /// Like [`DetectConfig::apply_env`], reading variables through `get`
+    /// rather than the process environment
+    pub fn apply_env_from(&mut self, get: impl Fn(&str) -> Option<String>) {
+        if let Some(conf) = get("NIODOO_DETECT_CONFIDENCE_THRESHOLD") {
+            if let Ok(value) = conf.parse::<f32>() {
+                self.confidence_threshold = value.clamp(0.0, 1.0);
+            }
+        }
+
+        if let Some(len) = get("NIODOO_DETECT_MAX_SNIPPET_LENGTH") {
+            if let Ok(value) = len.parse::<usize>() {
+                self.max_snippet_length = value;
+            }
+        }
+
+        if let Some(fallback) = get("NIODOO_DETECT_ENABLE_REGEX_FALLBACK") {
+            self.enable_regex_fallback = matches!(
+                fallback.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_PARAMS") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_params = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_NESTING") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_nesting = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_SNIPPET_CONTEXT_BYTES") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.snippet_context_bytes = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_FUNCTION_LINES") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_function_lines = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_SCAN_MILLIS") {
+            if let Ok(value) = value.parse::<u64>() {
+                self.max_scan_millis = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_DUPLICATE_WINDOW") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.duplicate_window = value;
+            }
+        }
+
+        // Comma-separated type names; unknown names are ignored
+        if let Some(names) = get("NIODOO_DETECT_DISABLED_TYPES") {
+            self.disabled_types
+                .extend(names.split(',').filter_map(|name| name.parse().ok()));
+        }
+        if let Some(names) = get("NIODOO_DETECT_ENABLED_TYPES") {
+            self.enabled_types
+                .extend(names.split(',').filter_map(|name| name.parse().ok()));
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_PUBLIC_ITEMS") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_public_items = value;
+            }
+        }
+
+        if let Some(allow) = get("NIODOO_DETECT_ALLOW_PRINT_MACROS") {
+            self.allow_print_macros = matches!(
+                allow.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
+
+        if let Some(paths) = get("NIODOO_DETECT_PRINT_ALLOWED_PATHS") {
+            self.print_allowed_paths = paths
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+
+        if let Some(paths) = get("NIODOO_DETECT_GLOB_IMPORT_ALLOWLIST") {
+            self.glob_import_allowlist = paths
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MIN_SEVERITY") {
+            if let Ok(value) = value.parse::<f32>() {
+                self.min_severity = value.clamp(0.0, 1.0);
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_CHAIN_LEN") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_chain_len = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_IMPL_METHODS") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_impl_methods = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_RETURNS") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_returns = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_TYPE_PARAMS") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_type_params = value;
+            }
+        }
+
+        if let Some(value) = get("NIODOO_DETECT_MAX_STRING_LITERAL_LEN") {
+            if let Ok(value) = value.parse::<usize>() {
+                self.max_string_literal_len = value;
+            }
+        }
+
+        if let Some(include) = get("NIODOO_DETECT_INCLUDE_GENERATED") {
+            self.include_generated = matches!(
+                include.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
+
+        // Comma-separated `TypeName=confidence` pairs; malformed entries are ignored
+        if let Some(overrides) = get("NIODOO_DETECT_CONFIDENCE_OVERRIDES") {
+            for entry in overrides.split(',') {
+                let Some((name, value)) = entry.split_once('=') else {
+                    continue;
+                };
+                if let (Ok(bs_type), Ok(value)) =
+                    (name.parse::<BullshitType>(), value.trim().parse::<f32>())
+                {
+                    self.confidence_overrides
+                        .insert(bs_type, value.clamp(0.0, 1.0));
+                }
+            }
+        }
+    }
+}
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File,
+    Env,
+    Flag,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File => write!(f, "file"),
+            ConfigSource::Env => write!(f, "env"),
+            ConfigSource::Flag => write!(f, "flag"),
+        }
+    }
+}
+
+/// One resolved setting, rendered as a TOML value
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigValue {
+    /// TOML table the key lives in, e.g. `detect.confidence_overrides`
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub source: ConfigSource,
+}
+
+/// Tracks which layer set each value while the config is being resolved
+///
+/// Call [`ConfigTrace::record`] after each layer is applied; a value is
+/// attributed to the last layer that changed it.
+#[derive(Debug, Clone)]
+pub struct ConfigTrace {
+    values: Vec<ConfigValue>,
+}
+
+impl ConfigTrace {
+    /// Start from the given (default) configs
+    pub fn new(detect: &DetectConfig, magic: &MagicNumberConfig) -> Self {
+        let values = snapshot(detect, magic)
+            .into_iter()
+            .map(|(section, key, value)| ConfigValue {
+                section,
+                key,
+                value,
+                source: ConfigSource::Default,
+            })
+            .collect();
+        Self { values }
+    }
+
+    /// Attribute any value that changed since the last layer to `source`
+    pub fn record(
+        &mut self,
+        source: ConfigSource,
+        detect: &DetectConfig,
+        magic: &MagicNumberConfig,
+    ) {
+        for (section, key, value) in snapshot(detect, magic) {
+            match self
+                .values
+                .iter_mut()
+                .find(|v| v.section == section && v.key == key)
+            {
+                Some(existing) if existing.value == value => {}
+                Some(existing) => {
+                    existing.value = value;
+                    existing.source = source;
+                }
+                None => self.values.push(ConfigValue {
+                    section,
+                    key,
+                    value,
+                    source,
+                }),
+            }
+        }
+    }
+
+    /// Every resolved value with its source
+    pub fn values(&self) -> &[ConfigValue] {
+        &self.values
+    }
+
+    /// Render as TOML, with each value's source as a trailing comment
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        let mut sections: Vec<&str> = Vec::new();
+        for value in &self.values {
+            if !sections.contains(&value.section.as_str()) {
+                sections.push(&value.section);
+            }
+        }
+
+        for (i, section) in sections.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", section));
+            for value in self.values.iter().filter(|v| v.section == *section) {
+                out.push_str(&format!(
+                    "{} = {}  # {}\n",
+                    value.key, value.value, value.source
+                ));
+            }
+        }
+
+        out
+    }
+}
+
+/// Flatten both configs into `(section, key, toml value)` triples
+fn snapshot(detect: &DetectConfig, magic: &MagicNumberConfig) -> Vec<(String, String, String)> {
+    let mut values = Vec::new();
+    let mut push = |section: &str, key: &str, value: String| {
+        values.push((section.to_string(), key.to_string(), value));
+    };
+
+    push(
+        "detect",
+        "confidence_threshold",
+        format!("{:?}", detect.confidence_threshold),
+    );
+    push(
+        "detect",
+        "min_severity",
+        format!("{:?}", detect.min_severity),
+    );
+    push(
+        "detect",
+        "max_snippet_length",
+        detect.max_snippet_length.to_string(),
+    );
+    push(
+        "detect",
+        "snippet_context_bytes",
+        detect.snippet_context_bytes.to_string(),
+    );
+    push(
+        "detect",
+        "enable_regex_fallback",
+        detect.enable_regex_fallback.to_string(),
+    );
+    push("detect", "max_params", detect.max_params.to_string());
+    push("detect", "max_nesting", detect.max_nesting.to_string());
+    push(
+        "detect",
+        "max_function_lines",
+        detect.max_function_lines.to_string(),
+    );
+    push("detect", "max_chain_len", detect.max_chain_len.to_string());
+    push(
+        "detect",
+        "max_impl_methods",
+        detect.max_impl_methods.to_string(),
+    );
+    push("detect", "max_returns", detect.max_returns.to_string());
+    push(
+        "detect",
+        "max_type_params",
+        detect.max_type_params.to_string(),
+    );
+    push(
+        "detect",
+        "max_string_literal_len",
+        detect.max_string_literal_len.to_string(),
+    );
+    push(
+        "detect",
+        "include_generated",
+        detect.include_generated.to_string(),
+    );
+    push(
+        "detect",
+        "max_public_items",
+        detect.max_public_items.to_string(),
+    );
+    push(
+        "detect",
+        "duplicate_window",
+        detect.duplicate_window.to_string(),
+    );
+    push(
+        "detect",
+        "max_scan_millis",
+        detect.max_scan_millis.to_string(),
+    );
+    let mut disabled_types: Vec<String> = detect
+        .disabled_types
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    disabled_types.sort();
+    push("detect", "disabled_types", format!("{:?}", disabled_types));
+    let mut enabled_types: Vec<String> =
+        detect.enabled_types.iter().map(|t| t.to_string()).collect();
+    enabled_types.sort();
+    push("detect", "enabled_types", format!("{:?}", enabled_types));
+    push(
+        "detect",
+        "allow_print_macros",
+        detect.allow_print_macros.to_string(),
+    );
+    push(
+        "detect",
+        "print_allowed_paths",
+        format!("{:?}", detect.print_allowed_paths),
+    );
+    push(
+        "detect",
+        "glob_import_allowlist",
+        format!("{:?}", detect.glob_import_allowlist),
+    );
+
+    let mut overrides: Vec<_> = detect.confidence_overrides.iter().collect();
+    overrides.sort_by(|a, b| a.0.cmp(b.0));
+    for (bs_type, confidence) in overrides {
+        push(
+            "detect.confidence_overrides",
+            &bs_type.to_string(),
+            format!("{:?}", confidence),
+        );
+    }
+
+    let mut whitelist_values: Vec<_> = magic.whitelist_values.iter().collect();
+    whitelist_values.sort();
+    push(
+        "magic",
+        "confidence_threshold",
+        format!("{:?}", magic.confidence_threshold),
+    );
+    push("magic", "min_severity", format!("{:?}", magic.min_severity));
+    push(
+        "magic",
+        "whitelist_paths",
+        format!("{:?}", magic.whitelist_paths),
+    );
+    push(
+        "magic",
+        "whitelist_values",
+        format!("{:?}", whitelist_values),
+    );
+    push(
+        "magic",
+        "no_default_whitelist",
+        (!magic.default_whitelist).to_string(),
+    );
+    let mut ignored_functions: Vec<_> = magic.ignore_literal_arg_functions.iter().collect();
+    ignored_functions.sort();
+    push(
+        "magic",
+        "ignore_literal_arg_functions",
+        format!("{:?}", ignored_functions),
+    );
+    push(
+        "magic",
+        "scan_config_files",
+        magic.scan_config_files.to_string(),
+    );
+    push(
+        "magic",
+        "scan_const_components",
+        magic.scan_const_components.to_string(),
+    );
+    push(
+        "magic",
+        "allow_power_of_two_buffers",
+        magic.allow_power_of_two_buffers.to_string(),
+    );
+    push(
+        "magic",
+        "max_float_digits",
+        magic.max_float_digits.to_string(),
+    );
+
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_confidence_overrides_are_clamped() {
+        let file = ConfigFile::from_toml_str(
+            r#"
+            [detect]
+            confidence_threshold = 0.7
+
+            [detect.confidence_overrides]
+            CloneAbuse = 0.95
+            UnwrapAbuse = 1.5
+            "#,
+        )
+        .unwrap();
+
+        let mut config = DetectConfig::default();
+        file.apply_to(&mut config).unwrap();
+
+        assert_eq!(config.confidence_threshold, 0.7);
+        assert_eq!(config.confidence_overrides[&BullshitType::CloneAbuse], 0.95);
+        assert_eq!(config.confidence_overrides[&BullshitType::UnwrapAbuse], 1.0);
+    }
+
+    #[test]
+    fn test_toml_rejects_unknown_type() {
+        let file = ConfigFile::from_toml_str(
+            r#"
+            [detect.confidence_overrides]
+            NotAType = 0.5
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            file.apply_to(&mut DetectConfig::default()),
+            Err(DetectError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_env_confidence_overrides() {
+        let mut config = DetectConfig::default();
+        config.apply_env_from(|name| {
+            (name == "NIODOO_DETECT_CONFIDENCE_OVERRIDES")
+                .then(|| "CloneAbuse=0.95, MagicNumber=-2, bogus".to_string())
+        });
+
+        assert_eq!(config.confidence_overrides[&BullshitType::CloneAbuse], 0.95);
+        assert_eq!(config.confidence_overrides[&BullshitType::MagicNumber], 0.0);
+        assert_eq!(config.confidence_overrides.len(), 2);
+    }
+
+    #[test]
+    fn test_config_trace_attributes_env_override() {
+        let mut detect = DetectConfig::default();
+        let magic = MagicNumberConfig::default();
+        let mut trace = ConfigTrace::new(&detect, &magic);
+
+        detect.apply_env_from(|name| (name == "NIODOO_DETECT_MAX_PARAMS").then(|| "9".to_string()));
+        trace.record(ConfigSource::Env, &detect, &magic);
+
+        let max_params = trace
+            .values()
+            .iter()
+            .find(|v| v.section == "detect" && v.key == "max_params")
+            .unwrap();
+        assert_eq!(max_params.value, "9");
+        assert_eq!(max_params.source, ConfigSource::Env);
+
+        let dump = trace.to_toml();
+        assert!(dump.contains("max_params = 9  # env"));
+        assert!(dump.contains("max_nesting = 4  # default"));
+    }
+
+    #[test]
+    fn test_path_overrides_most_specific_wins() {
+        let file = ConfigFile::from_toml_str(
+            r#"
+            [[overrides]]
+            path = "src/experiments/**"
+            confidence_threshold = 0.9
+            disabled_types = ["CloneAbuse"]
+
+            [[overrides]]
+            path = "src/**"
+            max_params = 3
+            "#,
+        )
+        .unwrap();
+        let mut config = DetectConfig::default();
+        file.apply_to(&mut config).unwrap();
+
+        let relaxed = config.effective_config_for(Path::new("./src/experiments/probe.rs"));
+        assert_eq!(relaxed.confidence_threshold, 0.9);
+        assert_eq!(relaxed.max_params, 3);
+        assert!(relaxed.disabled_types.contains(&BullshitType::CloneAbuse));
+
+        let strict = config.effective_config_for(Path::new("/repo/src/core/engine.rs"));
+        assert_eq!(strict.confidence_threshold, 0.618);
+        assert_eq!(strict.max_params, 3);
+        assert!(strict.disabled_types.is_empty());
+
+        let outside = config.effective_config_for(Path::new("benches/scan.rs"));
+        assert_eq!(outside.max_params, 5);
+    }
+}
@@ -3,7 +3,7 @@
 // Attribution required for all derivative works
 
 /// Mathematical constants - derived, not hardcoded
-pub const GOLDEN_RATIO: f32 = 1.618033988749895;
-pub const GOLDEN_RATIO_INV: f32 = 0.618033988749895;
+pub const GOLDEN_RATIO: f32 = 1.618_034;
+pub const GOLDEN_RATIO_INV: f32 = 0.618_034;
 pub const GOLDEN_RATIO_F64: f64 = 1.618033988749895;
-pub const ONE_MINUS_PHI_INV: f32 = 0.381966011250105;
+pub const ONE_MINUS_PHI_INV: f32 = 0.381_966;
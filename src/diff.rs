@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Compare two saved scan results
+//!
+//! Alerts are matched by [`fingerprint`], as for baselines, so the scans can
+//! come from different machines and code that merely moved isn't reported.
+
+use crate::baseline::fingerprint;
+use crate::error::{DetectError, Result};
+use crate::BullshitAlert;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How the alerts of two scans line up
+#[derive(Debug, Clone, Default)]
+pub struct DiffReport {
+    /// Alerts only in the new scan
+    pub added: Vec<BullshitAlert>,
+    /// Alerts only in the old scan
+    pub removed: Vec<BullshitAlert>,
+    /// Alerts in both scans, as they appear in the new one
+    pub common: Vec<BullshitAlert>,
+}
+
+/// Classify alerts as added, removed or common between `old` and `new`
+///
+/// Identical alerts are matched one for one, so a snippet repeated three
+/// times where it used to appear twice counts as one added alert.
+pub fn diff_results(old: &[BullshitAlert], new: &[BullshitAlert]) -> DiffReport {
+    let mut unmatched: HashMap<String, usize> = HashMap::new();
+    for alert in old {
+        *unmatched.entry(fingerprint(alert)).or_default() += 1;
+    }
+
+    let mut report = DiffReport::default();
+    for alert in new {
+        match unmatched.get_mut(&fingerprint(alert)).filter(|n| **n > 0) {
+            Some(n) => {
+                *n -= 1;
+                report.common.push(alert.clone());
+            }
+            None => report.added.push(alert.clone()),
+        }
+    }
+
+    // Whatever the new scan didn't match was removed; take the earliest ones
+    for alert in old {
+        if let Some(n) = unmatched.get_mut(&fingerprint(alert)).filter(|n| **n > 0) {
+            *n -= 1;
+            report.removed.push(alert.clone());
+        }
+    }
+
+    report
+}
+
+/// Load scan results saved with `--output json` or `--output jsonl`
+pub fn load_results(path: &Path) -> Result<Vec<BullshitAlert>> {
+    let contents = fs::read_to_string(path)?;
+    let invalid =
+        |e: serde_json::Error| DetectError::InvalidConfig(format!("{}: {}", path.display(), e));
+
+    if contents.trim_start().starts_with('[') {
+        return serde_json::from_str(&contents).map_err(invalid);
+    }
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(invalid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BullshitType, SourceLocation};
+
+    fn alert(issue_type: BullshitType, line: usize, snippet: &str) -> BullshitAlert {
+        BullshitAlert {
+            issue_type,
+            confidence: 0.8,
+            location: SourceLocation::new(line, 5),
+            context_snippet: snippet.to_string(),
+            why_bs: "why".to_string(),
+            sug: "fix".to_string(),
+            severity: 0.8,
+            file_path: Some("src/lib.rs".to_string()),
+            breakdown: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_diff_classifies_added_removed_and_common() {
+        let old = vec![
+            alert(BullshitType::UnwrapAbuse, 3, "let a = load().unwrap();"),
+            alert(BullshitType::CloneAbuse, 9, "let b = x.clone();"),
+        ];
+        // The unwrap moved down two lines, the clone was fixed, a new sleep appeared
+        let new = [
+            alert(BullshitType::UnwrapAbuse, 5, "let a = load().unwrap();"),
+            alert(BullshitType::SleepAbuse, 12, "std::thread::sleep(d);"),
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let old_path = dir.path().join("old.json");
+        let new_path = dir.path().join("new.jsonl");
+        fs::write(&old_path, serde_json::to_string_pretty(&old).unwrap()).unwrap();
+        let jsonl: Vec<String> = new
+            .iter()
+            .map(|a| serde_json::to_string(a).unwrap())
+            .collect();
+        fs::write(&new_path, jsonl.join("\n")).unwrap();
+
+        let report = diff_results(
+            &load_results(&old_path).unwrap(),
+            &load_results(&new_path).unwrap(),
+        );
+
+        let types = |alerts: &[BullshitAlert]| -> Vec<BullshitType> {
+            alerts.iter().map(|a| a.issue_type.clone()).collect()
+        };
+        assert_eq!(types(&report.added), vec![BullshitType::SleepAbuse]);
+        assert_eq!(types(&report.removed), vec![BullshitType::CloneAbuse]);
+        assert_eq!(types(&report.common), vec![BullshitType::UnwrapAbuse]);
+        assert_eq!(report.common[0].location.line, 5);
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Library error type
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors returned by the public scanning and config APIs
+#[derive(Debug, Error)]
+pub enum DetectError {
+    /// Reading a source or config file failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A detection pattern failed to compile
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+
+    /// A config file, env var or scan path couldn't be interpreted
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    /// A source file isn't valid UTF-8
+    #[error("{} is not valid UTF-8", path.display())]
+    NonUtf8 { path: PathBuf },
+
+    /// A source file has a generated-code header and `include_generated` is off
+    #[error("{} is generated code", path.display())]
+    Generated { path: PathBuf },
+
+    /// Scanning a file took longer than `max_scan_millis`
+    #[error("Scan exceeded its {millis}ms time budget")]
+    Timeout { millis: u64 },
+}
+
+/// Result alias for library APIs
+pub type Result<T> = std::result::Result<T, DetectError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::compile_regex;
+
+    #[test]
+    fn test_malformed_regex_is_invalid_regex() {
+        let err = compile_regex(r"(unclosed").unwrap_err();
+        assert!(matches!(err, DetectError::InvalidRegex(_)));
+
+        // Well-formed, but over the crate's compiled size limit
+        let err = compile_regex("a{1000}{1000}").unwrap_err();
+        assert!(matches!(
+            err,
+            DetectError::InvalidRegex(regex::Error::CompiledTooBig(_))
+        ));
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! File and directory scanning on top of `scan_code`
+
+use crate::error::{DetectError, Result};
+use crate::{scan_code, sort_alerts, BullshitAlert, DetectConfig};
+use ignore::WalkBuilder;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{self, Path, PathBuf};
+use std::str::FromStr;
+
+/// How reported file paths are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathMode {
+    /// Relative to the scanned path (its directory, when scanning one file)
+    #[default]
+    Relative,
+    /// Canonical absolute paths
+    Absolute,
+}
+
+impl FromStr for PathMode {
+    type Err = DetectError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "relative" => Ok(PathMode::Relative),
+            "absolute" => Ok(PathMode::Absolute),
+            other => Err(DetectError::InvalidConfig(format!(
+                "Unknown path mode {} (expected relative or absolute)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Read a source file, failing with [`DetectError::NonUtf8`] for content that
+/// is neither UTF-8 nor BOM-marked UTF-16
+///
+/// A UTF-8 byte order mark is dropped so offsets start at the first real
+/// character, and UTF-16 (LE or BE, by its BOM) is converted to UTF-8.
+pub fn read_source(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)?;
+    let non_utf8 = || DetectError::NonUtf8 {
+        path: path.to_path_buf(),
+    };
+
+    let (rest, little_endian) = match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => {
+            return String::from_utf8(rest.to_vec()).map_err(|_| non_utf8())
+        }
+        [0xFF, 0xFE, rest @ ..] => (rest, true),
+        [0xFE, 0xFF, rest @ ..] => (rest, false),
+        _ => return String::from_utf8(bytes).map_err(|_| non_utf8()),
+    };
+    if rest.len() % 2 != 0 {
+        return Err(non_utf8());
+    }
+    let units: Vec<u16> = rest
+        .chunks_exact(2)
+        .map(|pair| {
+            let pair = [pair[0], pair[1]];
+            if little_endian {
+                u16::from_le_bytes(pair)
+            } else {
+                u16::from_be_bytes(pair)
+            }
+        })
+        .collect();
+    String::from_utf16(&units).map_err(|_| non_utf8())
+}
+
+/// Lines at the top of a file searched for a generated-code marker
+const GENERATED_HEADER_LINES: usize = 5;
+
+/// Comment markers tools put at the top of the files they write
+const GENERATED_MARKERS: [&str; 3] = [
+    "@generated",
+    "code generated by",
+    "automatically generated by",
+];
+
+/// Whether `code` opens with a generated-code comment, like `// @generated`
+/// or Go-style `// Code generated by protoc. DO NOT EDIT.`
+pub fn is_generated(code: &str) -> bool {
+    code.lines().take(GENERATED_HEADER_LINES).any(|line| {
+        let line = line.trim_start();
+        let is_comment = line.starts_with("//") || line.starts_with("/*") || line.starts_with('*');
+        let line = line.to_ascii_lowercase();
+        is_comment && GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+    })
+}
+
+/// Read a source file to scan, failing with [`DetectError::Generated`] for a
+/// generated one unless `include_generated`
+pub fn read_scannable(path: &Path, include_generated: bool) -> Result<String> {
+    let code = read_source(path)?;
+    if !include_generated && is_generated(&code) {
+        return Err(DetectError::Generated {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(code)
+}
+
+/// Scan a single file, tagging each alert with its path
+///
+/// Non-UTF-8 files fail with [`DetectError::NonUtf8`], and generated ones
+/// with [`DetectError::Generated`] unless `config.include_generated`.
+pub fn scan_file(path: &Path, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let code = read_scannable(path, config.include_generated)?;
+    let mut alerts = scan_code(&code, &config.effective_config_for(path))?;
+
+    for alert in &mut alerts {
+        alert.file_path = Some(path.display().to_string());
+    }
+
+    Ok(alerts)
+}
+
+/// Scan every Rust file under `path` (or `path` itself if it's a file)
+///
+/// Non-UTF-8 and generated files are skipped; any other read error aborts
+/// the scan.
+pub fn scan_directory(path: &Path, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    for file_path in find_rust_files(path)? {
+        match scan_file(&file_path, config) {
+            Ok(file_alerts) => alerts.extend(file_alerts),
+            Err(DetectError::NonUtf8 { .. } | DetectError::Generated { .. }) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    sort_alerts(&mut alerts);
+
+    Ok(alerts)
+}
+
+/// Collect Rust source files, skipping `target/` and `tests/` directories
+/// and anything `.gitignore`d
+pub fn find_rust_files(path: &Path) -> Result<Vec<PathBuf>> {
+    find_rust_files_with(path, true)
+}
+
+/// Collect Rust source files, skipping `target/` and `tests/` directories
+///
+/// With `respect_gitignore`, files excluded by `.gitignore` (in the scanned
+/// tree or its parents), `.git/info/exclude` or the global git excludes are
+/// skipped too, whether or not the tree is a git checkout.
+pub fn find_rust_files_with(path: &Path, respect_gitignore: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    if path.is_file() {
+        files.push(path.to_path_buf());
+    } else if path.is_dir() {
+        let walker = WalkBuilder::new(path)
+            .hidden(false)
+            .ignore(false)
+            .parents(respect_gitignore)
+            .git_ignore(respect_gitignore)
+            .git_exclude(respect_gitignore)
+            .git_global(respect_gitignore)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker.flatten() {
+            let file_path = entry.into_path();
+            if file_path.extension().is_none_or(|ext| ext != "rs") || !file_path.is_file() {
+                continue;
+            }
+            // Skip test files and target directory
+            let path_str = file_path.to_string_lossy();
+            if !path_str.contains("/target/") && !path_str.contains("/tests/") {
+                files.push(file_path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Rust files under `path` that [`find_rust_files_with`] only leaves out
+/// because git ignores them
+pub fn find_gitignored_rust_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let kept: HashSet<PathBuf> = find_rust_files_with(path, true)?.into_iter().collect();
+    Ok(find_rust_files_with(path, false)?
+        .into_iter()
+        .filter(|file| !kept.contains(file))
+        .collect())
+}
+
+/// Write `path` consistently for output, whatever form it was found in
+///
+/// Paths that can't be made relative to `root` fall back to absolute.
+pub fn normalize_path(path: &Path, root: &Path, mode: PathMode) -> String {
+    let absolute = absolute_path(path);
+
+    let relative = match mode {
+        PathMode::Absolute => None,
+        PathMode::Relative => {
+            let root = absolute_path(root);
+            let base = if root.is_file() {
+                root.parent().map(Path::to_path_buf)
+            } else {
+                Some(root)
+            };
+            base.and_then(|base| absolute.strip_prefix(base).ok().map(Path::to_path_buf))
+        }
+    };
+
+    relative.unwrap_or(absolute).display().to_string()
+}
+
+/// Canonical form of `path`, or just made absolute if it doesn't exist
+fn absolute_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .or_else(|_| path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BullshitType, SourceLocation};
+
+    #[test]
+    fn test_scan_file_sets_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "fn f() { let v = load().unwrap(); }\n").unwrap();
+
+        let alerts = scan_file(&path, &DetectConfig::default()).unwrap();
+
+        let unwrap = alerts
+            .iter()
+            .find(|a| a.issue_type == BullshitType::UnwrapAbuse)
+            .unwrap();
+        assert_eq!(
+            unwrap.file_path.as_deref(),
+            Some(&*path.display().to_string())
+        );
+    }
+
+    #[test]
+    fn test_scan_file_non_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("binary.rs");
+        fs::write(&path, [0x66, 0x6e, 0xff, 0xfe, 0x00]).unwrap();
+
+        let err = scan_file(&path, &DetectConfig::default()).unwrap_err();
+
+        assert!(matches!(err, DetectError::NonUtf8 { path: p } if p == path));
+    }
+
+    #[test]
+    fn test_scan_file_strips_bom_and_decodes_utf16() {
+        let dir = tempfile::tempdir().unwrap();
+        let code = "fn f() {\n    let v = load().unwrap();\n}\n";
+        let unwrap_at = |path: &Path| {
+            scan_file(path, &DetectConfig::default())
+                .unwrap()
+                .into_iter()
+                .find(|a| a.issue_type == BullshitType::UnwrapAbuse)
+                .map(|a| a.location)
+                .unwrap()
+        };
+        let expected = unwrap_at(&{
+            let path = dir.path().join("plain.rs");
+            fs::write(&path, code).unwrap();
+            path
+        });
+
+        let bom = dir.path().join("bom.rs");
+        fs::write(&bom, [&[0xEF, 0xBB, 0xBF][..], code.as_bytes()].concat()).unwrap();
+        let le = dir.path().join("utf16le.rs");
+        let le_bytes: Vec<u8> = code.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        fs::write(&le, [&[0xFF, 0xFE][..], &le_bytes].concat()).unwrap();
+        let be = dir.path().join("utf16be.rs");
+        let be_bytes: Vec<u8> = code.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        fs::write(&be, [&[0xFE, 0xFF][..], &be_bytes].concat()).unwrap();
+
+        assert_eq!(expected, SourceLocation::new(2, 19));
+        assert_eq!(read_source(&bom).unwrap(), code);
+        assert_eq!(unwrap_at(&bom), expected);
+        assert_eq!(unwrap_at(&le), expected);
+        assert_eq!(unwrap_at(&be), expected);
+    }
+
+    #[test]
+    fn test_scan_directory_skips_target_and_non_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::write(dir.path().join("src/a.rs"), "let a = x.clone();\n").unwrap();
+        fs::write(dir.path().join("src/nested/b.rs"), "let b = y.clone();\n").unwrap();
+        fs::write(dir.path().join("src/bad.rs"), [0xff, 0xff]).unwrap();
+        fs::write(dir.path().join("target/debug/c.rs"), "let c = z.clone();\n").unwrap();
+
+        let alerts = scan_directory(dir.path(), &DetectConfig::default()).unwrap();
+
+        let mut files: Vec<_> = alerts.iter().filter_map(|a| a.file_path.clone()).collect();
+        files.sort();
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("src/a.rs"));
+        assert!(files[1].ends_with("src/nested/b.rs"));
+    }
+
+    #[test]
+    fn test_normalize_path_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        let file = root.join("src/nested/../nested/lib.rs");
+        fs::write(&file, "").unwrap();
+        let canonical_root = fs::canonicalize(&root).unwrap();
+
+        assert_eq!(
+            normalize_path(&file, &root, PathMode::Relative),
+            Path::new("src/nested/lib.rs").display().to_string()
+        );
+        assert_eq!(
+            normalize_path(&file, &root.join("src/nested/lib.rs"), PathMode::Relative),
+            "lib.rs"
+        );
+        assert_eq!(
+            normalize_path(&file, &root, PathMode::Absolute),
+            canonical_root
+                .join("src/nested/lib.rs")
+                .display()
+                .to_string()
+        );
+        assert_eq!("Absolute".parse::<PathMode>().unwrap(), PathMode::Absolute);
+    }
+}
@@ -22,17 +22,45 @@
 //! let alerts = scan_code(code, &config).unwrap();
 //! 
 //! for alert in alerts {
-//!     println!("Found {} at line {}", alert.issue_type, alert.location.0);
+//!     println!("Found {} at line {}", alert.issue_type, alert.location.line);
 //! }
 //! ```
 
+use crate::limits::compile_regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::str::FromStr;
 
+pub mod baseline;
+#[cfg(feature = "cli")]
+pub mod blame;
+pub mod config;
 pub mod constants;
+pub mod diff;
+pub mod error;
+#[cfg(feature = "cli")]
+pub mod files;
+mod limits;
+pub mod location;
+pub mod lsp;
+pub mod magic_numbers;
+pub mod output;
+pub mod progress;
+pub mod report;
+pub mod scoring;
+pub mod smells;
+mod syntax;
+#[cfg(feature = "cli")]
+pub mod workspace;
+
+pub use error::DetectError;
+#[cfg(feature = "cli")]
+pub use files::{scan_directory, scan_file};
+pub use location::byte_offset_to_line_col;
 
 /// Bullshit alert types
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum BullshitType {
     FakeComplexity,
     CargoCult,
@@ -46,6 +74,33 @@ pub enum BullshitType {
     MutexAbuse,
     MagicNumber,
     HardcodedThreshold,
+    ShadowAbuse,
+    LongParameterList,
+    DeepNesting,
+    NeedlessAllocation,
+    SwallowedError,
+    HardcodedPath,
+    LongFunction,
+    FloatEquality,
+    DuplicateCode,
+    HardcodedSecret,
+    UndocumentedUnsafe,
+    InconsistentLiteralStyle,
+    DebugLeftover,
+    StringlyTypedError,
+    LongMethodChain,
+    BorrowedOwnedType,
+    LossyCast,
+    MutableStatic,
+    BlockingInAsync,
+    MixedErrorHandling,
+    PollingLoop,
+    EarlyReturn,
+    NestedWrapper,
+    LenZeroComparison,
+    EagerFallback,
+    OutParameter,
+    LongStringLiteral,
 }
 
 impl fmt::Display for BullshitType {
@@ -63,20 +118,403 @@ impl fmt::Display for BullshitType {
             BullshitType::MutexAbuse => write!(f, "MutexAbuse"),
             BullshitType::MagicNumber => write!(f, "MagicNumber"),
             BullshitType::HardcodedThreshold => write!(f, "HardcodedThreshold"),
+            BullshitType::ShadowAbuse => write!(f, "ShadowAbuse"),
+            BullshitType::LongParameterList => write!(f, "LongParameterList"),
+            BullshitType::DeepNesting => write!(f, "DeepNesting"),
+            BullshitType::NeedlessAllocation => write!(f, "NeedlessAllocation"),
+            BullshitType::SwallowedError => write!(f, "SwallowedError"),
+            BullshitType::HardcodedPath => write!(f, "HardcodedPath"),
+            BullshitType::LongFunction => write!(f, "LongFunction"),
+            BullshitType::FloatEquality => write!(f, "FloatEquality"),
+            BullshitType::DuplicateCode => write!(f, "DuplicateCode"),
+            BullshitType::HardcodedSecret => write!(f, "HardcodedSecret"),
+            BullshitType::UndocumentedUnsafe => write!(f, "UndocumentedUnsafe"),
+            BullshitType::InconsistentLiteralStyle => write!(f, "InconsistentLiteralStyle"),
+            BullshitType::DebugLeftover => write!(f, "DebugLeftover"),
+            BullshitType::StringlyTypedError => write!(f, "StringlyTypedError"),
+            BullshitType::LongMethodChain => write!(f, "LongMethodChain"),
+            BullshitType::BorrowedOwnedType => write!(f, "BorrowedOwnedType"),
+            BullshitType::LossyCast => write!(f, "LossyCast"),
+            BullshitType::MutableStatic => write!(f, "MutableStatic"),
+            BullshitType::BlockingInAsync => write!(f, "BlockingInAsync"),
+            BullshitType::MixedErrorHandling => write!(f, "MixedErrorHandling"),
+            BullshitType::PollingLoop => write!(f, "PollingLoop"),
+            BullshitType::EarlyReturn => write!(f, "EarlyReturn"),
+            BullshitType::NestedWrapper => write!(f, "NestedWrapper"),
+            BullshitType::LenZeroComparison => write!(f, "LenZeroComparison"),
+            BullshitType::EagerFallback => write!(f, "EagerFallback"),
+            BullshitType::OutParameter => write!(f, "OutParameter"),
+            BullshitType::LongStringLiteral => write!(f, "LongStringLiteral"),
+        }
+    }
+}
+
+impl FromStr for BullshitType {
+    type Err = DetectError;
+
+    /// Parse a type from its `Display` name (case-insensitive, and `-`/`_`
+    /// are ignored so `early-return` works too)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .trim()
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match name.as_str() {
+            "fakecomplexity" => Ok(BullshitType::FakeComplexity),
+            "cargocult" => Ok(BullshitType::CargoCult),
+            "overengineering" => Ok(BullshitType::OverEngineering),
+            "arcabuse" => Ok(BullshitType::ArcAbuse),
+            "rwlockabuse" => Ok(BullshitType::RwLockAbuse),
+            "sleepabuse" => Ok(BullshitType::SleepAbuse),
+            "unwrapabuse" => Ok(BullshitType::UnwrapAbuse),
+            "dyntraitabuse" => Ok(BullshitType::DynTraitAbuse),
+            "cloneabuse" => Ok(BullshitType::CloneAbuse),
+            "mutexabuse" => Ok(BullshitType::MutexAbuse),
+            "magicnumber" => Ok(BullshitType::MagicNumber),
+            "hardcodedthreshold" => Ok(BullshitType::HardcodedThreshold),
+            "shadowabuse" => Ok(BullshitType::ShadowAbuse),
+            "longparameterlist" => Ok(BullshitType::LongParameterList),
+            "deepnesting" => Ok(BullshitType::DeepNesting),
+            "needlessallocation" => Ok(BullshitType::NeedlessAllocation),
+            "swallowederror" => Ok(BullshitType::SwallowedError),
+            "hardcodedpath" => Ok(BullshitType::HardcodedPath),
+            "longfunction" => Ok(BullshitType::LongFunction),
+            "floatequality" => Ok(BullshitType::FloatEquality),
+            "duplicatecode" => Ok(BullshitType::DuplicateCode),
+            "hardcodedsecret" => Ok(BullshitType::HardcodedSecret),
+            "undocumentedunsafe" => Ok(BullshitType::UndocumentedUnsafe),
+            "inconsistentliteralstyle" => Ok(BullshitType::InconsistentLiteralStyle),
+            "debugleftover" => Ok(BullshitType::DebugLeftover),
+            "stringlytypederror" => Ok(BullshitType::StringlyTypedError),
+            "longmethodchain" => Ok(BullshitType::LongMethodChain),
+            "borrowedownedtype" => Ok(BullshitType::BorrowedOwnedType),
+            "lossycast" => Ok(BullshitType::LossyCast),
+            "mutablestatic" => Ok(BullshitType::MutableStatic),
+            "blockinginasync" => Ok(BullshitType::BlockingInAsync),
+            "mixederrorhandling" => Ok(BullshitType::MixedErrorHandling),
+            "pollingloop" => Ok(BullshitType::PollingLoop),
+            "earlyreturn" => Ok(BullshitType::EarlyReturn),
+            "nestedwrapper" => Ok(BullshitType::NestedWrapper),
+            "lenzerocomparison" => Ok(BullshitType::LenZeroComparison),
+            "eagerfallback" => Ok(BullshitType::EagerFallback),
+            "outparameter" => Ok(BullshitType::OutParameter),
+            "longstringliteral" => Ok(BullshitType::LongStringLiteral),
+            _ => Err(DetectError::InvalidConfig(format!(
+                "Unknown bullshit type: {}",
+                s
+            ))),
         }
     }
 }
 
+impl BullshitType {
+    /// Cross-cutting category, for filtering across related types
+    pub fn category(&self) -> Category {
+        match self {
+            BullshitType::HardcodedSecret
+            | BullshitType::UndocumentedUnsafe
+            | BullshitType::MutableStatic => Category::Security,
+            BullshitType::UnwrapAbuse
+            | BullshitType::SwallowedError
+            | BullshitType::FloatEquality
+            | BullshitType::MutexAbuse
+            | BullshitType::RwLockAbuse
+            | BullshitType::LossyCast => Category::Correctness,
+            BullshitType::CloneAbuse
+            | BullshitType::NeedlessAllocation
+            | BullshitType::SleepAbuse
+            | BullshitType::ArcAbuse
+            | BullshitType::BlockingInAsync
+            | BullshitType::PollingLoop
+            | BullshitType::EagerFallback => Category::Perf,
+            BullshitType::ShadowAbuse
+            | BullshitType::InconsistentLiteralStyle
+            | BullshitType::DebugLeftover
+            | BullshitType::CargoCult
+            | BullshitType::BorrowedOwnedType
+            | BullshitType::EarlyReturn
+            | BullshitType::LenZeroComparison
+            | BullshitType::OutParameter => Category::Style,
+            BullshitType::FakeComplexity
+            | BullshitType::OverEngineering
+            | BullshitType::DynTraitAbuse
+            | BullshitType::MagicNumber
+            | BullshitType::HardcodedThreshold
+            | BullshitType::LongParameterList
+            | BullshitType::DeepNesting
+            | BullshitType::HardcodedPath
+            | BullshitType::LongFunction
+            | BullshitType::DuplicateCode
+            | BullshitType::StringlyTypedError
+            | BullshitType::LongMethodChain
+            | BullshitType::MixedErrorHandling
+            | BullshitType::NestedWrapper
+            | BullshitType::LongStringLiteral => Category::Maintainability,
+        }
+    }
+}
+
+/// Broad grouping of [`BullshitType`]s, see [`BullshitType::category`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Security,
+    Correctness,
+    Perf,
+    Maintainability,
+    Style,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Security => write!(f, "security"),
+            Category::Correctness => write!(f, "correctness"),
+            Category::Perf => write!(f, "perf"),
+            Category::Maintainability => write!(f, "maintainability"),
+            Category::Style => write!(f, "style"),
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = DetectError;
+
+    /// Parse a category from its `Display` name (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "security" => Ok(Category::Security),
+            "correctness" => Ok(Category::Correctness),
+            "perf" => Ok(Category::Perf),
+            "maintainability" => Ok(Category::Maintainability),
+            "style" => Ok(Category::Style),
+            _ => Err(DetectError::InvalidConfig(format!(
+                "Unknown category: {} (expected security, correctness, perf, maintainability or style)",
+                s
+            ))),
+        }
+    }
+}
+
+/// 1-based position of an alert in the scanned source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 /// Bullshit alert with confidence and suggestions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BullshitAlert {
     pub issue_type: BullshitType,
     pub confidence: f32,
-    pub location: (usize, usize), // (line, column)
+    pub location: SourceLocation,
     pub context_snippet: String,
     pub why_bs: String,
     pub sug: String,
     pub severity: f32,
+    /// Source file the alert came from, when scanning files rather than raw code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// How `confidence` was reached, for `--explain` (not serialized)
+    #[serde(skip)]
+    pub breakdown: scoring::ScoreBreakdown,
+}
+
+/// Severity buckets used when reporting alerts
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SeverityLevel {
+    Critical,
+    High,
+    Medium,
+}
+
+impl SeverityLevel {
+    /// Bucket a raw severity score
+    pub fn from_score(severity: f32) -> Self {
+        if severity >= 0.9 {
+            SeverityLevel::Critical
+        } else if severity >= 0.75 {
+            SeverityLevel::High
+        } else {
+            SeverityLevel::Medium
+        }
+    }
+
+    /// Whether this is `min` or worse
+    pub fn is_at_least(self, min: SeverityLevel) -> bool {
+        self.rank() >= min.rank()
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            SeverityLevel::Critical => 2,
+            SeverityLevel::High => 1,
+            SeverityLevel::Medium => 0,
+        }
+    }
+}
+
+impl FromStr for SeverityLevel {
+    type Err = DetectError;
+
+    /// Parse a level from its `Display` name (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "critical" => Ok(SeverityLevel::Critical),
+            "high" => Ok(SeverityLevel::High),
+            "medium" => Ok(SeverityLevel::Medium),
+            _ => Err(DetectError::InvalidConfig(format!(
+                "Unknown severity: {} (expected critical, high or medium)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SeverityLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeverityLevel::Critical => write!(f, "CRITICAL"),
+            SeverityLevel::High => write!(f, "HIGH"),
+            SeverityLevel::Medium => write!(f, "MEDIUM"),
+        }
+    }
+}
+
+impl BullshitAlert {
+    /// Start building an alert of `issue_type`
+    pub fn builder(issue_type: BullshitType) -> AlertBuilder {
+        AlertBuilder::new(issue_type)
+    }
+
+    /// Severity bucket for this alert
+    pub fn severity_level(&self) -> SeverityLevel {
+        SeverityLevel::from_score(self.severity)
+    }
+}
+
+/// Fluent constructor for [`BullshitAlert`]
+///
+/// Unset fields default to: confidence 0.7, location 1:1, an empty snippet
+/// and explanation, the type's stock suggestion, no file, a severity
+/// derived from the confidence by [`scoring::default_severity`], and a score
+/// breakdown that is just the confidence as a base.
+#[derive(Debug, Clone)]
+pub struct AlertBuilder {
+    issue_type: BullshitType,
+    confidence: f32,
+    location: SourceLocation,
+    snippet: String,
+    why: String,
+    suggestion: Option<String>,
+    severity: Option<f32>,
+    file_path: Option<String>,
+    breakdown: Option<scoring::ScoreBreakdown>,
+}
+
+impl AlertBuilder {
+    pub fn new(issue_type: BullshitType) -> Self {
+        Self {
+            issue_type,
+            confidence: 0.7,
+            location: SourceLocation::new(1, 1),
+            snippet: String::new(),
+            why: String::new(),
+            suggestion: None,
+            severity: None,
+            file_path: None,
+            breakdown: None,
+        }
+    }
+
+    pub fn issue_type(mut self, issue_type: BullshitType) -> Self {
+        self.issue_type = issue_type;
+        self
+    }
+
+    pub fn confidence(mut self, confidence: f32) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Set the location from a 1-based line and column
+    pub fn at(self, line: usize, column: usize) -> Self {
+        self.location(SourceLocation::new(line, column))
+    }
+
+    pub fn location(mut self, location: SourceLocation) -> Self {
+        self.location = location;
+        self
+    }
+
+    pub fn snippet(mut self, snippet: impl Into<String>) -> Self {
+        self.snippet = snippet.into();
+        self
+    }
+
+    pub fn why(mut self, why: impl Into<String>) -> Self {
+        self.why = why.into();
+        self
+    }
+
+    pub fn suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Override the severity derived from the confidence
+    pub fn severity(mut self, severity: f32) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    pub fn file_path(mut self, file_path: impl Into<String>) -> Self {
+        self.file_path = Some(file_path.into());
+        self
+    }
+
+    /// How the confidence was scored, from [`scoring::score_explained`]
+    pub fn breakdown(mut self, breakdown: scoring::ScoreBreakdown) -> Self {
+        self.breakdown = Some(breakdown);
+        self
+    }
+
+    pub fn build(self) -> BullshitAlert {
+        let severity = self
+            .severity
+            .unwrap_or_else(|| scoring::default_severity(&self.issue_type, self.confidence));
+        let sug = self
+            .suggestion
+            .unwrap_or_else(|| generate_suggestion(&self.issue_type));
+        BullshitAlert {
+            issue_type: self.issue_type,
+            confidence: self.confidence,
+            location: self.location,
+            context_snippet: self.snippet,
+            why_bs: self.why,
+            sug,
+            severity,
+            file_path: self.file_path,
+            breakdown: self
+                .breakdown
+                .unwrap_or_else(|| scoring::ScoreBreakdown::base(self.confidence)),
+        }
+    }
 }
 
 /// Detection configuration
@@ -85,6 +523,53 @@ pub struct DetectConfig {
     pub confidence_threshold: f32,
     pub max_snippet_length: usize,
     pub enable_regex_fallback: bool,
+    /// Per-type confidences that replace the built-in defaults (clamped to 0.0-1.0)
+    pub confidence_overrides: HashMap<BullshitType, f32>,
+    /// Parameters a function may take before it's flagged (excluding `self`)
+    pub max_params: usize,
+    /// Block nesting depth inside a function before it's flagged
+    pub max_nesting: usize,
+    /// Bytes of context around a match before the snippet is widened to full lines
+    pub snippet_context_bytes: usize,
+    /// Body lines a function may span before it's flagged
+    pub max_function_lines: usize,
+    /// Per-file time budget in milliseconds before a scan aborts with a timeout (0 disables it)
+    pub max_scan_millis: u64,
+    /// Non-trivial lines in a block before a repeat of it is flagged as duplicate code
+    pub duplicate_window: usize,
+    /// Types that are never reported
+    pub disabled_types: HashSet<BullshitType>,
+    /// Types too noisy to report by default that should be, e.g. `MixedErrorHandling`
+    pub enabled_types: HashSet<BullshitType>,
+    /// `[[overrides]]` from the config file, applied per file by
+    /// [`DetectConfig::effective_config_for`]
+    pub path_overrides: Vec<config::PathOverride>,
+    /// `[gating]` rules from the config file, for a [`config::Gate`]
+    pub gating: Vec<config::GatingRule>,
+    /// Public items a file may declare before it's flagged as a god module
+    pub max_public_items: usize,
+    /// Skip `println!`/`print!`/`eprintln!`/`eprint!` (`dbg!` is still flagged); set per file for `print_allowed_paths`
+    pub allow_print_macros: bool,
+    /// Paths where print macros are expected output (`bin/` matches a directory,
+    /// `main.rs` a file); see [`DetectConfig::effective_config_for`]
+    pub print_allowed_paths: Vec<String>,
+    /// Glob imports that aren't flagged: `a::b` allows `use a::b::*` and below,
+    /// a bare segment like `prelude` allows any path containing it
+    pub glob_import_allowlist: Vec<String>,
+    /// Alerts with a lower severity are dropped (independent of `confidence_threshold`)
+    pub min_severity: f32,
+    /// Method calls an expression chain may make before it's flagged
+    pub max_chain_len: usize,
+    /// Methods an impl block may define before it's flagged
+    pub max_impl_methods: usize,
+    /// `return` statements a function may contain before it's flagged (opt-in `EarlyReturn`)
+    pub max_returns: usize,
+    /// Type parameters a struct, enum, trait or impl may declare before it's flagged as over-generic
+    pub max_type_params: usize,
+    /// Characters in a string literal before it's flagged
+    pub max_string_literal_len: usize,
+    /// Scan files with a generated-code header (`// @generated`) instead of skipping them
+    pub include_generated: bool,
 }
 
 impl Default for DetectConfig {
@@ -93,91 +578,427 @@ impl Default for DetectConfig {
             confidence_threshold: 0.618, // Golden ratio inverse
             max_snippet_length: 500,
             enable_regex_fallback: true,
+            confidence_overrides: HashMap::new(),
+            max_params: 5,
+            max_nesting: 4,
+            snippet_context_bytes: 50,
+            max_function_lines: 60,
+            max_scan_millis: 10_000,
+            duplicate_window: 6,
+            disabled_types: HashSet::new(),
+            enabled_types: HashSet::new(),
+            path_overrides: Vec::new(),
+            gating: Vec::new(),
+            max_public_items: 30,
+            allow_print_macros: false,
+            print_allowed_paths: vec!["main.rs".to_string(), "bin/".to_string()],
+            glob_import_allowlist: vec!["prelude".to_string()],
+            min_severity: 0.0,
+            max_chain_len: 6,
+            max_impl_methods: 25,
+            max_returns: 3,
+            max_type_params: 4,
+            max_string_literal_len: 500,
+            include_generated: false,
         }
     }
 }
 
+impl DetectConfig {
+    /// Confidence for a type: the configured override if any, else `default`
+    pub fn confidence_for(&self, bs_type: &BullshitType, default: f32) -> f32 {
+        self.confidence_overrides
+            .get(bs_type)
+            .map(|c| c.clamp(0.0, 1.0))
+            .unwrap_or(default)
+    }
+}
+
+/// Scan `code` with a JSON config shaped like the `[detect]` config table
+/// (`""` or `"{}"` for the defaults), returning the alerts as a JSON array
+///
+/// Never panics or touches the filesystem or environment, so it can be called
+/// straight from a wasm build; failures come back as `{"error": "..."}`.
+pub fn scan_code_json(code: &str, config_json: &str) -> String {
+    let result = parse_json_config(config_json)
+        .and_then(|config| scan_code(code, &config))
+        .map(|alerts| serde_json::to_value(alerts).unwrap_or_default());
+
+    match result {
+        Ok(alerts) => alerts.to_string(),
+        Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+    }
+}
+
+fn parse_json_config(config_json: &str) -> error::Result<DetectConfig> {
+    let config_json = if config_json.trim().is_empty() {
+        "{}"
+    } else {
+        config_json
+    };
+    let detect =
+        serde_json::from_str(config_json).map_err(|e| DetectError::InvalidConfig(e.to_string()))?;
+
+    let mut config = DetectConfig::default();
+    config::ConfigFile {
+        detect,
+        ..Default::default()
+    }
+    .apply_to(&mut config)?;
+    Ok(config)
+}
+
+/// Signature shared by the structural detectors in [`smells`]
+type SmellScanner = fn(&str, &DetectConfig) -> error::Result<Vec<BullshitAlert>>;
+
+/// One of the detectors [`scan_code`] runs, for [`scan_code_with_detectors`]
+///
+/// The regex pattern rules are named after the type they report; the rest
+/// after the structural check they make. Magic number scanning proper is
+/// [`magic_numbers::scan_for_magic_numbers`], not a detector here.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DetectorId {
+    OverEngineering,
+    SleepAbuse,
+    UnwrapAbuse,
+    CloneAbuse,
+    MagicNumber,
+    HardcodedThreshold,
+    Shadowing,
+    LongParameterList,
+    DeepNesting,
+    NeedlessAllocation,
+    SwallowedError,
+    HardcodedPath,
+    LongFunction,
+    HardcodedSecret,
+    UndocumentedUnsafe,
+    LockUnwrap,
+    DebugLeftover,
+    GlobImport,
+    StringlyTypedError,
+    LongMethodChain,
+    BorrowedOwnedType,
+    LossyCast,
+    MutableStatic,
+    BlockingInAsync,
+    PollingLoop,
+    CopyClone,
+    NestedWrapper,
+    LenZeroComparison,
+    EagerFallback,
+    OutParameter,
+    GenericParams,
+    LongStringLiteral,
+    DuplicateCode,
+    LiteralStyle,
+    PublicItems,
+    ImplSize,
+    MixedErrorHandling,
+    EarlyReturn,
+}
+
+/// Structural smells that need scope tracking rather than a single match
+const SCOPED_SCANNERS: [(DetectorId, SmellScanner); 26] = [
+    (DetectorId::Shadowing, smells::scan_shadowing),
+    (
+        DetectorId::LongParameterList,
+        smells::scan_long_parameter_lists,
+    ),
+    (DetectorId::DeepNesting, smells::scan_deep_nesting),
+    (
+        DetectorId::NeedlessAllocation,
+        smells::scan_needless_allocations,
+    ),
+    (DetectorId::SwallowedError, smells::scan_swallowed_errors),
+    (DetectorId::HardcodedPath, smells::scan_hardcoded_paths),
+    (DetectorId::LongFunction, smells::scan_long_functions),
+    (DetectorId::HardcodedSecret, smells::scan_hardcoded_secrets),
+    (
+        DetectorId::UndocumentedUnsafe,
+        smells::scan_undocumented_unsafe,
+    ),
+    (DetectorId::LockUnwrap, smells::scan_lock_unwraps),
+    (DetectorId::DebugLeftover, smells::scan_debug_leftovers),
+    (DetectorId::GlobImport, smells::scan_glob_imports),
+    (DetectorId::StringlyTypedError, smells::scan_stringly_errors),
+    (DetectorId::LongMethodChain, smells::scan_method_chains),
+    (
+        DetectorId::BorrowedOwnedType,
+        smells::scan_borrowed_owned_params,
+    ),
+    (DetectorId::LossyCast, smells::scan_lossy_casts),
+    (DetectorId::MutableStatic, smells::scan_mutable_statics),
+    (DetectorId::BlockingInAsync, smells::scan_blocking_in_async),
+    (DetectorId::PollingLoop, smells::scan_polling_loops),
+    (DetectorId::CopyClone, smells::scan_copy_clones),
+    (DetectorId::NestedWrapper, smells::scan_nested_wrappers),
+    (
+        DetectorId::LenZeroComparison,
+        smells::scan_len_zero_comparisons,
+    ),
+    (DetectorId::EagerFallback, smells::scan_eager_fallbacks),
+    (DetectorId::OutParameter, smells::scan_out_params),
+    (DetectorId::GenericParams, smells::scan_generic_params),
+    (
+        DetectorId::LongStringLiteral,
+        smells::scan_long_string_literals,
+    ),
+];
+
+/// Structural smells judged against the whole file, which a fragment can't stand in for
+const FILE_SCANNERS: [(DetectorId, SmellScanner); 4] = [
+    (DetectorId::DuplicateCode, smells::scan_duplicate_code),
+    (DetectorId::LiteralStyle, smells::scan_literal_style),
+    (DetectorId::PublicItems, smells::scan_public_items),
+    (DetectorId::ImplSize, smells::scan_impl_sizes),
+];
+
+/// Structural smells that only run when their type is opted into, or their
+/// detector is asked for by name
+const OPT_IN_SCANNERS: [(DetectorId, BullshitType, SmellScanner); 2] = [
+    (
+        DetectorId::MixedErrorHandling,
+        BullshitType::MixedErrorHandling,
+        smells::scan_mixed_error_handling,
+    ),
+    (
+        DetectorId::EarlyReturn,
+        BullshitType::EarlyReturn,
+        smells::scan_early_returns,
+    ),
+];
+
 /// Scan code for bullshit patterns using regex
-pub fn scan_code(code: &str, config: &DetectConfig) -> anyhow::Result<Vec<BullshitAlert>> {
-    use regex::Regex;
-    use std::collections::HashMap;
+///
+/// Fails with [`DetectError::Timeout`] if the scan runs past `config.max_scan_millis`.
+pub fn scan_code(code: &str, config: &DetectConfig) -> error::Result<Vec<BullshitAlert>> {
+    let budget = limits::ScanBudget::start(config.max_scan_millis);
+    scan_with(code, config, true, None, budget)
+}
+
+/// Like [`scan_code`], but run only the listed detectors - the others cost nothing
+pub fn scan_code_with_detectors(
+    code: &str,
+    config: &DetectConfig,
+    detectors: &[DetectorId],
+) -> error::Result<Vec<BullshitAlert>> {
+    let budget = limits::ScanBudget::start(config.max_scan_millis);
+    scan_with(code, config, true, Some(detectors), budget)
+}
 
+/// Scan a fragment of a larger document, e.g. the region an editor changed
+///
+/// `base_line` is the number of document lines before the fragment, and is
+/// added to every alert's line so locations point into the full document.
+/// Whole-file detectors (duplicate code, literal style, public item counts)
+/// are skipped, and function-level ones only see functions that lie entirely
+/// inside the fragment.
+pub fn scan_region(
+    code: &str,
+    base_line: usize,
+    config: &DetectConfig,
+) -> error::Result<Vec<BullshitAlert>> {
+    let budget = limits::ScanBudget::start(config.max_scan_millis);
+    let mut alerts = scan_with(code, config, false, None, budget)?;
+    for alert in &mut alerts {
+        alert.location.line += base_line;
+    }
+    Ok(alerts)
+}
+
+/// Run the detectors (all of them, or just `detectors`) over `code`, failing
+/// once `budget` is spent
+fn scan_with(
+    code: &str,
+    config: &DetectConfig,
+    whole_file: bool,
+    detectors: Option<&[DetectorId]>,
+    budget: limits::ScanBudget,
+) -> error::Result<Vec<BullshitAlert>> {
+    let wanted = |id: DetectorId| detectors.is_none_or(|ids| ids.contains(&id));
     let mut alerts = Vec::new();
-    let mut patterns = HashMap::new();
-
-    // Pattern definitions
-    patterns.insert(r"Arc<RwLock<.*>>", BullshitType::OverEngineering);
-    patterns.insert(r"Mutex<HashMap<.*>>", BullshitType::OverEngineering);
-    patterns.insert(r"std::thread::sleep", BullshitType::SleepAbuse);
-    patterns.insert(r"tokio::time::sleep", BullshitType::SleepAbuse);
-    patterns.insert(r"\.unwrap\(\)", BullshitType::UnwrapAbuse);
-    patterns.insert(r"\.clone\(\)", BullshitType::CloneAbuse);
-    
-    // Magic number patterns
-    patterns.insert(r"if\s+.*\s*[<>=]+\s*0\.[3-9][0-9]*", BullshitType::MagicNumber);
-    patterns.insert(r"Duration::from_secs\(\d{2,}\)", BullshitType::HardcodedThreshold);
+    // Pattern definitions, in a fixed order so output is reproducible
+    let patterns = [
+        (
+            r"Arc<RwLock<.*>>",
+            BullshitType::OverEngineering,
+            DetectorId::OverEngineering,
+        ),
+        (
+            r"Mutex<HashMap<.*>>",
+            BullshitType::OverEngineering,
+            DetectorId::OverEngineering,
+        ),
+        (
+            r"std::thread::sleep",
+            BullshitType::SleepAbuse,
+            DetectorId::SleepAbuse,
+        ),
+        (
+            r"tokio::time::sleep",
+            BullshitType::SleepAbuse,
+            DetectorId::SleepAbuse,
+        ),
+        (
+            r"\.unwrap\(\)",
+            BullshitType::UnwrapAbuse,
+            DetectorId::UnwrapAbuse,
+        ),
+        (
+            r"\.clone\(\)",
+            BullshitType::CloneAbuse,
+            DetectorId::CloneAbuse,
+        ),
+        // Magic number patterns
+        (
+            r"if\s+.*\s*[<>=]+\s*0\.[3-9][0-9]*",
+            BullshitType::MagicNumber,
+            DetectorId::MagicNumber,
+        ),
+        (
+            r"Duration::from_secs\(\d{2,}\)",
+            BullshitType::HardcodedThreshold,
+            DetectorId::HardcodedThreshold,
+        ),
+    ];
 
-    for (pattern, bs_type) in patterns {
-        let regex = Regex::new(pattern)?;
+    // `.lock().unwrap()` gets its own alert from `smells::scan_lock_unwraps`,
+    // unless that detector isn't running or its type is disabled
+    let lock_unwraps: HashSet<usize> =
+        if wanted(DetectorId::UnwrapAbuse) && wanted(DetectorId::LockUnwrap) {
+            smells::find_lock_unwraps(code)?
+                .into_iter()
+                .filter(|lock| !config.disabled_types.contains(&lock.issue_type))
+                .map(|lock| lock.unwrap_at)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+    // Likewise `5u32.clone()` and friends from `smells::scan_copy_clones`
+    let copy_clones: HashSet<usize> =
+        if wanted(DetectorId::CloneAbuse) && wanted(DetectorId::CopyClone) {
+            smells::find_copy_clones(code)?
+                .into_iter()
+                .map(|clone| clone.call_at)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
+    let lines = location::LineIndex::new(code);
+    for (pattern, bs_type, id) in patterns {
+        if !wanted(id) {
+            continue;
+        }
+        let regex = compile_regex(pattern)?;
         for mat in regex.find_iter(code) {
-            let confidence = match bs_type {
-                BullshitType::OverEngineering => 0.8,
-                BullshitType::SleepAbuse => 0.75,
-                BullshitType::MagicNumber => 0.9,
-                BullshitType::HardcodedThreshold => 0.85,
-                _ => 0.7,
-            };
+            budget.check()?;
+            if bs_type == BullshitType::UnwrapAbuse && lock_unwraps.contains(&mat.start()) {
+                continue;
+            }
+            if bs_type == BullshitType::CloneAbuse && copy_clones.contains(&mat.start()) {
+                continue;
+            }
+            let (base, _) = scoring::score(
+                scoring::ScoreKind::Pattern(&bs_type),
+                scoring::ScoreFeatures::default(),
+            );
+            let confidence = config.confidence_for(&bs_type, base);
 
             if confidence >= config.confidence_threshold {
-                alerts.push(BullshitAlert {
-                    issue_type: bs_type.clone(),
-                    confidence,
-                    location: find_line_column(code, mat.start()),
-                    context_snippet: extract_snippet(code, mat.start(), mat.end(), config.max_snippet_length),
-                    why_bs: format!("Pattern match: {}", pattern),
-                    sug: generate_suggestion(&bs_type),
-                    severity: confidence,
-                });
+                alerts.push(
+                    BullshitAlert::builder(bs_type.clone())
+                        .confidence(confidence)
+                        .location(lines.location(code, mat.start()))
+                        .snippet(extract_snippet(code, mat.start(), mat.end(), config))
+                        .why(format!("Pattern match: {}", pattern))
+                        .build(),
+                );
             }
         }
     }
 
-    Ok(alerts)
-}
-
-/// Find line and column for a character position
-fn find_line_column(code: &str, char_pos: usize) -> (usize, usize) {
-    let mut line = 1;
-    let mut col = 1;
-
-    for (i, ch) in code.char_indices() {
-        if i >= char_pos {
-            break;
+    let file_scanners: &[(DetectorId, SmellScanner)] =
+        if whole_file { &FILE_SCANNERS } else { &[] };
+    for (id, scan) in SCOPED_SCANNERS.iter().chain(file_scanners) {
+        if !wanted(*id) {
+            continue;
         }
-        if ch == '\n' {
-            line += 1;
-            col = 1;
-        } else {
-            col += 1;
+        budget.check()?;
+        alerts.extend(scan(code, config)?);
+    }
+    for (id, bs_type, scan) in &OPT_IN_SCANNERS {
+        let opted_in = match detectors {
+            Some(ids) => ids.contains(id),
+            None => config.enabled_types.contains(bs_type),
+        };
+        if !opted_in {
+            continue;
         }
+        budget.check()?;
+        alerts.extend(scan(code, config)?);
     }
+    alerts.retain(|a| {
+        a.confidence >= config.confidence_threshold
+            && a.severity >= config.min_severity
+            && !config.disabled_types.contains(&a.issue_type)
+    });
+    sort_alerts(&mut alerts);
+
+    Ok(alerts)
+}
 
-    (line, col)
+/// Sort alerts by file, line, column and type so output is stable across runs
+pub fn sort_alerts(alerts: &mut [BullshitAlert]) {
+    alerts.sort_by(|a, b| {
+        (&a.file_path, a.location, &a.issue_type).cmp(&(&b.file_path, b.location, &b.issue_type))
+    });
 }
 
 /// Extract code snippet around a position
-fn extract_snippet(code: &str, start: usize, end: usize, max_length: usize) -> String {
-    let snippet_start = start.saturating_sub(50);
-    let snippet_end = (end + 50).min(code.len());
+fn extract_snippet(code: &str, start: usize, end: usize, config: &DetectConfig) -> String {
+    let context = config.snippet_context_bytes;
+    let window_start = floor_char_boundary(code, start.saturating_sub(context));
+    let window_end = ceil_char_boundary(code, end.saturating_add(context).min(code.len()));
+
+    // Widen to whole lines so the snippet never starts or ends mid-token
+    let snippet_start = code[..window_start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let snippet_end = code[window_end..]
+        .find('\n')
+        .map(|i| window_end + i)
+        .unwrap_or(code.len());
     let snippet = &code[snippet_start..snippet_end];
+    let max_length = config.max_snippet_length;
     
     if snippet.len() > max_length {
-        format!("{}...", &snippet[..max_length])
+        format!(
+            "{}...",
+            &snippet[..floor_char_boundary(snippet, max_length)]
+        )
     } else {
         snippet.to_string()
     }
 }
 
+/// Largest char boundary at or before `index`
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Smallest char boundary at or after `index`
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
 /// Generate suggestions based on bullshit type
 fn generate_suggestion(bs_type: &BullshitType) -> String {
     match bs_type {
@@ -193,12 +1014,42 @@ fn generate_suggestion(bs_type: &BullshitType) -> String {
         BullshitType::CargoCult => "Import only what you actually use".to_string(),
         BullshitType::MagicNumber => "Extract to constant or config".to_string(),
         BullshitType::HardcodedThreshold => "Move to configuration struct".to_string(),
+        BullshitType::ShadowAbuse => "Give each intermediate value its own name".to_string(),
+        BullshitType::LongParameterList => {
+            "Group related parameters into a struct or builder".to_string()
+        }
+        BullshitType::DeepNesting => "Flatten with early returns or extract helper functions".to_string(),
+        BullshitType::NeedlessAllocation => "Borrow instead of allocating a new String or collection".to_string(),
+        BullshitType::SwallowedError => "Propagate the error with ? or at least log it".to_string(),
+        BullshitType::HardcodedPath => "Read the path from config or std::env instead of hardcoding it".to_string(),
+        BullshitType::LongFunction => "Split the function into smaller, focused helpers".to_string(),
+        BullshitType::FloatEquality => "Compare floats with a tolerance instead of == or !=".to_string(),
+        BullshitType::DuplicateCode => "Extract the repeated block into a shared function".to_string(),
+        BullshitType::HardcodedSecret => "Load the secret from the environment or a secrets manager, and rotate it".to_string(),
+        BullshitType::UndocumentedUnsafe => "Add a // SAFETY: comment explaining why the invariants hold".to_string(),
+        BullshitType::InconsistentLiteralStyle => "Write equal values the same way throughout the file".to_string(),
+        BullshitType::DebugLeftover => "Remove debug output or route it through a logger".to_string(),
+        BullshitType::StringlyTypedError => "Define an error enum (e.g. with thiserror) so callers can match on failures".to_string(),
+        BullshitType::LongMethodChain => "Break the chain into named intermediate values".to_string(),
+        BullshitType::BorrowedOwnedType => "Borrow the slice, str or inner value instead".to_string(),
+        BullshitType::LossyCast => "Convert with TryFrom and handle the out-of-range case".to_string(),
+        BullshitType::MutableStatic => "Use an atomic, a Mutex, or OnceLock instead of static mut".to_string(),
+        BullshitType::BlockingInAsync => "Use the async equivalent or move the work to spawn_blocking".to_string(),
+        BullshitType::MixedErrorHandling => "Pick one error-handling style per function - propagate with ? unless a failure really is impossible".to_string(),
+        BullshitType::PollingLoop => "Wait on a channel, Condvar or async Notify instead of sleeping in a loop".to_string(),
+        BullshitType::EarlyReturn => "Restructure so the function has a single exit, e.g. compute the result in one expression".to_string(),
+        BullshitType::NestedWrapper => "Flatten the layers (e.g. .flatten() or ?) or replace them with an enum naming each case".to_string(),
+        BullshitType::LenZeroComparison => "Use .is_empty() / !.is_empty() instead of comparing .len() with zero".to_string(),
+        BullshitType::EagerFallback => "Use the lazy _else variant so the fallback is only computed when needed".to_string(),
+        BullshitType::OutParameter => "Return the value (or a tuple/struct of values) instead of filling a &mut out-parameter".to_string(),
+        BullshitType::LongStringLiteral => "Move the text to a file and load it with `include_str!`".to_string(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_magic_number_detection() {
@@ -227,6 +1078,85 @@ mod tests {
         assert!(alerts.iter().any(|a| a.issue_type == BullshitType::UnwrapAbuse));
     }
 
+    #[test]
+    fn test_lock_unwrap_replaces_generic_unwrap() {
+        let code = r#"
+            let guard = m.lock().unwrap();
+            let value = some_fn().unwrap();
+        "#;
+
+        let alerts = scan_code(code, &DetectConfig::default()).unwrap();
+        let types: Vec<_> = alerts.iter().map(|a| &a.issue_type).collect();
+
+        assert_eq!(
+            types,
+            vec![&BullshitType::MutexAbuse, &BullshitType::UnwrapAbuse]
+        );
+        assert_eq!(alerts[1].location.line, 3);
+    }
+
+    #[test]
+    fn test_scan_region_shifts_lines_by_base() {
+        let region = "    let copy = data.clone();\n    let value = some_fn().unwrap();\n";
+
+        let alerts = scan_region(region, 40, &DetectConfig::default()).unwrap();
+        let lines: Vec<_> = alerts
+            .iter()
+            .map(|a| (&a.issue_type, a.location.line))
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                (&BullshitType::CloneAbuse, 41),
+                (&BullshitType::UnwrapAbuse, 42)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_security_category_filter() {
+        let code = r#"
+            let api_key = "sk-live-9fA3kQ7zXw";
+            let copy = data.clone();
+        "#;
+        let alerts = scan_code(code, &DetectConfig::default()).unwrap();
+        assert!(alerts
+            .iter()
+            .any(|a| a.issue_type == BullshitType::CloneAbuse));
+
+        let security: Vec<_> = alerts
+            .iter()
+            .filter(|a| a.issue_type.category() == Category::Security)
+            .map(|a| &a.issue_type)
+            .collect();
+        assert_eq!(security, vec![&BullshitType::HardcodedSecret]);
+        assert_eq!("Security".parse::<Category>().unwrap(), Category::Security);
+    }
+
+    #[test]
+    fn test_min_severity_prunes_low_severity_alerts() {
+        let code = r#"
+            let copy = data.clone();
+            let value = some_fn().unwrap();
+        "#;
+        let mut config = DetectConfig::default();
+        config
+            .confidence_overrides
+            .insert(BullshitType::CloneAbuse, 0.65);
+
+        let all = scan_code(code, &config).unwrap();
+        assert!(all.iter().any(|a| a.issue_type == BullshitType::CloneAbuse));
+
+        config.min_severity = 0.68;
+        let types: Vec<_> = scan_code(code, &config)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.issue_type)
+            .collect();
+        assert_eq!(types, vec![BullshitType::UnwrapAbuse]);
+    }
+
     #[test]
     fn test_sleep_abuse_detection() {
         let code = r#"
@@ -238,4 +1168,186 @@ mod tests {
 
         assert!(alerts.iter().any(|a| a.issue_type == BullshitType::SleepAbuse));
     }
+
+    #[test]
+    fn test_confidence_override_passes_threshold() {
+        let code = r#"
+            let copy = data.clone();
+        "#;
+
+        let mut config = DetectConfig {
+            confidence_threshold: 0.9,
+            ..Default::default()
+        };
+        let alerts = scan_code(code, &config).unwrap();
+        assert!(!alerts
+            .iter()
+            .any(|a| a.issue_type == BullshitType::CloneAbuse));
+
+        config
+            .confidence_overrides
+            .insert(BullshitType::CloneAbuse, 0.95);
+        let alerts = scan_code(code, &config).unwrap();
+        let clone = alerts
+            .iter()
+            .find(|a| a.issue_type == BullshitType::CloneAbuse)
+            .expect("override should lift CloneAbuse over the threshold");
+        assert_eq!(clone.confidence, 0.95);
+    }
+
+    #[test]
+    fn test_snippet_window_inside_multibyte_char() {
+        // Odd window sizes land inside a two-byte `é` on either side of the match
+        let code = "let é = ééé.clone();ééé\nlet next = 1;";
+
+        for context in 0..8 {
+            let config = DetectConfig {
+                snippet_context_bytes: context,
+                ..DetectConfig::default()
+            };
+            let alerts = scan_code(code, &config).unwrap();
+
+            let clone = alerts
+                .iter()
+                .find(|a| a.issue_type == BullshitType::CloneAbuse)
+                .unwrap();
+            assert_eq!(clone.context_snippet, "let é = ééé.clone();ééé");
+        }
+    }
+
+    #[test]
+    fn test_snippet_truncation_inside_multibyte_char() {
+        // 数 and 値 are three bytes each, so most cut points land mid-codepoint
+        let code = "// 数値 0.85\nlet v = x.clone(); // 数値 0.85\n";
+
+        for max_length in 0..16 {
+            let config = DetectConfig {
+                max_snippet_length: max_length,
+                ..DetectConfig::default()
+            };
+            let alerts = scan_code(code, &config).unwrap();
+
+            let clone = alerts
+                .iter()
+                .find(|a| a.issue_type == BullshitType::CloneAbuse)
+                .unwrap();
+            let truncated = clone.context_snippet.trim_end_matches("...");
+            assert!(truncated.len() <= max_length);
+            assert!(code.starts_with(truncated));
+        }
+    }
+
+    #[test]
+    fn test_repeated_scans_give_identical_json() {
+        let code = r#"
+            let data = Arc<RwLock<HashMap<String, u32>>>::default();
+            let v = load().unwrap().clone();
+            std::thread::sleep(Duration::from_secs(30));
+            if score > 0.75 { retry().unwrap(); }
+        "#;
+        let config = DetectConfig::default();
+
+        let first = serde_json::to_string(&scan_code(code, &config).unwrap()).unwrap();
+        for _ in 0..10 {
+            let again = serde_json::to_string(&scan_code(code, &config).unwrap()).unwrap();
+            assert_eq!(first, again);
+        }
+    }
+
+    #[test]
+    fn test_spent_budget_times_out() {
+        // Started before the limit, so the first check fails however fast the scan is
+        let started = Instant::now() - Duration::from_millis(5);
+        let budget = limits::ScanBudget::started_at(started, 1);
+
+        let err = scan_with(
+            "let v = x.clone();\n",
+            &DetectConfig::default(),
+            true,
+            None,
+            budget,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, DetectError::Timeout { millis: 1 }));
+    }
+
+    #[test]
+    fn test_scan_code_json_round_trips() {
+        let code = "fn f() {\n    let v = x.clone();\n    load().unwrap();\n}\n";
+
+        let json = scan_code_json(code, r#"{"confidence_overrides": {"CloneAbuse": 0.3}}"#);
+        let alerts: Vec<BullshitAlert> = serde_json::from_str(&json).unwrap();
+        assert!(alerts
+            .iter()
+            .any(|a| a.issue_type == BullshitType::UnwrapAbuse));
+        assert!(alerts
+            .iter()
+            .all(|a| a.issue_type != BullshitType::CloneAbuse));
+
+        let defaults: Vec<BullshitAlert> = serde_json::from_str(&scan_code_json(code, "")).unwrap();
+        assert_eq!(defaults.len(), alerts.len() + 1);
+
+        let error: serde_json::Value =
+            serde_json::from_str(&scan_code_json(code, r#"{"max_params": "six"}"#)).unwrap();
+        assert!(error["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid config"));
+    }
+
+    #[test]
+    fn test_alert_builder_derives_severity() {
+        let alert = BullshitAlert::builder(BullshitType::HardcodedSecret)
+            .confidence(0.8)
+            .at(3, 9)
+            .snippet("let key = \"sk-live\";")
+            .why("Secret in source")
+            .build();
+
+        assert_eq!(alert.location, SourceLocation::new(3, 9));
+        assert!(
+            (alert.severity - 0.9).abs() < 1e-6,
+            "security boosts severity"
+        );
+        assert_eq!(
+            alert.sug,
+            generate_suggestion(&BullshitType::HardcodedSecret)
+        );
+        assert_eq!(alert.file_path, None);
+
+        let style = BullshitAlert::builder(BullshitType::CargoCult)
+            .confidence(0.7)
+            .build();
+        assert!((style.severity - 0.6).abs() < 1e-6);
+
+        let pinned = BullshitAlert::builder(BullshitType::CargoCult)
+            .confidence(0.7)
+            .severity(0.95)
+            .build();
+        assert_eq!(pinned.severity, 0.95);
+    }
+
+    #[test]
+    fn test_scan_code_with_detectors_runs_only_those() {
+        let code =
+            "fn f(v: &Vec<u8>) {\n    let w = v.clone();\n    if score > 0.75 { go(); }\n}\n";
+
+        let all = scan_code(code, &DetectConfig::default()).unwrap();
+        assert!(all.iter().any(|a| a.issue_type == BullshitType::CloneAbuse));
+
+        let only =
+            scan_code_with_detectors(code, &DetectConfig::default(), &[DetectorId::MagicNumber])
+                .unwrap();
+        assert!(!only.is_empty());
+        assert!(only
+            .iter()
+            .all(|a| a.issue_type == BullshitType::MagicNumber));
+
+        assert!(
+            scan_code_with_detectors(code, &DetectConfig::default(), &[])
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
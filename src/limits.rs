@@ -0,0 +1,66 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Resource limits for scanning untrusted input
+//!
+//! Every pattern is compiled with bounded program and DFA cache sizes, and a
+//! per-file time budget aborts scans of pathological input with
+//! [`DetectError::Timeout`] instead of hanging.
+
+use crate::error::{DetectError, Result};
+use regex::{Regex, RegexBuilder};
+use std::time::{Duration, Instant};
+
+/// Largest compiled program a pattern may produce
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Largest lazy DFA cache a pattern may use while matching
+const REGEX_DFA_SIZE_LIMIT: usize = 1 << 21;
+
+/// Compile a pattern with the crate's size limits
+pub(crate) fn compile_regex(pattern: &str) -> Result<Regex> {
+    Ok(RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .build()?)
+}
+
+/// Wall-clock budget for scanning one file
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScanBudget {
+    /// Start time and limit, or `None` when the budget is disabled - in which
+    /// case the clock is never read (it panics on `wasm32-unknown-unknown`)
+    limit: Option<(Instant, Duration)>,
+}
+
+impl ScanBudget {
+    /// Start a budget of `millis` (0 disables it)
+    ///
+    /// Always disabled on wasm32, which has no clock to read.
+    pub fn start(millis: u64) -> Self {
+        let millis = if cfg!(target_arch = "wasm32") { 0 } else { millis };
+        Self {
+            limit: (millis > 0).then(|| (Instant::now(), Duration::from_millis(millis))),
+        }
+    }
+
+    /// A budget of `millis` that began at `started`, so tests can hand a
+    /// scan one that is already spent
+    #[cfg(test)]
+    pub fn started_at(started: Instant, millis: u64) -> Self {
+        Self {
+            limit: Some((started, Duration::from_millis(millis))),
+        }
+    }
+
+    /// Fail with [`DetectError::Timeout`] once the budget is spent
+    pub fn check(&self) -> Result<()> {
+        match self.limit {
+            Some((started, limit)) if started.elapsed() > limit => Err(DetectError::Timeout {
+                millis: limit.as_millis() as u64,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Byte offsets to the 1-based line/column positions alerts carry
+//!
+//! Regex matches give byte offsets; [`SourceLocation`] wants lines and
+//! columns, with columns counted in characters so multi-byte text lines up
+//! with what an editor shows.
+
+use crate::{ceil_char_boundary, SourceLocation};
+
+/// Where each line of a source starts, for many lookups in the same source
+///
+/// Each lookup is a binary search over the line starts, rather than a scan
+/// from the top of the file.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Index the lines of `code`
+    pub fn new(code: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    /// Position of byte `offset` in `code`, the source this index was built from
+    ///
+    /// Offsets past the end are clamped to it, and an offset inside a
+    /// multi-byte character counts that character.
+    pub fn location(&self, code: &str, offset: usize) -> SourceLocation {
+        let offset = ceil_char_boundary(code, offset);
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+
+        SourceLocation::new(line, code[line_start..offset].chars().count() + 1)
+    }
+}
+
+/// Position of byte `offset` in `code`, clamped to the end of the source
+///
+/// For a handful of lookups; build a [`LineIndex`] to convert many offsets
+/// in the same source.
+pub fn byte_offset_to_line_col(code: &str, offset: usize) -> SourceLocation {
+    let offset = ceil_char_boundary(code, offset);
+    let before = &code[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    SourceLocation::new(
+        before.bytes().filter(|&b| b == b'\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_to_line_col() {
+        let code = "fn main() {\n    let café = 42;\n}\n";
+        let index = LineIndex::new(code);
+        let mid_line = code.find("42").unwrap();
+
+        for (offset, expected) in [
+            (0, SourceLocation::new(1, 1)),
+            // `é` is two bytes but one column
+            (mid_line, SourceLocation::new(2, 16)),
+            (code.len() - 1, SourceLocation::new(3, 2)),
+            (code.len() + 100, SourceLocation::new(4, 1)),
+        ] {
+            assert_eq!(byte_offset_to_line_col(code, offset), expected);
+            assert_eq!(index.location(code, offset), expected);
+        }
+    }
+}
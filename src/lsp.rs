@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Minimal Language Server Protocol mode for live editor diagnostics
+//!
+//! Only a tiny subset is spoken: `initialize`, `shutdown`/`exit`, and full
+//! document sync through `textDocument/didOpen` and `didChange`, each answered
+//! with `textDocument/publishDiagnostics`. JSON-RPC framing is hand-rolled.
+
+use crate::{scan_code, BullshitAlert, DetectConfig, SeverityLevel, SourceLocation};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+/// JSON-RPC error code for requests we don't implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Serve LSP over `reader`/`writer` until `exit` or end of input
+pub fn run<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    config: &DetectConfig,
+) -> io::Result<()> {
+    while let Some(message) = read_message(reader)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let id = message.get("id").cloned();
+
+        match method {
+            "initialize" => {
+                let result = json!({
+                    "capabilities": { "textDocumentSync": 1 },
+                    "serverInfo": { "name": "bullshitdetector" },
+                });
+                write_response(writer, id, result)?;
+            }
+            "shutdown" => write_response(writer, id, Value::Null)?,
+            "exit" => break,
+            "textDocument/didOpen" => {
+                let document = &message["params"]["textDocument"];
+                if let (Some(uri), Some(text)) =
+                    (document["uri"].as_str(), document["text"].as_str())
+                {
+                    publish_diagnostics(writer, uri, text, config)?;
+                }
+            }
+            "textDocument/didChange" => {
+                let params = &message["params"];
+                // Full sync: the last change carries the whole document
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(uri), Some(text)) = (params["textDocument"]["uri"].as_str(), text) {
+                    publish_diagnostics(writer, uri, text, config)?;
+                }
+            }
+            // Other requests get an error so the client doesn't wait forever;
+            // other notifications are ignored
+            _ if id.is_some() => {
+                let error = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": METHOD_NOT_FOUND,
+                        "message": format!("Unsupported method {}", method),
+                    },
+                });
+                write_message(writer, &error)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the `publishDiagnostics` params for a document
+pub fn diagnostics_for(uri: &str, text: &str, config: &DetectConfig) -> Value {
+    // A scan error (e.g. a bad regex override) just means no diagnostics
+    let alerts = scan_code(text, config).unwrap_or_default();
+    let diagnostics: Vec<Value> = alerts
+        .iter()
+        .map(|alert| to_diagnostic(alert, text))
+        .collect();
+
+    json!({ "uri": uri, "diagnostics": diagnostics })
+}
+
+/// Read one `Content-Length` framed message, or `None` at end of input
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write one `Content-Length` framed message
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn write_response<W: Write>(writer: &mut W, id: Option<Value>, result: Value) -> io::Result<()> {
+    write_message(
+        writer,
+        &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    )
+}
+
+fn publish_diagnostics<W: Write>(
+    writer: &mut W,
+    uri: &str,
+    text: &str,
+    config: &DetectConfig,
+) -> io::Result<()> {
+    let notification = json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": diagnostics_for(uri, text, config),
+    });
+    write_message(writer, &notification)
+}
+
+/// Map an alert to an LSP `Diagnostic` spanning from its column to the end of the line
+fn to_diagnostic(alert: &BullshitAlert, text: &str) -> Value {
+    let SourceLocation { line, column: col } = alert.location;
+    let line_text = text.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+    let start = utf16_offset(line_text, col.saturating_sub(1));
+    let end = utf16_offset(line_text, usize::MAX).max(start);
+
+    let severity = match alert.severity_level() {
+        SeverityLevel::Critical => 1,
+        SeverityLevel::High => 2,
+        SeverityLevel::Medium => 3,
+    };
+
+    json!({
+        "range": {
+            "start": { "line": line.saturating_sub(1), "character": start },
+            "end": { "line": line.saturating_sub(1), "character": end },
+        },
+        "severity": severity,
+        "code": alert.issue_type.to_string(),
+        "source": "bsd",
+        "message": alert.why_bs,
+    })
+}
+
+/// LSP positions count UTF-16 code units; convert from a char count
+fn utf16_offset(line: &str, chars: usize) -> usize {
+    line.chars().take(chars).map(char::len_utf16).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn frame(message: Value) -> String {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    #[test]
+    fn test_did_open_publishes_diagnostics() {
+        let input = [
+            frame(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })),
+            frame(json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": { "textDocument": {
+                    "uri": "file:///src/lib.rs",
+                    "languageId": "rust",
+                    "version": 1,
+                    "text": "fn f() {\n    let v = load().unwrap();\n}\n",
+                }},
+            })),
+            frame(json!({ "jsonrpc": "2.0", "method": "exit" })),
+        ]
+        .concat();
+
+        let mut output = Vec::new();
+        run(
+            &mut Cursor::new(input),
+            &mut output,
+            &DetectConfig::default(),
+        )
+        .unwrap();
+
+        let mut reader = Cursor::new(output);
+        let initialize = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(initialize["id"], 1);
+        assert_eq!(initialize["result"]["capabilities"]["textDocumentSync"], 1);
+
+        let publish = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(publish["method"], "textDocument/publishDiagnostics");
+        assert_eq!(publish["params"]["uri"], "file:///src/lib.rs");
+
+        let diagnostics = publish["params"]["diagnostics"].as_array().unwrap();
+        let unwrap = diagnostics
+            .iter()
+            .find(|d| d["code"] == "UnwrapAbuse")
+            .unwrap();
+        assert_eq!(unwrap["source"], "bsd");
+        assert_eq!(unwrap["severity"], 3);
+        assert_eq!(
+            unwrap["range"],
+            json!({
+                "start": { "line": 1, "character": 18 },
+                "end": { "line": 1, "character": 28 },
+            })
+        );
+
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+}
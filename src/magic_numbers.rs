@@ -9,12 +9,81 @@
 //!
 //! Aligned with NO_MAGIC_NUMBERS_PHASE1_PLAN.md
 
-use crate::{BullshitAlert, BullshitType};
-use anyhow::Result;
+use crate::error::Result;
+use crate::limits::compile_regex;
+use crate::scoring::{score, score_explained, ScoreFeatures, ScoreKind};
+use crate::syntax::{
+    find_functions, line_at, matching_brace, string_literal_ranges, test_code_ranges,
+};
+use crate::{byte_offset_to_line_col, sort_alerts, BullshitAlert, BullshitType};
+use chrono::{DateTime, Utc};
 use regex::Regex;
 use std::collections::HashSet;
 use std::env;
-use std::path::Path;
+
+/// Largest `for attempt in 0..N` bound still treated as a retry count
+const MAX_RETRY_LOOP_BOUND: u32 = 20;
+
+/// Largest literal capacity hint or buffer length too small to be worth naming
+const MAX_TRIVIAL_CAPACITY: u64 = 4;
+
+/// Relative error within which a float literal counts as a math constant
+const MATH_CONSTANT_TOLERANCE: f64 = 1e-3;
+
+/// `std::f64::consts` items worth recognizing in hand-typed literals
+const MATH_CONSTANTS: &[(&str, f64)] = &[
+    ("PI", std::f64::consts::PI),
+    ("TAU", std::f64::consts::TAU),
+    ("E", std::f64::consts::E),
+    ("SQRT_2", std::f64::consts::SQRT_2),
+    ("FRAC_1_SQRT_2", std::f64::consts::FRAC_1_SQRT_2),
+    ("FRAC_PI_2", std::f64::consts::FRAC_PI_2),
+    ("FRAC_PI_4", std::f64::consts::FRAC_PI_4),
+    ("LN_2", std::f64::consts::LN_2),
+    ("LN_10", std::f64::consts::LN_10),
+];
+
+/// Well-known ratios with no `std` constant, for naming precise literals
+const NAMED_RATIOS: &[(&str, &str, f64)] = &[
+    ("the golden ratio φ", "PHI", 1.618_033_988_749_895),
+    (
+        "1/φ, the golden ratio conjugate",
+        "INV_PHI",
+        0.618_033_988_749_895,
+    ),
+];
+
+/// Seconds and milliseconds in common time units, with the chrono
+/// `Duration` constructor that says the same thing
+const TIME_CONSTANTS: &[(u64, &str, &str)] = &[
+    (3_600, "an hour in seconds", "hours(1)"),
+    (86_400, "a day in seconds", "days(1)"),
+    (604_800, "a week in seconds", "weeks(1)"),
+    (60_000, "a minute in milliseconds", "minutes(1)"),
+    (3_600_000, "an hour in milliseconds", "hours(1)"),
+    (86_400_000, "a day in milliseconds", "days(1)"),
+];
+
+/// Factors that time arithmetic like `24 * 60 * 60` or `1000 * 60` is built from
+const TIME_FACTORS: &[u64] = &[1000, 60, 24, 7];
+
+/// Words on a `Duration` line that mark it as a timeout or retry delay
+const TIMEOUT_KEYWORDS: &[&str] = &["timeout", "deadline", "backoff", "retry", "delay"];
+
+/// Async timer calls whose `Duration` argument is a tuning knob, with the
+/// config field the suggestion names
+const TIMER_CALLS: &[(&str, &str)] = &[
+    ("interval", "tick_period"),
+    ("interval_at", "tick_period"),
+    ("timeout", "timeout"),
+    ("timeout_at", "timeout"),
+    ("sleep_until", "delay"),
+    ("delay_for", "delay"),
+];
+
+/// Values whitelisted out of the box: indexing and halving, percentage and
+/// millisecond bases, and a common epsilon
+pub const DEFAULT_WHITELIST_VALUES: [&str; 6] = ["0", "1", "2", "100", "1000", "1e-10"];
 
 /// Configuration for magic number detection
 #[derive(Debug, Clone)]
@@ -25,34 +94,58 @@ pub struct MagicNumberConfig {
     /// Numeric values to whitelist (e.g., 0, 1, 2 for array indexing)
     pub whitelist_values: HashSet<String>,
 
+    /// Whether `whitelist_values` still includes `DEFAULT_WHITELIST_VALUES`
+    /// (cleared by [`MagicNumberConfig::clear_default_whitelist`])
+    pub default_whitelist: bool,
+
     /// Minimum confidence threshold
     pub confidence_threshold: f32,
 
+    /// Alerts with a lower severity are dropped (independent of `confidence_threshold`)
+    pub min_severity: f32,
+
     /// Whether to check inside config.rs (should be false for Phase 1)
     pub scan_config_files: bool,
+
+    /// Flag the literals inside compound `const`/`static` initializers like
+    /// `30 * 60 * 1000`, even in otherwise whitelisted config files
+    pub scan_const_components: bool,
+
+    /// Callees whose literal arguments are data rather than tuning, like
+    /// `Vec3::new(1.0, 0.0, 0.0)` or `Color::rgb(255, 0, 0)`
+    pub ignore_literal_arg_functions: HashSet<String>,
+
+    /// Don't flag buffer lengths that are powers of two, like `[0u8; 4096]`
+    pub allow_power_of_two_buffers: bool,
+
+    /// Significant digits a float literal may have before it's flagged as a
+    /// pasted-in derived value, like `0.6180339887`
+    pub max_float_digits: usize,
 }
 
 impl Default for MagicNumberConfig {
     fn default() -> Self {
-        let mut whitelist_values = HashSet::new();
-
-        // Common non-magic numbers
-        whitelist_values.insert("0".to_string());
-        whitelist_values.insert("1".to_string());
-        whitelist_values.insert("2".to_string());
-        whitelist_values.insert("100".to_string()); // Common percentage base
-        whitelist_values.insert("1000".to_string()); // Common millisecond base
-        whitelist_values.insert("1e-10".to_string()); // Common epsilon
-
         Self {
             whitelist_paths: vec![
                 "src/config.rs".to_string(),
                 "tests/".to_string(),
                 "benches/".to_string(),
             ],
-            whitelist_values,
+            whitelist_values: DEFAULT_WHITELIST_VALUES
+                .iter()
+                .map(|value| value.to_string())
+                .collect(),
+            default_whitelist: true,
             confidence_threshold: 0.7,
+            min_severity: 0.0,
             scan_config_files: false,
+            scan_const_components: false,
+            ignore_literal_arg_functions: ["new", "rgb", "rgba", "vec2", "vec3", "vec4"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            allow_power_of_two_buffers: false,
+            max_float_digits: 6,
         }
     }
 }
@@ -61,9 +154,37 @@ impl MagicNumberConfig {
     /// Build configuration from environment overrides while preserving sane defaults.
     pub fn from_env() -> Self {
         let mut config = Self::default();
+        config.apply_env();
+        config
+    }
+
+    /// Drop `DEFAULT_WHITELIST_VALUES` from the whitelist, so only explicitly
+    /// configured values are skipped
+    ///
+    /// Only the first call removes anything, so values configured after it
+    /// stay put even if they match a default.
+    pub fn clear_default_whitelist(&mut self) {
+        if self.default_whitelist {
+            for value in DEFAULT_WHITELIST_VALUES {
+                self.whitelist_values.remove(value);
+            }
+            self.default_whitelist = false;
+        }
+    }
+
+    /// Apply `NIODOO_MAGIC_*` environment overrides on top of the current values
+    pub fn apply_env(&mut self) {
+        if let Ok(clear) = env::var("NIODOO_MAGIC_NO_DEFAULT_WHITELIST") {
+            if matches!(
+                clear.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            ) {
+                self.clear_default_whitelist();
+            }
+        }
 
         if let Ok(paths) = env::var("NIODOO_MAGIC_WHITELIST_PATHS") {
-            config.whitelist_paths = paths
+            self.whitelist_paths = paths
                 .split(',')
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
@@ -71,7 +192,15 @@ impl MagicNumberConfig {
         }
 
         if let Ok(values) = env::var("NIODOO_MAGIC_WHITELIST_VALUES") {
-            config.whitelist_values = values
+            self.whitelist_values = values
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect::<HashSet<_>>();
+        }
+
+        if let Ok(functions) = env::var("NIODOO_MAGIC_IGNORE_LITERAL_ARG_FUNCTIONS") {
+            self.ignore_literal_arg_functions = functions
                 .split(',')
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
@@ -80,18 +209,42 @@ impl MagicNumberConfig {
 
         if let Ok(conf) = env::var("NIODOO_MAGIC_CONFIDENCE_THRESHOLD") {
             if let Ok(value) = conf.parse::<f32>() {
-                config.confidence_threshold = value.clamp(0.0, 1.0);
+                self.confidence_threshold = value.clamp(0.0, 1.0);
+            }
+        }
+
+        if let Ok(severity) = env::var("NIODOO_MAGIC_MIN_SEVERITY") {
+            if let Ok(value) = severity.parse::<f32>() {
+                self.min_severity = value.clamp(0.0, 1.0);
             }
         }
 
         if let Ok(scan_config) = env::var("NIODOO_MAGIC_SCAN_CONFIG_FILES") {
-            config.scan_config_files = matches!(
+            self.scan_config_files = matches!(
                 scan_config.to_ascii_lowercase().as_str(),
                 "1" | "true" | "yes" | "on"
             );
         }
 
-        config
+        if let Ok(allow) = env::var("NIODOO_MAGIC_ALLOW_POWER_OF_TWO_BUFFERS") {
+            self.allow_power_of_two_buffers = matches!(
+                allow.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
+
+        if let Ok(digits) = env::var("NIODOO_MAGIC_MAX_FLOAT_DIGITS") {
+            if let Ok(value) = digits.parse::<usize>() {
+                self.max_float_digits = value;
+            }
+        }
+
+        if let Ok(scan_components) = env::var("NIODOO_MAGIC_SCAN_CONST_COMPONENTS") {
+            self.scan_const_components = matches!(
+                scan_components.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
     }
 }
 
@@ -103,28 +256,75 @@ pub fn scan_for_magic_numbers(
 ) -> Result<Vec<BullshitAlert>> {
     let mut alerts = Vec::new();
 
-    // Check if file is whitelisted
+    // Check if file is whitelisted - named constants are the point of config
+    // files, but their compound initializers can still bury components
     if is_path_whitelisted(file_path, config) {
+        if config.scan_const_components {
+            alerts.extend(scan_const_components(code, config)?);
+            alerts.retain(|a| {
+                a.confidence >= config.confidence_threshold && a.severity >= config.min_severity
+            });
+            sort_alerts(&mut alerts);
+        }
         return Ok(alerts);
     }
 
     // Scan for hardcoded thresholds in conditionals
     alerts.extend(scan_conditional_thresholds(code)?);
 
+    // Scan for hardcoded limits checked by assertions
+    alerts.extend(scan_assertion_thresholds(code)?);
+
+    // Scan for thresholds that are the whole answer of a function
+    alerts.extend(scan_policy_boundaries(code)?);
+
+    // Scan for retry counts, backoff multipliers and timeouts
+    alerts.extend(scan_retry_and_timeout(code)?);
+
     // Scan for hardcoded constants in assignments
     alerts.extend(scan_assignment_literals(code, config)?);
 
     // Scan for hardcoded values in function arguments
     alerts.extend(scan_function_arg_literals(code, config)?);
 
+    // Scan for literal patterns in match arms
+    alerts.extend(scan_match_literals(code, config)?);
+
+    // Scan for literal collection capacity hints
+    alerts.extend(scan_capacity_literals(code)?);
+
+    // Scan for fixed buffer lengths
+    alerts.extend(scan_buffer_sizes(code, config)?);
+
+    // Scan for unexplained enum discriminants
+    alerts.extend(scan_enum_discriminants(code, config)?);
+
+    // Scan for hand-typed approximations of math constants
+    alerts.extend(scan_math_constants(code)?);
+
+    // Scan for raw seconds/milliseconds time arithmetic
+    alerts.extend(scan_time_constants(code)?);
+
+    // Scan for high-precision floats pasted in from a calculation
+    alerts.extend(scan_precise_floats(code, config)?);
+
+    // Scan for literals buried in compound const initializers
+    if config.scan_const_components {
+        alerts.extend(scan_const_components(code, config)?);
+    }
+
     // Filter by confidence
-    alerts.retain(|a| a.confidence >= config.confidence_threshold);
+    alerts.retain(|a| {
+        a.confidence >= config.confidence_threshold && a.severity >= config.min_severity
+    });
+    sort_alerts(&mut alerts);
 
     Ok(alerts)
 }
 
-/// Check if a path matches whitelist patterns
-fn is_path_whitelisted(file_path: &str, config: &MagicNumberConfig) -> bool {
+/// Check if a path matches whitelist patterns (only compound const
+/// initializers are scanned in such files)
+pub fn is_path_whitelisted(file_path: &str, config: &MagicNumberConfig) -> bool {
     for pattern in &config.whitelist_paths {
         if config.scan_config_files
             && (pattern.contains("config.rs") || pattern.contains("config/"))
@@ -151,45 +351,332 @@ fn scan_conditional_thresholds(code: &str) -> Result<Vec<BullshitAlert>> {
         r"(?m)^\s*\|\s*\w+\s+if\s+.*?\s*([<>=!]+)\s*(\d+\.?\d*(?:[eE][+-]?\d+)?)", // Guard clauses
     ];
 
+    // `x > 0.3 && x < 0.7`: two bounds on one variable form a band
+    let range_regex = compile_regex(
+        r"([\w.]+)\s*(<=|>=|<|>)\s*(\d+\.?\d*(?:[eE][+-]?\d+)?)\s*(&&|\|\|)\s*([\w.]+)\s*(<=|>=|<|>)\s*(\d+\.?\d*(?:[eE][+-]?\d+)?)",
+    )?;
+
     for pattern_str in patterns {
-        let regex = Regex::new(pattern_str)?;
+        let regex = compile_regex(pattern_str)?;
 
         for cap in regex.captures_iter(code) {
-            if let Some(value_match) = cap.get(cap.len() - 1) {
-                let value = value_match.as_str();
-                let pos = value_match.start();
-                let (line, col) = find_line_column(code, pos);
-
-                // Extract context snippet
-                let line_start = code[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
-                let line_end = code[pos..]
-                    .find('\n')
-                    .map(|i| pos + i)
-                    .unwrap_or(code.len());
-                let snippet = code[line_start..line_end].trim().to_string();
+            let (Some(op_match), Some(value_match)) =
+                (cap.get(cap.len() - 2), cap.get(cap.len() - 1))
+            else {
+                continue;
+            };
+            let op = op_match.as_str();
+            let value = value_match.as_str();
+            let pos = value_match.start();
+            let location = byte_offset_to_line_col(code, pos);
+
+            // Extract context snippet
+            let line_start = code[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let line_end = code[pos..]
+                .find('\n')
+                .map(|i| pos + i)
+                .unwrap_or(code.len());
+            let snippet = code[line_start..line_end].trim().to_string();
+
+            if let Some(range) = range_regex
+                .captures(&code[line_start..line_end])
+                .filter(|r| r[1] == r[5] && r.get(3).map(|m| line_start + m.start()) == Some(pos))
+            {
+                let (low, high) = (&range[3], &range[7]);
+                let (confidence, severity, breakdown) = score_explained(
+                    ScoreKind::ConditionalThreshold,
+                    ScoreFeatures::for_threshold(&snippet, low),
+                );
+                alerts.push(
+                    BullshitAlert::builder(BullshitType::HardcodedThreshold)
+                        .confidence(confidence)
+                        .breakdown(breakdown)
+                        .location(location)
+                        .snippet(snippet.clone())
+                        .why(format!(
+                            "Hardcoded range `{}` - bounds {} and {} define an implicit band",
+                            &range[0], low, high
+                        ))
+                        .suggestion(format!(
+                            "Name the band in config, e.g. {}_range: RangeInclusive<f64> = {}..={}, and test it with .contains(&{})",
+                            infer_config_name(&snippet),
+                            low,
+                            high,
+                            &range[1]
+                        ))
+                        .severity(severity)
+                        .build(),
+                );
+                continue;
+            }
 
-                // Calculate confidence based on context
-                let confidence = calculate_threshold_confidence(&snippet, value);
-
-                if confidence > 0.5 {
-                    alerts.push(BullshitAlert {
-                        issue_type: BullshitType::HardcodedThreshold,
-                        confidence,
-                        location: (line, col),
-                        context_snippet: snippet.clone(),
-                        why_bs: format!(
-                            "Hardcoded threshold {} in conditional - should be in RuntimeConfig",
+            // `== 0.5` on a float is its own (worse) smell
+            if matches!(op, "==" | "!=") && is_float_literal(value) {
+                let (confidence, severity) =
+                    score(ScoreKind::FloatEquality, ScoreFeatures::default());
+                alerts.push(
+                    BullshitAlert::builder(BullshitType::FloatEquality)
+                        .confidence(confidence)
+                        .location(location)
+                        .snippet(snippet)
+                        .why(format!(
+                            "Float compared with `{} {}` - rounding makes exact matches unreliable",
+                            op, value
+                        ))
+                        .suggestion(format!(
+                            "Compare with a tolerance, e.g. (x - {}).abs() < epsilon with epsilon from config",
                             value
-                        ),
-                        sug: format!(
+                        ))
+                        .severity(severity)
+                        .build(),
+                );
+                continue;
+            }
+
+            // Calculate confidence based on context
+            let (confidence, severity, breakdown) = score_explained(
+                ScoreKind::ConditionalThreshold,
+                ScoreFeatures::for_threshold(&snippet, value),
+            );
+
+            if confidence > 0.5 {
+                alerts.push(
+                    BullshitAlert::builder(BullshitType::HardcodedThreshold)
+                        .confidence(confidence)
+                        .breakdown(breakdown)
+                        .location(location)
+                        .snippet(snippet.clone())
+                        .why(format!(
+                            "Hardcoded threshold `{} {}` in conditional - should be in RuntimeConfig",
+                            op, value
+                        ))
+                        .suggestion(format!(
                             "Move {} to config and use self.config.{}_threshold",
                             value,
                             infer_config_name(&snippet)
-                        ),
-                        severity: confidence,
-                    });
+                        ))
+                        .severity(severity)
+                        .build(),
+                );
+            }
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for hardcoded limits in `assert!`-family macros outside test code
+/// Examples: `assert!(ratio > 0.9)`, `debug_assert_eq!(buf.len(), 4096)`
+///
+/// Only the checked expression counts: the condition of `assert!`, the two
+/// operands of `assert_eq!`/`assert_ne!`, never the panic message.
+fn scan_assertion_thresholds(code: &str) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let assert_regex = compile_regex(r"\b((?:debug_)?assert(_eq|_ne)?)!\s*\(")?;
+    let comparison_regex =
+        compile_regex(r"(<=|>=|==|!=|<|>)\s*(-?\d[\d_]*\.?\d*(?:[eE][+-]?\d+)?)\b")?;
+    let literal_regex =
+        compile_regex(r"^-?\d[\d_]*\.?\d*(?:[eE][+-]?\d+)?(?:_?[fiu]\d+|_?[iu]size)?$")?;
+    let strings = string_literal_ranges(code);
+    let test_ranges = test_code_ranges(code);
+
+    for cap in assert_regex.captures_iter(code) {
+        let (Some(whole), Some(name)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let pos = whole.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos))
+            || test_ranges.iter().any(|r| r.contains(&pos))
+            || code[line_start..pos].contains("//")
+        {
+            continue;
+        }
+        let Some(args) = macro_arguments(code, whole.end() - 1) else {
+            continue;
+        };
+
+        // (operator, literal, offset of the literal)
+        let mut compared = Vec::new();
+        match cap.get(2).map(|m| m.as_str()) {
+            Some(kind) if args.len() >= 2 => {
+                let op = if kind == "_eq" { "==" } else { "!=" };
+                for &(offset, arg) in &args[..2] {
+                    let value = arg.trim();
+                    if literal_regex.is_match(value) {
+                        let value_pos = offset + (arg.len() - arg.trim_start().len());
+                        compared.push((op, value, value_pos));
+                    }
+                }
+            }
+            Some(_) => {}
+            None => {
+                let Some(&(offset, condition)) = args.first() else {
+                    continue;
+                };
+                for m in comparison_regex.captures_iter(condition) {
+                    let (Some(op), Some(value)) = (m.get(1), m.get(2)) else {
+                        continue;
+                    };
+                    // `=>` and `->` aren't comparisons
+                    if op.as_str() == ">" && condition[..op.start()].ends_with(['=', '-']) {
+                        continue;
+                    }
+                    compared.push((op.as_str(), value.as_str(), offset + value.start()));
+                }
+            }
+        }
+
+        for (op, value, value_pos) in compared {
+            let snippet = line_at(code, value_pos);
+            let (confidence, severity, breakdown) = score_explained(
+                ScoreKind::ConditionalThreshold,
+                ScoreFeatures::for_threshold(&snippet, value),
+            );
+            if confidence <= 0.5 {
+                continue;
+            }
+
+            alerts.push(
+                BullshitAlert::builder(BullshitType::HardcodedThreshold)
+                    .confidence(confidence)
+                    .breakdown(breakdown)
+                    .location(byte_offset_to_line_col(code, value_pos))
+                    .snippet(snippet.clone())
+                    .why(format!(
+                        "Hardcoded threshold `{} {}` in assertion `{}!` - the limit it enforces should be in config",
+                        op,
+                        value,
+                        name.as_str()
+                    ))
+                    .suggestion(format!(
+                        "Move {} to config and assert against self.config.{}_threshold",
+                        value,
+                        infer_config_name(&snippet)
+                    ))
+                    .severity(severity)
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// The comma-separated arguments of the macro call whose `(` is at `open`,
+/// each with its byte offset
+///
+/// Only `()`, `[]` and `{}` nest - `<` and `>` are comparisons here - and
+/// commas inside string literals don't split.
+fn macro_arguments(code: &str, open: usize) -> Option<Vec<(usize, &str)>> {
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut start = open + 1;
+    let mut in_string = false;
+    let mut prev = '\0';
+
+    for (i, ch) in code[open..].char_indices() {
+        let i = open + i;
+        if in_string {
+            in_string = !(ch == '"' && prev != '\\');
+            prev = if prev == '\\' { '\0' } else { ch };
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    args.push((start, &code[start..i]));
+                    return Some(args);
                 }
             }
+            ',' if depth == 1 => {
+                args.push((start, &code[start..i]));
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = ch;
+    }
+
+    None
+}
+
+/// Scan for functions whose tail expression is a hardcoded comparison
+/// Example: `fn is_confident(x: f64) -> bool { x > 0.42 }`
+///
+/// The threshold there *is* the policy, so it scores higher than an ordinary
+/// conditional threshold.
+fn scan_policy_boundaries(code: &str) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let tail_regex = compile_regex(
+        r"^(?:return\s+)?[^;{}]*?\s*(<=|>=|==|!=|<|>)\s*(-?\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?)\s*;?$",
+    )?;
+
+    for function in find_functions(code) {
+        let body = function.body(code);
+
+        // The tail is whatever follows the last statement or nested block
+        let tail_start = body.rfind([';', '{', '}']).map(|i| i + 1).unwrap_or(0);
+        let mut tail = &body[tail_start..];
+        let mut tail_offset = function.body_open + 1 + tail_start;
+
+        // `return x > 0.4;` ends in a semicolon, so step back one statement
+        if tail.trim().is_empty() && body.trim_end().ends_with(';') {
+            let trimmed = body.trim_end();
+            let stmt_end = trimmed.len() - 1;
+            let stmt_start = trimmed[..stmt_end]
+                .rfind([';', '{', '}'])
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            if trimmed[stmt_start..].trim_start().starts_with("return") {
+                tail = &body[stmt_start..=stmt_end];
+                tail_offset = function.body_open + 1 + stmt_start;
+            }
+        }
+
+        let leading = tail.len() - tail.trim_start().len();
+        let tail_expr = tail.trim();
+        tail_offset += leading;
+
+        if let Some(cap) = tail_regex.captures(tail_expr) {
+            let (Some(op_match), Some(value_match)) = (cap.get(1), cap.get(2)) else {
+                continue;
+            };
+            let value = value_match.as_str();
+            let pos = tail_offset + value_match.start();
+            let location = byte_offset_to_line_col(code, pos);
+
+            let snippet = tail_expr.trim_end_matches(';').trim().to_string();
+            let (confidence, severity, breakdown) = score_explained(
+                ScoreKind::PolicyBoundary,
+                ScoreFeatures::for_threshold(&snippet, value),
+            );
+
+            alerts.push(
+                BullshitAlert::builder(BullshitType::HardcodedThreshold)
+                    .confidence(confidence)
+                    .breakdown(breakdown)
+                    .location(location)
+                    .snippet(snippet.clone())
+                    .why(format!(
+                        "Hardcoded comparison `{} {}` is a policy boundary in function `{}`",
+                        op_match.as_str(),
+                        value,
+                        function.name
+                    ))
+                    .suggestion(format!(
+                        "Move {} to config and compare against self.config.{}_threshold",
+                        value,
+                        infer_config_name(&snippet)
+                    ))
+                    .severity(severity)
+                    .build(),
+            );
         }
     }
 
@@ -201,27 +688,32 @@ fn scan_conditional_thresholds(code: &str) -> Result<Vec<BullshitAlert>> {
 fn scan_assignment_literals(code: &str, config: &MagicNumberConfig) -> Result<Vec<BullshitAlert>> {
     let mut alerts = Vec::new();
 
-    // Pattern: let bindings or assignments with numeric literals
+    // Pattern: let/const/static bindings or assignments with numeric literals
     let patterns = vec![
-        r"(?m)^\s*let\s+(\w+)\s*=\s*(\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?)\s*;",
+        r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(?:let|const|static)\s+(?:mut\s+)?(\w+)\s*(?::[^=;]+)?=\s*(\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?)\s*;",
         r"(?m)^\s*(\w+)\s*=\s*(\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?)\s*;",
     ];
 
     for pattern_str in patterns {
-        let regex = Regex::new(pattern_str)?;
+        let regex = compile_regex(pattern_str)?;
 
         for cap in regex.captures_iter(code) {
             if let (Some(var_match), Some(value_match)) = (cap.get(1), cap.get(2)) {
                 let var_name = var_match.as_str();
                 let value = value_match.as_str();
 
-                // Skip whitelisted values
-                if config.whitelist_values.contains(value) {
+                // Skip whitelisted values, retry settings which get their own
+                // alert, and SCREAMING_CASE consts/statics - naming the value
+                // is the fix, not the problem
+                if config.whitelist_values.contains(value)
+                    || is_retry_name(var_name)
+                    || is_screaming_case(var_name)
+                {
                     continue;
                 }
 
                 let pos = value_match.start();
-                let (line, col) = find_line_column(code, pos);
+                let location = byte_offset_to_line_col(code, pos);
 
                 // Extract context
                 let line_start = code[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
@@ -232,24 +724,30 @@ fn scan_assignment_literals(code: &str, config: &MagicNumberConfig) -> Result<Ve
                 let snippet = code[line_start..line_end].trim().to_string();
 
                 // Check if this looks like a config value based on variable name
-                let confidence = calculate_assignment_confidence(var_name, value, &snippet);
+                // (the untrimmed line keeps the indentation used for the scope check)
+                let (confidence, severity, breakdown) = score_explained(
+                    ScoreKind::AssignmentLiteral,
+                    ScoreFeatures::for_assignment(var_name, value, &code[line_start..line_end]),
+                );
 
                 if confidence > 0.6 {
-                    alerts.push(BullshitAlert {
-                        issue_type: BullshitType::MagicNumber,
-                        confidence,
-                        location: (line, col),
-                        context_snippet: snippet.clone(),
-                        why_bs: format!(
-                            "Magic number {} assigned to {} - should be in config",
-                            value, var_name
-                        ),
-                        sug: format!(
-                            "Add {} to RuntimeConfig and initialize from config",
-                            var_name
-                        ),
-                        severity: confidence,
-                    });
+                    alerts.push(
+                        BullshitAlert::builder(BullshitType::MagicNumber)
+                            .confidence(confidence)
+                            .breakdown(breakdown)
+                            .location(location)
+                            .snippet(snippet.clone())
+                            .why(format!(
+                                "Magic number {} assigned to {} - should be in config",
+                                value, var_name
+                            ))
+                            .suggestion(format!(
+                                "Add {} to RuntimeConfig and initialize from config",
+                                var_name
+                            ))
+                            .severity(severity)
+                            .build(),
+                    );
                 }
             }
         }
@@ -258,6 +756,14 @@ fn scan_assignment_literals(code: &str, config: &MagicNumberConfig) -> Result<Ve
     Ok(alerts)
 }
 
+/// `MAX_RETRIES`-style names: uppercase letters, digits and underscores
+fn is_screaming_case(name: &str) -> bool {
+    name.chars().any(|c| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
 /// Scan for hardcoded values passed as function arguments
 /// Example: `calculate_topology(0.5, 0.8)` instead of `calculate_topology(config.threshold1, config.threshold2)`
 fn scan_function_arg_literals(
@@ -268,15 +774,18 @@ fn scan_function_arg_literals(
 
     // Pattern: function calls with numeric literal arguments
     let regex =
-        Regex::new(r"(\w+)\s*\(\s*([^)]*?(\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?)[^)]*?)\s*\)")?;
+        compile_regex(r"(\w+)\s*\(\s*([^)]*?(\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?)[^)]*?)\s*\)")?;
+    let literal_regex = compile_regex(r"\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?")?;
 
     for cap in regex.captures_iter(code) {
         if let (Some(func_match), Some(args_match)) = (cap.get(1), cap.get(2)) {
             let func_name = func_match.as_str();
             let args = args_match.as_str();
+            if config.ignore_literal_arg_functions.contains(func_name) {
+                continue;
+            }
 
             // Count numeric literals in arguments
-            let literal_regex = Regex::new(r"\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64)?")?;
             let literals: Vec<&str> = literal_regex
                 .find_iter(args)
                 .map(|m| m.as_str())
@@ -285,7 +794,7 @@ fn scan_function_arg_literals(
 
             if literals.len() >= 2 {
                 let pos = args_match.start();
-                let (line, col) = find_line_column(code, pos);
+                let location = byte_offset_to_line_col(code, pos);
 
                 // Extract context
                 let line_start = code[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
@@ -295,21 +804,23 @@ fn scan_function_arg_literals(
                     .unwrap_or(code.len());
                 let snippet = code[line_start..line_end].trim().to_string();
 
-                let confidence = 0.75; // High confidence for multiple literals in function args
-
-                alerts.push(BullshitAlert {
-                    issue_type: BullshitType::MagicNumber,
-                    confidence,
-                    location: (line, col),
-                    context_snippet: snippet.clone(),
-                    why_bs: format!(
-                        "Function {} called with {} hardcoded numeric arguments",
-                        func_name,
-                        literals.len()
-                    ),
-                    sug: "Pass config values instead of hardcoded literals".to_string(),
-                    severity: confidence,
-                });
+                let (confidence, severity) =
+                    score(ScoreKind::LiteralArguments, ScoreFeatures::default());
+
+                alerts.push(
+                    BullshitAlert::builder(BullshitType::MagicNumber)
+                        .confidence(confidence)
+                        .location(location)
+                        .snippet(snippet.clone())
+                        .why(format!(
+                            "Function {} called with {} hardcoded numeric arguments",
+                            func_name,
+                            literals.len()
+                        ))
+                        .suggestion("Pass config values instead of hardcoded literals")
+                        .severity(severity)
+                        .build(),
+                );
             }
         }
     }
@@ -317,90 +828,666 @@ fn scan_function_arg_literals(
     Ok(alerts)
 }
 
-/// Calculate confidence that a threshold value is problematic
-fn calculate_threshold_confidence(snippet: &str, value: &str) -> f32 {
-    let mut confidence = 0.5;
-
-    // Keywords that suggest this is a behavioral threshold
-    let threshold_keywords = [
-        "threshold",
-        "limit",
-        "bound",
-        "min",
-        "max",
-        "tolerance",
-        "entropy",
-        "yawn",
-        "healing",
-        "spectral",
-        "knot",
-        "persistence",
-        "quality",
-        "gate",
-        "circuit",
-        "similarity",
-        "cosine",
-    ];
+/// Scan `match` arms for bare numeric literal patterns
+/// Example: `match status { 404 => ..., 500 | 503 => ... }`
+///
+/// Ranges and bindings aren't bare literals and are skipped.
+fn scan_match_literals(code: &str, config: &MagicNumberConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::MatchLiteral, ScoreFeatures::default());
+
+    let match_regex = compile_regex(r"\bmatch\b([^{;]*)\{")?;
+    let scrutinee_regex = compile_regex(r"(\w+)\W*$")?;
+    let arm_regex = compile_regex(
+        r"(?m)^\s*((?:-?\d[\d_]*(?:\.\d+)?\s*\|\s*)*-?\d[\d_]*(?:\.\d+)?)\s*(?:if\b[^\n]*?)?=>",
+    )?;
+    let literal_regex = compile_regex(r"-?\d[\d_]*(?:\.\d+)?")?;
+
+    // Nested matches are scanned with their parent, so report each arm once
+    let mut seen = HashSet::new();
+    for cap in match_regex.captures_iter(code) {
+        let open = cap.get(0).map_or(0, |m| m.end()) - 1;
+        // `match response.status() {` suggests `STATUS_404`
+        let prefix = cap
+            .get(1)
+            .and_then(|m| scrutinee_regex.captures(m.as_str().trim()))
+            .map_or("VALUE".to_string(), |c| c[1].to_uppercase());
+        let close = matching_brace(code, open).unwrap_or(code.len());
+        let body = &code[open + 1..close];
+
+        for arm in arm_regex.captures_iter(body) {
+            let Some(patterns) = arm.get(1) else { continue };
+            let offset = open + 1 + patterns.start();
+            if !seen.insert(offset) {
+                continue;
+            }
+
+            for literal in literal_regex.find_iter(patterns.as_str()) {
+                let value = literal.as_str();
+                if config.whitelist_values.contains(value) {
+                    continue;
+                }
 
-    for keyword in &threshold_keywords {
-        if snippet.to_lowercase().contains(keyword) {
-            confidence += 0.15;
+                let pos = offset + literal.start();
+                alerts.push(
+                    BullshitAlert::builder(BullshitType::MagicNumber)
+                        .confidence(confidence)
+                        .location(byte_offset_to_line_col(code, pos))
+                        .snippet(line_at(code, pos))
+                        .why(format!(
+                            "Magic number {} used as a match arm pattern",
+                            value
+                        ))
+                        .suggestion(format!(
+                            "Name it: `const {}_{}: _ = {};` and match on the constant",
+                            prefix,
+                            value.trim_start_matches('-').replace(['.', '_'], ""),
+                            value
+                        ))
+                        .severity(severity)
+                        .build(),
+                );
+            }
         }
     }
 
-    // Values between 0 and 1 are often thresholds
-    if let Ok(val) = value.parse::<f64>() {
-        if val > 0.0 && val < 1.0 {
-            confidence += 0.2;
+    Ok(alerts)
+}
+
+/// Scan for literal capacity hints
+/// Example: `Vec::with_capacity(4096)` bakes in an assumption about input size
+///
+/// Capacities up to `MAX_TRIVIAL_CAPACITY` are skipped.
+fn scan_capacity_literals(code: &str) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::CapacityLiteral, ScoreFeatures::default());
+    let regex = compile_regex(r"\bwith_capacity\(\s*(\d[\d_]*)(?:usize)?\s*\)")?;
+
+    for cap in regex.captures_iter(code) {
+        let Some(literal) = cap.get(1) else { continue };
+        let value = literal.as_str().replace('_', "");
+        if value
+            .parse::<u64>()
+            .is_ok_and(|capacity| capacity <= MAX_TRIVIAL_CAPACITY)
+        {
+            continue;
         }
+
+        let pos = literal.start();
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MagicNumber)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "Hardcoded capacity {} - an unstated guess at the input size",
+                    literal.as_str()
+                ))
+                .suggestion(
+                    "Size it from the input (e.g. `items.len()`) or name the estimate as a const",
+                )
+                .severity(severity)
+                .build(),
+        );
     }
 
-    confidence.min(0.95)
+    Ok(alerts)
 }
 
-/// Calculate confidence that an assignment is a magic number
-fn calculate_assignment_confidence(var_name: &str, value: &str, snippet: &str) -> f32 {
-    let mut confidence = 0.4;
+/// Scan for float literals approximating a `std::f64::consts` value
+/// Examples: `let pi = 3.14159;`, `angle * 6.2832`, `x / 1.41421`
+///
+/// Literals need two or more decimals and must agree with the constant to
+/// within `MATH_CONSTANT_TOLERANCE`, so `3.14` matches PI but `3.1` doesn't.
+fn scan_math_constants(code: &str) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::MathConstant, ScoreFeatures::default());
+    let regex = compile_regex(r"\b(\d+\.\d{2,})(?:_?(f32|f64))?\b")?;
+    let strings = string_literal_ranges(code);
 
-    // Variable name patterns suggesting config values
-    let config_patterns = [
-        "threshold",
-        "limit",
-        "bound",
-        "weight",
-        "ratio",
-        "factor",
-        "radius",
-        "width",
-        "height",
-        "size",
-        "count",
-        "max",
-        "min",
-        "alpha",
-        "beta",
-        "gamma",
-        "epsilon",
-        "delta",
-    ];
+    for cap in regex.captures_iter(code) {
+        let Some(literal) = cap.get(1) else { continue };
+        let pos = literal.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos)) || code[line_start..pos].contains("//") {
+            continue;
+        }
+        let Ok(value) = literal.as_str().parse::<f64>() else {
+            continue;
+        };
+        let Some((name, _)) = MATH_CONSTANTS
+            .iter()
+            .find(|(_, constant)| ((value - constant) / constant).abs() <= MATH_CONSTANT_TOLERANCE)
+        else {
+            continue;
+        };
+
+        let float = cap.get(2).map_or("f64", |suffix| suffix.as_str());
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MagicNumber)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "{} is a hand-typed approximation of {}",
+                    literal.as_str(),
+                    name
+                ))
+                .suggestion(format!("Use std::{}::consts::{}", float, name))
+                .severity(severity)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for float literals with more than `config.max_float_digits`
+/// significant digits
+/// Example: `let k = 0.6180339887;` - a derived value pasted in, not a tuning
+///
+/// Approximations of `std` math constants are left to `scan_math_constants`;
+/// the golden ratio and its conjugate are named from `NAMED_RATIOS`. Values
+/// in a SCREAMING_CASE const initializer, which already have a name, and
+/// test code are skipped.
+fn scan_precise_floats(code: &str, config: &MagicNumberConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::PreciseFloat, ScoreFeatures::default());
+    let regex = compile_regex(r"\b(\d[\d_]*\.\d[\d_]*)((?:[eE][+-]?\d+)?)(?:_?(f32|f64))?\b")?;
+    let const_regex = named_const_regex()?;
+    let strings = string_literal_ranges(code);
+    let test_ranges = test_code_ranges(code);
+
+    for cap in regex.captures_iter(code) {
+        let (Some(literal), Some(mantissa)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let pos = literal.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos))
+            || test_ranges.iter().any(|r| r.contains(&pos))
+            || code[line_start..pos].contains("//")
+        {
+            continue;
+        }
 
-    for pattern in &config_patterns {
-        if var_name.to_lowercase().contains(pattern) {
-            confidence += 0.25;
+        let digits = significant_digits(mantissa.as_str());
+        let number = format!("{}{}", mantissa.as_str(), &cap[2]).replace('_', "");
+        let Ok(value) = number.parse::<f64>() else {
+            continue;
+        };
+        let near = |constant: f64| ((value - constant) / constant).abs() <= MATH_CONSTANT_TOLERANCE;
+        if digits <= config.max_float_digits
+            || MATH_CONSTANTS.iter().any(|&(_, constant)| near(constant))
+            || in_named_const(code, pos, &const_regex)
+        {
+            continue;
         }
+
+        let float = cap.get(3).map_or("f64", |suffix| suffix.as_str());
+        let (why, suggestion) = match NAMED_RATIOS.iter().find(|&&(_, _, ratio)| near(ratio)) {
+            Some((name, const_name, _)) => (
+                format!(
+                    "{} has {} significant digits - it's {}",
+                    literal.as_str(),
+                    digits,
+                    name
+                ),
+                format!(
+                    "Name it: `const {}: {} = {};`",
+                    const_name,
+                    float,
+                    literal.as_str()
+                ),
+            ),
+            None => (
+                format!(
+                    "{} has {} significant digits (max {}) - likely a derived value pasted in",
+                    literal.as_str(),
+                    digits,
+                    config.max_float_digits
+                ),
+                "Name it with a const, computed from the formula it came from where possible"
+                    .to_string(),
+            ),
+        };
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MagicNumber)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(why)
+                .suggestion(suggestion)
+                .severity(severity)
+                .build(),
+        );
     }
 
-    // Type suffixes suggest this is a raw value, not a constant
-    if value.ends_with("f32") || value.ends_with("f64") {
-        confidence += 0.15;
+    Ok(alerts)
+}
+
+/// Significant digits in the digits of a float literal, ignoring `_`,
+/// leading zeros and trailing zeros after the point
+fn significant_digits(mantissa: &str) -> usize {
+    let digits = mantissa.replace('_', "");
+    let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    let all = format!("{}{}", whole, fraction.trim_end_matches('0'));
+    all.trim_start_matches('0').len()
+}
+
+/// Matches the start of a `const`/`static` item, past any comments and
+/// attributes, capturing its name
+fn named_const_regex() -> Result<Regex> {
+    compile_regex(
+        r"^\s*(?://[^\n]*\n\s*|#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:const|static)\s+(?:mut\s+)?(\w+)",
+    )
+}
+
+/// Whether the literal at `pos` is in the initializer of a SCREAMING_CASE
+/// const or static, so it already has a name
+fn in_named_const(code: &str, pos: usize, const_regex: &Regex) -> bool {
+    let statement_start = code[..pos].rfind([';', '}']).map_or(0, |i| i + 1);
+    const_regex
+        .captures(&code[statement_start..pos])
+        .and_then(|cap| cap.get(1))
+        .is_some_and(|name| is_screaming_case(name.as_str()))
+}
+
+/// Scan for raw time constants and arithmetic building them
+/// Examples: `let day = 86400;`, `let ms = 1000 * 60;`, `elapsed > 24 * 60 * 60`
+///
+/// A product counts when it has a `60` and at most one factor outside
+/// `TIME_FACTORS`, so `30 * 60` is flagged but `3 * 4` isn't. Values in the
+/// initializer of a SCREAMING_CASE const, or already inside `Duration::from_*`,
+/// are skipped.
+fn scan_time_constants(code: &str) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::TimeConstant, ScoreFeatures::default());
+    let product_regex = compile_regex(r"\b\d[\d_]*(?:\s*\*\s*\d[\d_]*)*\b")?;
+    let const_regex = named_const_regex()?;
+    let duration_regex = compile_regex(r"Duration::from_\w+\(\s*$")?;
+    let strings = string_literal_ranges(code);
+
+    for product in product_regex.find_iter(code) {
+        let pos = product.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &code[line_start..pos];
+        // Part of a float like `1.5 * 60` or `60.0`
+        let in_float = prefix.ends_with('.') || code[product.end()..].starts_with('.');
+        if in_float
+            || strings.iter().any(|r| r.contains(&pos))
+            || prefix.contains("//")
+            || duration_regex.is_match(prefix)
+            || in_named_const(code, pos, &const_regex)
+        {
+            continue;
+        }
+
+        let factors: Option<Vec<u64>> = product
+            .as_str()
+            .split('*')
+            .map(|factor| factor.trim().replace('_', "").parse().ok())
+            .collect();
+        let Some(factors) = factors else { continue };
+        let value: u64 = factors.iter().product();
+        let known = TIME_CONSTANTS.iter().find(|(v, _, _)| *v == value);
+        let is_time_product = factors.len() > 1
+            && factors.contains(&60)
+            && factors.iter().filter(|f| !TIME_FACTORS.contains(f)).count() <= 1;
+        if !is_time_product && (factors.len() > 1 || known.is_none()) {
+            continue;
+        }
+
+        let (description, suggestion) = match known {
+            Some((_, description, helper)) => (
+                description.to_string(),
+                format!(
+                    "Use a `Duration` (e.g. chrono's `Duration::{}`) or a named const",
+                    helper
+                ),
+            ),
+            None => (
+                format!(
+                    "a time span in {}",
+                    if factors.contains(&1000) {
+                        "milliseconds"
+                    } else {
+                        "seconds"
+                    }
+                ),
+                "Use `Duration::from_secs`/`from_millis` or a named const".to_string(),
+            ),
+        };
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MagicNumber)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "`{}` is {} written as a raw number",
+                    product.as_str(),
+                    description
+                ))
+                .suggestion(suggestion)
+                .severity(severity)
+                .build(),
+        );
     }
 
-    // Check if inside a function (not at module level)
-    if snippet.starts_with("    ") || snippet.starts_with("\t") {
-        confidence += 0.15; // Inside function scope
+    Ok(alerts)
+}
+
+/// Scan for fixed-size arrays and buffers with a literal length
+/// Examples: `let buf = [0u8; 8192];`, `vec![0; 1024]`, `fn f(b: &[u8; 512])`
+///
+/// Lengths up to `MAX_TRIVIAL_CAPACITY` are skipped, and so are powers of two
+/// with `config.allow_power_of_two_buffers`.
+fn scan_buffer_sizes(code: &str, config: &MagicNumberConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::CapacityLiteral, ScoreFeatures::default());
+    let regex = compile_regex(r"\[[^\[\];\n]+;\s*(\d[\d_]*)(?:usize)?\s*\]")?;
+
+    for cap in regex.captures_iter(code) {
+        let Some(literal) = cap.get(1) else { continue };
+        let Ok(length) = literal.as_str().replace('_', "").parse::<u64>() else {
+            continue;
+        };
+        if length <= MAX_TRIVIAL_CAPACITY
+            || (config.allow_power_of_two_buffers && length.is_power_of_two())
+        {
+            continue;
+        }
+
+        let pos = literal.start();
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MagicNumber)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "Hardcoded buffer length {} - an unstated assumption about message or chunk size",
+                    literal.as_str()
+                ))
+                .suggestion(
+                    "Name it: `const BUFFER_SIZE: usize = ...;` (or size it from the input)",
+                )
+                .severity(severity)
+                .build(),
+        );
     }
 
-    confidence.min(0.95)
+    Ok(alerts)
+}
+
+/// Scan `enum` bodies for explicit discriminants nobody explained
+/// Example: `Red = 0xFF0000,` with no comment on or above the line
+///
+/// A `//` comment at the end of the line, or a comment or doc comment just
+/// above the variant (attributes aside), counts as an explanation.
+fn scan_enum_discriminants(code: &str, config: &MagicNumberConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::EnumDiscriminant, ScoreFeatures::default());
+
+    let enum_regex = compile_regex(r"\benum\s+(\w+)[^{;]*\{")?;
+    let variant_regex = compile_regex(
+        r"(?m)^[ \t]*(\w+)\s*=\s*(-?(?:0x[0-9A-Fa-f_]+|0o[0-7_]+|0b[01_]+|\d[\d_]*)(?:[iu](?:8|16|32|64|128|size))?)[ \t]*,?",
+    )?;
+
+    for cap in enum_regex.captures_iter(code) {
+        let (Some(whole), Some(name)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let open = whole.end() - 1;
+        let close = matching_brace(code, open).unwrap_or(code.len());
+        let body_start = open + 1;
+        let body = &code[body_start..close];
+
+        for variant in variant_regex.captures_iter(body) {
+            let (Some(variant_name), Some(value)) = (variant.get(1), variant.get(2)) else {
+                continue;
+            };
+            if config.whitelist_values.contains(value.as_str())
+                || has_explanation(body, variant_name.start())
+            {
+                continue;
+            }
+
+            let pos = body_start + value.start();
+            alerts.push(
+                BullshitAlert::builder(BullshitType::MagicNumber)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "Discriminant {}::{} = {} has no comment saying where the value comes from",
+                        name.as_str(),
+                        variant_name.as_str(),
+                        value.as_str()
+                    ))
+                    .suggestion("Document the value (e.g. `/// Wire value from the spec`) or use a named const")
+                    .severity(severity)
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Whether the line at `pos` ends in a comment or is preceded by one
+/// (skipping attribute lines)
+fn has_explanation(text: &str, pos: usize) -> bool {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[pos..].find('\n').map_or(text.len(), |i| pos + i);
+    if text[pos..line_end].contains("//") {
+        return true;
+    }
+
+    text[..line_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.starts_with("#["))
+        .is_some_and(|line| line.starts_with("//") || line.ends_with("*/"))
+}
+
+/// Scan compound `const`/`static` initializers for their component literals
+/// Example: `const TIMEOUT_MS: u64 = 30 * 60 * 1000;` buries `30` and `60`
+///
+/// A const initialized with a single literal is already named and is skipped.
+fn scan_const_components(code: &str, config: &MagicNumberConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::ConstComponent, ScoreFeatures::default());
+
+    let const_regex = compile_regex(
+        r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const|static)\s+(?:mut\s+)?(\w+)\s*:[^=;]+=\s*([^;]+);",
+    )?;
+    let literal_regex = compile_regex(
+        r"\b\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d+)?(?:_?(?:[uif]\d+|usize|isize))?\b",
+    )?;
+
+    for cap in const_regex.captures_iter(code) {
+        let (Some(name_match), Some(expr_match)) = (cap.get(1), cap.get(2)) else {
+            continue;
+        };
+        let expr = expr_match.as_str().trim();
+
+        let literals: Vec<_> = literal_regex.find_iter(expr_match.as_str()).collect();
+        if literals.len() == 1 && literals[0].as_str() == expr {
+            continue;
+        }
+
+        for literal in literals {
+            if config.whitelist_values.contains(literal.as_str()) {
+                continue;
+            }
+            let pos = expr_match.start() + literal.start();
+            alerts.push(
+                BullshitAlert::builder(BullshitType::MagicNumber)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "Literal {} is buried in the initializer of {} (`{}`)",
+                        literal.as_str(),
+                        name_match.as_str(),
+                        expr
+                    ))
+                    .suggestion(format!(
+                        "Extract {} into its own named constant and build {} from it",
+                        literal.as_str(),
+                        name_match.as_str()
+                    ))
+                    .severity(severity)
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for hardcoded retry counts, backoff multipliers and timeout durations
+/// Examples: `let max_retries = 5;`, `for attempt in 0..3`, `timeout(Duration::from_secs(30), f)`,
+/// `tokio::time::interval(Duration::from_millis(250))`
+fn scan_retry_and_timeout(code: &str) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let (confidence, severity) = score(ScoreKind::RetryPolicy, ScoreFeatures::default());
+
+    // `let max_retries = 5;` or a struct field `backoff_factor: 2.0,`
+    let setting_regex = compile_regex(
+        r"(?m)^\s*(?:let\s+(?:mut\s+)?)?(\w+)\s*(?::\s*\w+\s*)?[=:]\s*(\d+\.?\d*(?:[eE][+-]?\d+)?(?:f32|f64|u32|u64|usize)?)\s*[;,]",
+    )?;
+    // `for attempt in 0..3` - a small upper bound on a retry-named loop variable
+    let loop_regex = compile_regex(r"\bfor\s+(\w+)\s+in\s+\d+\s*\.\.=?\s*(\d+)\b")?;
+    let duration_regex = compile_regex(
+        r"Duration::from_(?:secs|millis|micros|nanos)(?:_f32|_f64)?\(\s*(\d+\.?\d*)\s*\)",
+    )?;
+
+    let mut push = |pos: usize, why_bs: String, sug: String| {
+        alerts.push(
+            BullshitAlert::builder(BullshitType::HardcodedThreshold)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(why_bs)
+                .suggestion(sug)
+                .severity(severity)
+                .build(),
+        );
+    };
+
+    for cap in setting_regex.captures_iter(code) {
+        if let (Some(name_match), Some(value_match)) = (cap.get(1), cap.get(2)) {
+            let name = name_match.as_str();
+            // `let mut attempts = 0;` is a counter, not a setting
+            if !is_retry_name(name) || value_match.as_str() == "0" {
+                continue;
+            }
+            push(
+                value_match.start(),
+                format!(
+                    "Hardcoded retry setting {} = {}",
+                    name,
+                    value_match.as_str()
+                ),
+                format!(
+                    "Move {} to RetryConfig so it can be tuned per deployment",
+                    name
+                ),
+            );
+        }
+    }
+
+    for cap in loop_regex.captures_iter(code) {
+        if let (Some(var_match), Some(bound_match)) = (cap.get(1), cap.get(2)) {
+            let small_bound = bound_match
+                .as_str()
+                .parse::<u32>()
+                .is_ok_and(|bound| bound <= MAX_RETRY_LOOP_BOUND);
+            if !small_bound || !is_retry_name(var_match.as_str()) {
+                continue;
+            }
+            push(
+                bound_match.start(),
+                format!(
+                    "Retry loop over `{}` hardcodes {} attempts",
+                    var_match.as_str(),
+                    bound_match.as_str()
+                ),
+                "Move the attempt count to RetryConfig (e.g. retry_config.max_attempts)"
+                    .to_string(),
+            );
+        }
+    }
+
+    for cap in duration_regex.captures_iter(code) {
+        let (Some(whole), Some(value_match)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let timer = enclosing_call(code, whole.start())
+            .and_then(|call| TIMER_CALLS.iter().find(|(name, _)| *name == call));
+        if let Some((call, field)) = timer {
+            push(
+                value_match.start(),
+                format!("Hardcoded {} duration {}", call, whole.as_str()),
+                format!(
+                    "Move the duration to config and pass it in (e.g. `{}(config.{})`) \
+                     so it can be tuned without a rebuild",
+                    call, field
+                ),
+            );
+            continue;
+        }
+        let line = line_at(code, whole.start()).to_lowercase();
+        if !TIMEOUT_KEYWORDS.iter().any(|k| line.contains(k)) {
+            continue;
+        }
+        push(
+            value_match.start(),
+            format!("Hardcoded timeout {}", whole.as_str()),
+            "Move the duration to RetryConfig (e.g. retry_config.timeout)".to_string(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Name of the innermost call whose argument list contains `pos`, looking
+/// back no further than the start of the statement
+fn enclosing_call(code: &str, pos: usize) -> Option<&str> {
+    let mut depth = 0usize;
+
+    for (i, ch) in code[..pos].char_indices().rev() {
+        match ch {
+            ')' => depth += 1,
+            '(' if depth > 0 => depth -= 1,
+            '(' => {
+                let before = code[..i].trim_end();
+                let name_start = before
+                    .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+                    .map_or(0, |j| j + 1);
+                return Some(&before[name_start..]).filter(|name| !name.is_empty());
+            }
+            ';' | '{' | '}' => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Whether a numeric literal is a float (has a fraction or exponent)
+fn is_float_literal(value: &str) -> bool {
+    value.contains(['.', 'e', 'E'])
+}
+
+/// Whether a variable name reads as a retry count or backoff setting
+fn is_retry_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["retry", "retries", "attempt", "tries", "backoff"]
+        .iter()
+        .any(|k| name.contains(k))
 }
 
 /// Infer a config field name from the code snippet
@@ -428,37 +1515,34 @@ fn infer_config_name(snippet: &str) -> String {
     }
 }
 
-/// Find line and column for a character position
-fn find_line_column(code: &str, char_pos: usize) -> (usize, usize) {
-    let mut line = 1;
-    let mut col = 1;
-
-    for (i, ch) in code.char_indices() {
-        if i >= char_pos {
-            break;
-        }
-        if ch == '\n' {
-            line += 1;
-            col = 1;
-        } else {
-            col += 1;
-        }
-    }
-
-    (line, col)
-}
+/// Environment variables that pin the report timestamp (Unix seconds or an
+/// RFC 3339 time), checked in order
+pub const REPORT_TIMESTAMP_VARS: [&str; 2] = ["SOURCE_DATE_EPOCH", "NIODOO_REPORT_TIMESTAMP"];
 
 /// Generate a comprehensive report of magic numbers in a codebase
-pub fn generate_magic_number_report(file_alerts: Vec<(String, Vec<BullshitAlert>)>) -> String {
+///
+/// The "Generated" time is `generated_at`, else the time pinned by
+/// [`REPORT_TIMESTAMP_VARS`] (for reproducible reports), else now. Builds
+/// without the `cli` feature have no clock, so without a pinned time the
+/// line is left out.
+pub fn generate_magic_number_report(
+    file_alerts: Vec<(String, Vec<BullshitAlert>)>,
+    generated_at: Option<DateTime<Utc>>,
+) -> String {
     let mut report = String::new();
 
     report.push_str("# Magic Number Detection Report\n\n");
-    report.push_str(&format!("Generated: {}\n\n", chrono::Utc::now()));
+    let generated_at = generated_at.or_else(pinned_report_timestamp);
+    #[cfg(feature = "cli")]
+    let generated_at = generated_at.or_else(|| Some(Utc::now()));
+    if let Some(generated_at) = generated_at {
+        report.push_str(&format!("Generated: {}\n\n", generated_at));
+    }
 
     let total_files: usize = file_alerts.len();
     let total_alerts: usize = file_alerts.iter().map(|(_, alerts)| alerts.len()).sum();
 
-    report.push_str(&format!("## Summary\n"));
+    report.push_str("## Summary\n");
     report.push_str(&format!("- Files scanned: {}\n", total_files));
     report.push_str(&format!(
         "- Total magic numbers found: {}\n\n",
@@ -480,8 +1564,8 @@ pub fn generate_magic_number_report(file_alerts: Vec<(String, Vec<BullshitAlert>
                 "{}. **{}** at line {}:{}\n",
                 i + 1,
                 alert.issue_type,
-                alert.location.0,
-                alert.location.1
+                alert.location.line,
+                alert.location.column
             ));
             report.push_str(&format!("   - **Why**: {}\n", alert.why_bs));
             report.push_str(&format!("   - **Suggestion**: {}\n", alert.sug));
@@ -500,6 +1584,26 @@ pub fn generate_magic_number_report(file_alerts: Vec<(String, Vec<BullshitAlert>
     report
 }
 
+/// The report time set through [`REPORT_TIMESTAMP_VARS`], if any parses
+fn pinned_report_timestamp() -> Option<DateTime<Utc>> {
+    pinned_report_timestamp_from(|name| env::var(name).ok())
+}
+
+/// Like [`pinned_report_timestamp`], reading variables through `get`
+/// rather than the process environment
+fn pinned_report_timestamp_from(get: impl Fn(&str) -> Option<String>) -> Option<DateTime<Utc>> {
+    REPORT_TIMESTAMP_VARS.iter().find_map(|var| {
+        let value = get(var)?;
+        let value = value.trim();
+        match value.parse::<i64>() {
+            Ok(seconds) => DateTime::from_timestamp(seconds, 0),
+            Err(_) => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|time| time.with_timezone(&Utc)),
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -519,6 +1623,39 @@ mod tests {
         assert_eq!(alerts[0].issue_type, BullshitType::HardcodedThreshold);
     }
 
+    #[test]
+    fn test_policy_boundary_in_bool_function() {
+        let code = r#"
+        fn is_confident(x: f64) -> bool { x > 0.42 }
+
+        fn gate(x: f64) {
+            if x > 0.42 {
+                do_something();
+            }
+        }
+        "#;
+
+        let config = MagicNumberConfig::default();
+        let alerts = scan_for_magic_numbers(code, "test.rs", &config).unwrap();
+
+        let policy = alerts
+            .iter()
+            .find(|a| {
+                a.why_bs
+                    .contains("policy boundary in function `is_confident`")
+            })
+            .expect("tail comparison should be flagged as a policy boundary");
+        let conditional = alerts
+            .iter()
+            .find(|a| a.location.line == 5)
+            .expect("plain conditional threshold still flagged");
+
+        assert_eq!(policy.issue_type, BullshitType::HardcodedThreshold);
+        assert_eq!(policy.location.line, 2);
+        assert!(policy.confidence > conditional.confidence);
+        assert!(policy.severity > conditional.severity);
+    }
+
     #[test]
     fn test_detects_assignment_literal() {
         let code = r#"
@@ -545,6 +1682,38 @@ mod tests {
         assert!(alerts.is_empty(), "Common values should be whitelisted");
     }
 
+    #[test]
+    fn test_no_default_whitelist_flags_100() {
+        // A bare `let x = 100;` scores too low to flag either way, so use a
+        // setting-like name
+        let code = "fn f() {\n    let limit = 100;\n    let threshold = 1000;\n}\n";
+        let mut config = MagicNumberConfig::default();
+        assert!(scan_assignment_literals(code, &config).unwrap().is_empty());
+
+        config.clear_default_whitelist();
+        let alerts = scan_assignment_literals(code, &config).unwrap();
+        let values: Vec<&str> = alerts.iter().map(|a| a.why_bs.as_str()).collect();
+        assert_eq!(
+            values,
+            vec![
+                "Magic number 100 assigned to limit - should be in config",
+                "Magic number 1000 assigned to threshold - should be in config",
+            ]
+        );
+
+        // The config file's own values survive its `no_default_whitelist`
+        let file = crate::config::ConfigFile::from_toml_str(
+            "[magic]\nno_default_whitelist = true\nwhitelist_values = [\"1000\"]\n",
+        )
+        .unwrap();
+        let mut config = MagicNumberConfig::default();
+        file.apply_magic_to(&mut config);
+        config.clear_default_whitelist();
+        let alerts = scan_assignment_literals(code, &config).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].why_bs.contains("100 assigned to limit"));
+    }
+
     #[test]
     fn test_whitelist_config_file() {
         let code = r#"
@@ -556,4 +1725,403 @@ mod tests {
 
         assert!(alerts.is_empty(), "config.rs should be whitelisted");
     }
+
+    #[test]
+    fn test_retry_count_assignment() {
+        let code = r#"
+        fn connect() {
+            let max_retries = 5;
+            let mut attempts = 0;
+        }
+        "#;
+
+        let config = MagicNumberConfig::default();
+        let alerts = scan_for_magic_numbers(code, "src/net.rs", &config).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::HardcodedThreshold);
+        assert_eq!(alerts[0].location.line, 3);
+        assert!(alerts[0].sug.contains("RetryConfig"));
+    }
+
+    #[test]
+    fn test_retry_loop_bound() {
+        let code = r#"
+        for attempt in 0..3 {
+            send();
+        }
+        for i in 0..3 {
+            step(i);
+        }
+        "#;
+
+        let alerts = scan_retry_and_timeout(code).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].why_bs.contains("3 attempts"));
+        assert!(alerts[0].sug.contains("RetryConfig"));
+    }
+
+    #[test]
+    fn test_async_timer_durations_flagged() {
+        let code = r#"
+        async fn poll(client: &Client) {
+            let mut ticker = tokio::time::interval(Duration::from_millis(250));
+            let reply = client.fetch().timeout(Duration::from_secs(5)).await;
+            let pause = Duration::from_millis(10);
+        }
+        "#;
+
+        let alerts = scan_retry_and_timeout(code).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (3, "Hardcoded interval duration Duration::from_millis(250)"),
+                (4, "Hardcoded timeout duration Duration::from_secs(5)"),
+            ]
+        );
+        assert!(alerts[0].sug.contains("`interval(config.tick_period)`"));
+    }
+
+    #[test]
+    fn test_assertion_thresholds_outside_tests() {
+        let code = r#"
+fn check(x: f64, buf: &[u8], limit: usize) {
+    assert!(x > 0.9, "x dropped below {}", 0.5);
+    assert!(limit <= 64);
+    debug_assert_eq!(buf.len(), 4096);
+    assert!(x >= 0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_score() {
+        assert!(score() > 0.9);
+    }
+}
+"#;
+        let alerts = scan_assertion_thresholds(code).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    3,
+                    "Hardcoded threshold `> 0.9` in assertion `assert!` - the limit it enforces should be in config"
+                ),
+                (
+                    4,
+                    "Hardcoded threshold `<= 64` in assertion `assert!` - the limit it enforces should be in config"
+                ),
+            ]
+        );
+        assert!(alerts
+            .iter()
+            .all(|a| a.issue_type == BullshitType::HardcodedThreshold));
+    }
+
+    #[test]
+    fn test_float_equality_outranks_threshold() {
+        let code = r#"
+        if x > 0.5 {
+            grow();
+        }
+        if x == 0.5 {
+            stop();
+        }
+        "#;
+
+        let config = MagicNumberConfig::default();
+        let alerts = scan_for_magic_numbers(code, "src/model.rs", &config).unwrap();
+
+        let threshold = alerts
+            .iter()
+            .find(|a| a.issue_type == BullshitType::HardcodedThreshold)
+            .unwrap();
+        let float_eq = alerts
+            .iter()
+            .find(|a| a.issue_type == BullshitType::FloatEquality)
+            .unwrap();
+        assert_eq!(threshold.location.line, 2);
+        assert_eq!(float_eq.location.line, 5);
+        assert!(float_eq.why_bs.contains("`== 0.5`"));
+        assert!(float_eq.severity > threshold.severity);
+    }
+
+    #[test]
+    fn test_const_components_flagged_when_enabled() {
+        let code = r#"
+const X: u64 = 30 * 60 * 1000;
+const NAMED: u64 = 42;
+"#;
+
+        let config = MagicNumberConfig {
+            scan_const_components: true,
+            ..MagicNumberConfig::default()
+        };
+        let alerts = scan_for_magic_numbers(code, "src/config.rs", &config).unwrap();
+
+        // 1000 is on the default value whitelist
+        let values: Vec<_> = alerts
+            .iter()
+            .map(|a| a.why_bs.split_whitespace().nth(1).unwrap())
+            .collect();
+        assert_eq!(values, ["30", "60"]);
+        assert!(alerts.iter().all(|a| a.location.line == 2));
+
+        let disabled = MagicNumberConfig::default();
+        assert!(scan_for_magic_numbers(code, "src/config.rs", &disabled)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_range_chain_reported_once() {
+        let code = "fn classify(x: f64) {\n    if x > 0.3 && x < 0.7 {\n        mid();\n    }\n}\n";
+
+        let alerts = scan_conditional_thresholds(code).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::HardcodedThreshold);
+        assert!(alerts[0].why_bs.contains("bounds 0.3 and 0.7"));
+        assert!(alerts[0].sug.contains("0.3..=0.7"));
+    }
+
+    #[test]
+    fn test_match_arm_literals_flagged() {
+        let code = r#"
+fn describe(status: u16) -> &'static str {
+    match status {
+        0 => "unset",
+        404 => "not found",
+        500..=502 => "server error",
+        503 | 504 => "unavailable",
+        other => "unknown",
+    }
+}
+"#;
+        let alerts = scan_match_literals(code, &MagicNumberConfig::default()).unwrap();
+        let values: Vec<&str> = alerts
+            .iter()
+            .map(|a| a.why_bs.split_whitespace().nth(2).unwrap())
+            .collect();
+
+        assert_eq!(values, vec!["404", "503", "504"]);
+        assert_eq!(alerts[0].location.line, 5);
+        assert!(alerts[0].sug.contains("STATUS_404"));
+    }
+
+    #[test]
+    fn test_ignored_callees_skip_literal_args() {
+        let code = r#"
+fn paint() {
+    let red = Color::rgb(255, 0, 0);
+    configure(0.5, 0.8);
+}
+"#;
+        let alerts = scan_function_arg_literals(code, &MagicNumberConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].why_bs.starts_with("Function configure"));
+    }
+
+    #[test]
+    fn test_capacity_literals_flagged_above_trivial() {
+        let code = "fn buffers() {\n    let big: Vec<u8> = Vec::with_capacity(4096);\n    let pair = Vec::with_capacity(2);\n    let sized = Vec::with_capacity(items.len());\n}\n";
+        let alerts = scan_capacity_literals(code).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].why_bs.contains("4096"));
+    }
+
+    #[test]
+    fn test_buffer_sizes_flagged_above_trivial() {
+        let code = "fn read_all(r: &mut impl Read) {\n    let mut buf = [0u8; 8192];\n    let mut tag = [0u8; 4];\n    let chunk = vec![0; 1_000];\n}\n";
+        let alerts = scan_buffer_sizes(code, &MagicNumberConfig::default()).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![2, 4]);
+        assert!(alerts[0].why_bs.contains("8192"));
+
+        let config = MagicNumberConfig {
+            allow_power_of_two_buffers: true,
+            ..Default::default()
+        };
+        assert_eq!(scan_buffer_sizes(code, &config).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_undocumented_enum_discriminants_flagged() {
+        let code = r#"
+#[repr(u32)]
+enum Color {
+    Red = 0xFF0000,
+    /// Matches the vendor palette
+    Green = 0x00FF00,
+    #[allow(dead_code)]
+    Blue = 0x0000FF, // web blue
+    Black = 0,
+}
+"#;
+        let alerts = scan_enum_discriminants(code, &MagicNumberConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 4);
+        assert!(alerts[0]
+            .why_bs
+            .starts_with("Discriminant Color::Red = 0xFF0000"));
+    }
+
+    #[test]
+    fn test_math_constant_approximations_flagged() {
+        let code = "fn area(r: f64) -> f64 {\n    let pi = 3.14159;\n    let rough = 3.14 * r;\n    let half = 2.5;\n    let e = 2.71828_f32;\n    pi * r * r\n}\n";
+        let alerts = scan_math_constants(code).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![2, 3, 5]);
+        assert_eq!(alerts[0].sug, "Use std::f64::consts::PI");
+        assert!(alerts[1].why_bs.starts_with("3.14 is"));
+        assert_eq!(alerts[2].sug, "Use std::f32::consts::E");
+    }
+
+    #[test]
+    fn test_precise_floats_flagged() {
+        let code = "const DECAY: f64 = 0.9731524;\nfn blend(x: f64) -> f64 {\n    let k = 0.6180339887;\n    let w = 0.7318421;\n    let quarter = 0.7853981634;\n    let half = 0.500000000;\n    let step = 0.125;\n    x * k * w\n}\n";
+        let alerts = scan_precise_floats(code, &MagicNumberConfig::default()).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    3,
+                    "0.6180339887 has 10 significant digits - it's 1/φ, the golden ratio conjugate"
+                ),
+                (
+                    4,
+                    "0.7318421 has 7 significant digits (max 6) - likely a derived value pasted in"
+                ),
+            ]
+        );
+        assert_eq!(
+            alerts[0].sug,
+            "Name it: `const INV_PHI: f64 = 0.6180339887;`"
+        );
+        // π/4 is reported once, by the math constant scan
+        assert_eq!(scan_math_constants(code).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_time_constants_flagged_unless_named() {
+        let code = "const SECS_PER_DAY: u64 =\n    24 * 60 * 60;\nfn expiry(now: u64) -> u64 {\n    let day = 86400;\n    let ms = 1000 * 60;\n    let grid = 3 * 4;\n    let minute = 60;\n    let ratio = 1.5 * 60.0;\n    now + day + ms\n}\n";
+        let alerts = scan_time_constants(code).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (4, "`86400` is a day in seconds written as a raw number"),
+                (
+                    5,
+                    "`1000 * 60` is a minute in milliseconds written as a raw number"
+                ),
+            ]
+        );
+        assert_eq!(
+            alerts[0].sug,
+            "Use a `Duration` (e.g. chrono's `Duration::days(1)`) or a named const"
+        );
+    }
+
+    #[test]
+    fn test_screaming_case_consts_are_not_magic() {
+        let code =
+            "const MAX: u32 = 5;\npub static LIMIT_2: f64 = 0.75;\nfn f() {\n    let max = 5;\n}\n";
+        let alerts = scan_assignment_literals(code, &MagicNumberConfig::default()).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![4]);
+        assert!(alerts[0].why_bs.contains("assigned to max"));
+    }
+
+    #[test]
+    fn test_report_timestamp_pinned_by_source_date_epoch() {
+        let alerts = vec![(
+            "src/lib.rs".to_string(),
+            scan_for_magic_numbers(
+                "fn f(x: f64) -> bool {\n    if x > 0.7 { return true; }\n    false\n}\n",
+                "src/lib.rs",
+                &MagicNumberConfig::default(),
+            )
+            .unwrap(),
+        )];
+
+        let pinned = pinned_report_timestamp_from(|name| {
+            (name == "SOURCE_DATE_EPOCH").then(|| " 1700000000\n".to_string())
+        });
+        let first = generate_magic_number_report(alerts.clone(), pinned);
+        let second = generate_magic_number_report(alerts.clone(), pinned);
+        assert_eq!(first, second);
+        assert!(first.starts_with(
+            "# Magic Number Detection Report\n\nGenerated: 2023-11-14 22:13:20 UTC\n\n"
+        ));
+
+        let explicit = DateTime::from_timestamp(0, 0).unwrap();
+        let report = generate_magic_number_report(alerts, Some(explicit));
+        assert!(report.contains("Generated: 1970-01-01 00:00:00 UTC\n"));
+    }
+
+    #[test]
+    fn test_pinned_report_timestamp_parses_seconds_and_rfc3339() {
+        let pinned = |vars: &[(&str, &str)]| {
+            pinned_report_timestamp_from(|name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+
+        let rfc3339 = pinned(&[("NIODOO_REPORT_TIMESTAMP", "2024-02-29T12:00:00+02:00")]);
+        assert_eq!(
+            rfc3339,
+            DateTime::parse_from_rfc3339("2024-02-29T10:00:00Z")
+                .ok()
+                .map(|time| time.with_timezone(&Utc))
+        );
+        assert_eq!(
+            pinned(&[("NIODOO_REPORT_TIMESTAMP", "0")]),
+            DateTime::from_timestamp(0, 0)
+        );
+
+        // SOURCE_DATE_EPOCH wins, but only if it parses
+        let both = [
+            ("SOURCE_DATE_EPOCH", "1700000000"),
+            ("NIODOO_REPORT_TIMESTAMP", "0"),
+        ];
+        assert_eq!(pinned(&both), DateTime::from_timestamp(1_700_000_000, 0));
+        let bad_epoch = [
+            ("SOURCE_DATE_EPOCH", "yesterday"),
+            ("NIODOO_REPORT_TIMESTAMP", "0"),
+        ];
+        assert_eq!(pinned(&bad_epoch), DateTime::from_timestamp(0, 0));
+        assert_eq!(pinned(&[]), None);
+    }
 }
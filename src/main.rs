@@ -2,17 +2,178 @@
 // Licensed under the MIT License - See LICENSE file for details
 // Attribution required for all derivative works
 
-use anyhow::Result;
-use bullshitdetector::{scan_code, DetectConfig, BullshitAlert};
+use anyhow::{bail, Result};
+use bullshitdetector::baseline::{fingerprint, Baseline, BaselineFormat};
+use bullshitdetector::blame::{blame_file, retain_since};
+use bullshitdetector::config::{ConfigFile, ConfigSource, ConfigTrace, Gate};
+use bullshitdetector::diff::{diff_results, load_results, DiffReport};
+use bullshitdetector::files::{
+    find_gitignored_rust_files, find_rust_files_with, normalize_path, read_scannable, PathMode,
+};
+use bullshitdetector::lsp;
+use bullshitdetector::magic_numbers::{
+    is_path_whitelisted, scan_for_magic_numbers, MagicNumberConfig,
+};
+use bullshitdetector::output::{
+    format_github_annotation, render_junit, render_sarif, render_template, validate_template,
+    write_csv, write_jsonl,
+};
+use bullshitdetector::progress::Progress;
+use bullshitdetector::report::{
+    rank_files, summarize_suggestions, write_report_file, FileScore, ScanReport, SkipReason,
+    SkippedFile,
+};
+use bullshitdetector::workspace::discover_workspace;
+use bullshitdetector::{
+    scan_file, sort_alerts, BullshitAlert, BullshitType, Category, DetectConfig, DetectError,
+    SeverityLevel,
+};
+use chrono::{DateTime, Duration, Utc};
 use clap::{Parser, Subcommand};
-use glob::glob;
+use std::collections::{BTreeSet, HashSet};
+use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Read};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process;
 
 #[derive(Parser)]
 #[command(name = "bullshitdetector")]
 #[command(about = "Fast detector for magic numbers and code smells", long_about = None)]
 struct Cli {
+    /// Config file (defaults to .bullshitdetector.toml or bsd.toml in the current directory)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Only report alerts on lines committed in the last N days (uses git blame)
+    #[arg(long, global = true, value_name = "N")]
+    since_days: Option<u64>,
+
+    /// Print the effective configuration and where each value came from, then exit
+    #[arg(long, global = true)]
+    config_dump: bool,
+
+    /// Report file paths relative to the scanned path, or absolute
+    #[arg(
+        long,
+        global = true,
+        value_name = "relative|absolute",
+        default_value = "relative"
+    )]
+    paths: PathMode,
+
+    /// Report relative paths against DIR (e.g. `.` for the current directory)
+    /// rather than the scanned path
+    #[arg(long, global = true, value_name = "DIR")]
+    root: Option<PathBuf>,
+
+    /// Also write aggregate counts (total, by type, by severity, files scanned) as JSON here
+    #[arg(long, global = true, value_name = "PATH")]
+    report_file: Option<PathBuf>,
+
+    /// Line template for `--output template`, e.g. "{file}:{line}:{col}: {type} {why}"
+    /// (placeholders: file line col type category confidence severity why suggestion snippet;
+    /// `{{`/`}}` for literal braces). Implies `--output template`.
+    #[arg(long, global = true, value_name = "TEMPLATE")]
+    template: Option<String>,
+
+    /// Don't report alerts accepted in this baseline file
+    #[arg(long, global = true, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Add every current alert to this baseline file (created if missing), report
+    /// entries no longer found in the code, and only show alerts new to it
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "baseline")]
+    update_baseline: Option<PathBuf>,
+
+    /// How `--update-baseline` writes the file: JSON, or one fingerprint per line
+    /// (either is read back)
+    #[arg(
+        long,
+        global = true,
+        value_name = "json|hashes",
+        default_value = "json"
+    )]
+    baseline_format: BaselineFormat,
+
+    /// Drop the alert with this fingerprint (as shown by `--show-fingerprints`);
+    /// repeat for several
+    #[arg(long, global = true, value_name = "HASH")]
+    ignore_fingerprint: Vec<String>,
+
+    /// Also report this opt-in type (e.g. MixedErrorHandling or early-return); repeat for several
+    #[arg(long, global = true, value_name = "TYPE")]
+    enable: Vec<BullshitType>,
+
+    /// Instead of listing alerts, rank the N worst files by total severity
+    /// (weighted by confidence)
+    #[arg(long, global = true, value_name = "N")]
+    top_files: Option<usize>,
+
+    /// Instead of listing alerts, list each distinct suggestion with how many
+    /// alerts it would fix, most common first
+    #[arg(long, global = true, conflicts_with = "top_files")]
+    suggestions_summary: bool,
+
+    /// Show each alert's fingerprint in text output, for `--ignore-fingerprint`
+    #[arg(long, global = true)]
+    show_fingerprints: bool,
+
+    /// Exit with status 1 if any reported alert is at least this severe
+    /// (`[gating]` in the config file can set this per path)
+    #[arg(long, global = true, value_name = "critical|high|medium")]
+    fail_on: Option<SeverityLevel>,
+
+    /// Stop scanning at the first file with an alert that fails `--fail-on`
+    /// (or `[gating]`) and exit with status 1. Can't update a baseline, which
+    /// needs the whole tree scanned.
+    #[arg(long, global = true, conflicts_with = "update_baseline")]
+    fail_fast: bool,
+
+    /// Show how each alert's confidence was scored in text output
+    /// (e.g. "base 0.50, 1 threshold keyword +0.15")
+    #[arg(long, global = true, visible_alias = "verbose")]
+    explain: bool,
+
+    /// Don't show scan progress on stderr (it is also hidden when stderr isn't a terminal)
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Scan files excluded by .gitignore, .git/info/exclude and the global git excludes too
+    #[arg(long, global = true)]
+    no_gitignore: bool,
+
+    /// Only report alerts in this category (security, correctness, perf,
+    /// maintainability or style); repeat for several
+    #[arg(long, global = true, value_name = "CATEGORY")]
+    only_category: Vec<Category>,
+
+    /// Scan only the newline-separated paths in this file (`-` for stdin) instead of
+    /// walking PATH; reported paths are still relative to PATH (or `--root`)
+    #[arg(long, global = true, value_name = "FILE")]
+    files_from: Option<PathBuf>,
+
+    /// Only report alerts on lines START to END (inclusive, e.g. `100:140`);
+    /// PATH must be a single file
+    #[arg(long, global = true, value_name = "START:END", value_parser = parse_line_range)]
+    lines: Option<RangeInclusive<usize>>,
+
+    /// List the files scanned, and those skipped with the reason (whitelisted,
+    /// gitignored, non-utf8, timeout or generated), in `--output json` and `--report-file`
+    #[arg(long, global = true)]
+    list_scanned: bool,
+
+    /// Scan files with a generated-code header (`// @generated`,
+    /// `// Code generated by ...`) rather than skipping them
+    #[arg(long, global = true)]
+    include_generated: bool,
+
+    /// Print what each exit status means and what would make this run fail,
+    /// then exit without scanning
+    #[arg(long, global = true)]
+    explain_exit: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -24,13 +185,26 @@ enum Commands {
         /// Directory or file to scan
         path: PathBuf,
         
-        /// Output format (text or json)
-        #[arg(short, long, default_value = "text")]
-        output: String,
+        /// Output format (text, json, jsonl, csv, junit, sarif, github or template; defaults to github under GitHub Actions)
+        #[arg(short, long)]
+        output: Option<String>,
         
         /// Confidence threshold (0.0-1.0)
         #[arg(short, long, default_value = "0.618")]
         threshold: f32,
+
+        /// Drop alerts whose severity is below this (0.0-1.0)
+        #[arg(long, value_name = "SEVERITY")]
+        min_severity: Option<f32>,
+
+        /// Treat PATH as a Cargo workspace and scan each member crate
+        #[arg(long)]
+        workspace: bool,
+
+        /// Don't whitelist the built-in values (0, 1, 2, 100, 1000, 1e-10);
+        /// only `whitelist_values` from the config file or environment apply
+        #[arg(long)]
+        no_default_whitelist: bool,
     },
     
     /// Scan code for all code smells
@@ -38,113 +212,849 @@ enum Commands {
         /// Directory or file to scan
         path: PathBuf,
         
-        /// Output format (text or json)
-        #[arg(short, long, default_value = "text")]
-        output: String,
+        /// Output format (text, json, jsonl, csv, junit, sarif, github or template; defaults to github under GitHub Actions)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Drop alerts whose severity is below this (0.0-1.0)
+        #[arg(long, value_name = "SEVERITY")]
+        min_severity: Option<f32>,
+
+        /// Treat PATH as a Cargo workspace and scan each member crate
+        #[arg(long)]
+        workspace: bool,
     },
+
+    /// Compare two saved `--output json`/`jsonl` results: added, removed and unchanged alerts
+    Diff {
+        /// Earlier scan results
+        old: PathBuf,
+
+        /// Later scan results
+        new: PathBuf,
+    },
+
+    /// Run as a language server over stdio, publishing alerts as diagnostics
+    Lsp,
+}
+
+/// The exit status, for CI scripts to act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitCode {
+    /// The run finished and no alert failed the gate
+    Clean = 0,
+    /// An alert failed `--fail-on` or a `[gating]` rule
+    GateFailed = 1,
+    /// The run couldn't finish: bad flags, config, baseline or paths
+    Error = 2,
+}
+
+impl ExitCode {
+    const ALL: [ExitCode; 3] = [ExitCode::Clean, ExitCode::GateFailed, ExitCode::Error];
+
+    fn name(self) -> &'static str {
+        match self {
+            ExitCode::Clean => "clean",
+            ExitCode::GateFailed => "gate-failed",
+            ExitCode::Error => "error",
+        }
+    }
+
+    fn meaning(self) -> &'static str {
+        match self {
+            ExitCode::Clean => "the run finished and no alert failed the gate",
+            ExitCode::GateFailed => "an alert failed --fail-on or a [gating] rule",
+            ExitCode::Error => "the run couldn't finish (bad flags, config, baseline or path)",
+        }
+    }
+}
+
+impl From<ExitCode> for process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        process::ExitCode::from(code as u8)
+    }
+}
+
+fn main() -> process::ExitCode {
+    match run() {
+        Ok(code) => code.into(),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::Error.into()
+        }
+    }
 }
 
-fn main() -> Result<()> {
+fn run() -> Result<ExitCode> {
     let cli = Cli::parse();
+    let no_default_whitelist = matches!(
+        cli.command,
+        Commands::ScanMagic {
+            no_default_whitelist: true,
+            ..
+        }
+    );
+    let (mut detect_config, mut magic_config, mut trace) =
+        load_config(cli.config.as_deref(), no_default_whitelist)?;
+    match &cli.command {
+        Commands::ScanMagic {
+            threshold,
+            min_severity,
+            ..
+        } => {
+            magic_config.confidence_threshold = *threshold;
+            if let Some(min_severity) = min_severity {
+                magic_config.min_severity = min_severity.clamp(0.0, 1.0);
+            }
+            trace.record(ConfigSource::Flag, &detect_config, &magic_config);
+        }
+        Commands::Scan {
+            min_severity: Some(min_severity),
+            ..
+        } => {
+            detect_config.min_severity = min_severity.clamp(0.0, 1.0);
+            trace.record(ConfigSource::Flag, &detect_config, &magic_config);
+        }
+        _ => {}
+    }
+    if !cli.enable.is_empty() {
+        detect_config
+            .enabled_types
+            .extend(cli.enable.iter().cloned());
+        trace.record(ConfigSource::Flag, &detect_config, &magic_config);
+    }
+    if cli.include_generated {
+        detect_config.include_generated = true;
+        trace.record(ConfigSource::Flag, &detect_config, &magic_config);
+    }
+
+    if cli.config_dump {
+        print!("{}", trace.to_toml());
+        return Ok(ExitCode::Clean);
+    }
+
+    if let Some(template) = &cli.template {
+        validate_template(template)?;
+    }
+    let gate = gate_for(&cli, &detect_config);
+    if cli.fail_fast && gate.fail_on.is_none() && gate.rules.is_empty() {
+        bail!("--fail-fast needs --fail-on or a [gating] section in the config file");
+    }
+    if cli.explain_exit {
+        print_exit_codes(&gate);
+        return Ok(ExitCode::Clean);
+    }
 
-    match cli.command {
-        Commands::ScanMagic { path, output, threshold } => {
-            scan_magic_numbers(path, &output, threshold)?;
+    let failed = match &cli.command {
+        Commands::ScanMagic {
+            path,
+            output,
+            workspace,
+            ..
+        } => {
+            let opts = scan_options(&cli, path, output.clone(), *workspace, &detect_config)?;
+            if *workspace {
+                scan_workspace_magic(&opts, &magic_config)?
+            } else {
+                scan_magic_numbers(&opts, &magic_config)?
+            }
+        }
+        Commands::Scan {
+            path,
+            output,
+            workspace,
+            ..
+        } => {
+            let opts = scan_options(&cli, path, output.clone(), *workspace, &detect_config)?;
+            if *workspace {
+                scan_workspace_all(&opts, &detect_config)?
+            } else {
+                scan_all(&opts, &detect_config)?
+            }
+        }
+        Commands::Diff { old, new } => {
+            let report = diff_results(&load_results(old)?, &load_results(new)?);
+            print_diff(&report, cli.show_fingerprints);
+            false
         }
-        Commands::Scan { path, output } => {
-            scan_all(path, &output)?;
+        Commands::Lsp => {
+            lsp::run(
+                &mut io::stdin().lock(),
+                &mut io::stdout().lock(),
+                &detect_config,
+            )?;
+            false
         }
+    };
+
+    Ok(if failed {
+        ExitCode::GateFailed
+    } else {
+        ExitCode::Clean
+    })
+}
+
+/// Which alerts fail the run: `[gating]` rules, then `--fail-on`
+fn gate_for(cli: &Cli, config: &DetectConfig) -> Gate {
+    Gate {
+        fail_on: cli.fail_on,
+        rules: config.gating.clone(),
     }
+}
 
-    Ok(())
+/// The options `scan` and `scan-magic` share, rejecting flag combinations
+/// that can't work for scanning `path`
+fn scan_options(
+    cli: &Cli,
+    path: &Path,
+    output: Option<String>,
+    workspace: bool,
+    config: &DetectConfig,
+) -> Result<ScanOptions> {
+    let opts = ScanOptions {
+        format: resolve_output_format(output, cli.template.is_some())?,
+        since: cli.since_days.map(since_cutoff).transpose()?,
+        root: path.to_path_buf(),
+        path_mode: cli.paths,
+        report_root: cli.root.clone(),
+        quiet: cli.quiet,
+        report_file: cli.report_file.clone(),
+        template: cli.template.clone(),
+        baseline: load_baseline(cli)?,
+        update_baseline: cli.update_baseline.clone(),
+        baseline_format: cli.baseline_format,
+        ignore_fingerprints: cli
+            .ignore_fingerprint
+            .iter()
+            .map(|hash| hash.to_ascii_lowercase())
+            .collect(),
+        show_fingerprints: cli.show_fingerprints,
+        explain: cli.explain,
+        top_files: cli.top_files,
+        suggestions_summary: cli.suggestions_summary,
+        files_from: cli.files_from.clone(),
+        only_category: cli.only_category.clone(),
+        respect_gitignore: !cli.no_gitignore,
+        lines: cli.lines.clone(),
+        list_scanned: cli.list_scanned,
+        fail_fast: cli.fail_fast,
+        include_generated: config.include_generated,
+        gate: gate_for(cli, config),
+    };
+
+    if workspace && opts.files_from.is_some() {
+        bail!("--files-from can't be combined with --workspace");
+    }
+    let single_file = !workspace && opts.files_from.is_none() && opts.root.is_file();
+    if opts.lines.is_some() && !single_file {
+        bail!("--lines only applies when scanning a single file");
+    }
+
+    Ok(opts)
+}
+
+/// The `--explain-exit` table, then which alerts would fail this run
+fn print_exit_codes(gate: &Gate) {
+    println!("Exit codes:");
+    for code in ExitCode::ALL {
+        println!("  {}  {:<12} {}", code as u8, code.name(), code.meaning());
+    }
+    println!();
+
+    let level = |fail_on: Option<SeverityLevel>| match fail_on {
+        Some(level) => level.to_string().to_lowercase(),
+        None => "never".to_string(),
+    };
+    if gate.fail_on.is_none() && gate.rules.iter().all(|rule| rule.fail_on.is_none()) {
+        println!("This run never exits 1: no --fail-on or [gating] severity is set");
+        return;
+    }
+    println!("This run exits 1 for an alert at this severity or worse:");
+    for rule in &gate.rules {
+        println!("  {:<20} {}", rule.path.glob, level(rule.fail_on));
+    }
+    let elsewhere = if gate.rules.is_empty() {
+        "anywhere"
+    } else {
+        "elsewhere"
+    };
+    println!("  {:<20} {}", elsewhere, level(gate.fail_on));
+}
+
+/// How a scan's results are filtered and reported
+struct ScanOptions {
+    /// Output format (text, json, jsonl, csv, junit, sarif, github or template)
+    format: String,
+    /// Line template for the `template` format
+    template: Option<String>,
+    /// Drop alerts on lines committed before this
+    since: Option<DateTime<Utc>>,
+    /// The path being scanned; reported paths are relative to it by default
+    root: PathBuf,
+    path_mode: PathMode,
+    /// Base for relative reported paths, when not `root`
+    report_root: Option<PathBuf>,
+    /// Hide the progress counter
+    quiet: bool,
+    /// Where to write the aggregate `ScanReport`, if anywhere
+    report_file: Option<PathBuf>,
+    /// Accepted alerts, which aren't reported
+    baseline: Option<Baseline>,
+    /// Where to write the baseline back with the current alerts added
+    update_baseline: Option<PathBuf>,
+    /// Format the `--update-baseline` file is written in
+    baseline_format: BaselineFormat,
+    /// Fingerprints of individual alerts to drop
+    ignore_fingerprints: HashSet<String>,
+    /// Print fingerprints with text output
+    show_fingerprints: bool,
+    /// Print score breakdowns with text output
+    explain: bool,
+    /// Print a table of this many worst files instead of the alerts
+    top_files: Option<usize>,
+    /// Print the suggestion checklist instead of the alerts
+    suggestions_summary: bool,
+    /// File listing the paths to scan (`-` for stdin), replacing the walk of `root`
+    files_from: Option<PathBuf>,
+    /// Categories to report; empty reports all
+    only_category: Vec<Category>,
+    /// Skip gitignored files when walking `root`
+    respect_gitignore: bool,
+    /// Lines of the single scanned file to report alerts on
+    lines: Option<RangeInclusive<usize>>,
+    /// Record which files were scanned and which skipped
+    list_scanned: bool,
+    /// Which reported alerts fail the run
+    gate: Gate,
+    /// Stop scanning once an alert fails the gate
+    fail_fast: bool,
+    /// Scan generated files rather than skipping them
+    include_generated: bool,
+}
+
+impl ScanOptions {
+    /// Whether a summary replaces the alert list in the output
+    fn summary_view(&self) -> bool {
+        self.top_files.is_some() || self.suggestions_summary
+    }
+}
+
+/// Everything a scan produced, ready to be reported
+#[derive(Default)]
+struct Collected {
+    /// Alerts not accepted by the baseline, sorted
+    alerts: Vec<BullshitAlert>,
+    /// Files actually scanned (skipped files don't count)
+    scanned: usize,
+    /// Fingerprints of every alert, accepted or not (only tracked with a baseline)
+    fingerprints: BTreeSet<String>,
+    /// Reported paths of the scanned files (only tracked with `--list-scanned`)
+    scanned_files: Vec<String>,
+    /// Files left out and why (only tracked with `--list-scanned`)
+    skipped: Vec<SkippedFile>,
+    /// `--fail-fast` stopped the scan before the last file
+    stopped: bool,
+}
+
+/// Parse `--lines START:END` into an inclusive range of line numbers
+fn parse_line_range(value: &str) -> Result<RangeInclusive<usize>, String> {
+    let (start, end) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected START:END, got `{}`", value))?;
+    let parse = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .map_err(|_| format!("`{}` is not a line number", n))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start > end {
+        return Err(format!("range {}:{} ends before it starts", start, end));
+    }
+    Ok(start..=end)
+}
+
+/// The commit time `--since-days` keeps alerts after, rejecting spans too
+/// far back to represent
+fn since_cutoff(days: u64) -> Result<DateTime<Utc>> {
+    i64::try_from(days)
+        .ok()
+        .and_then(Duration::try_days)
+        .and_then(|span| Utc::now().checked_sub_signed(span))
+        .ok_or_else(|| anyhow::anyhow!("--since-days {} reaches too far back", days))
+}
+
+/// The baseline to filter against: `--update-baseline`'s file (empty if it
+/// doesn't exist yet) or `--baseline`'s
+fn load_baseline(cli: &Cli) -> Result<Option<Baseline>> {
+    if let Some(path) = &cli.update_baseline {
+        if !path.exists() {
+            return Ok(Some(Baseline::default()));
+        }
+        return Ok(Some(Baseline::load(path)?));
+    }
+    match &cli.baseline {
+        Some(path) => Ok(Some(Baseline::load(path)?)),
+        None => Ok(None),
+    }
 }
 
-fn scan_magic_numbers(path: PathBuf, output_format: &str, threshold: f32) -> Result<()> {
+/// The files to scan: those listed by `--files-from`, or every Rust file under the root
+fn files_to_scan(opts: &ScanOptions) -> Result<Vec<PathBuf>> {
+    let Some(list) = &opts.files_from else {
+        return Ok(find_rust_files_with(&opts.root, opts.respect_gitignore)?);
+    };
+    let contents = if list == Path::new("-") {
+        let mut buffer = Vec::new();
+        io::stdin().read_to_end(&mut buffer)?;
+        buffer
+    } else {
+        fs::read(list)?
+    };
+
+    let mut files = Vec::new();
+    for (index, line) in contents.split(|&b| b == b'\n').enumerate() {
+        let Ok(line) = std::str::from_utf8(line) else {
+            eprintln!(
+                "warning: skipping {} line {}: path is not valid UTF-8",
+                list.display(),
+                index + 1
+            );
+            continue;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let path = PathBuf::from(line);
+        if path.is_file() {
+            files.push(path);
+        } else {
+            eprintln!("warning: skipping {}: no such file", path.display());
+        }
+    }
+    Ok(files)
+}
+
+fn scan_magic_numbers(opts: &ScanOptions, config: &MagicNumberConfig) -> Result<bool> {
+    let files = files_to_scan(opts)?;
+    let whitelisted = |file_path: &Path| is_path_whitelisted(&file_path.to_string_lossy(), config);
+    run_scan(&files, opts, whitelisted, |file_path| {
+        let code = read_scannable(file_path, opts.include_generated)?;
+        Ok(scan_for_magic_numbers(
+            &code,
+            &file_path.to_string_lossy(),
+            config,
+        )?)
+    })
+}
+
+/// Resolve the detection and magic number configs: defaults, then the config
+/// file, then the environment - tracking where each value came from
+///
+/// `--no-default-whitelist` is applied before the file and environment, so
+/// the whitelist values they list are kept.
+fn load_config(
+    explicit: Option<&Path>,
+    no_default_whitelist: bool,
+) -> Result<(DetectConfig, MagicNumberConfig, ConfigTrace)> {
     let mut config = DetectConfig::default();
-    config.confidence_threshold = threshold;
+    let mut magic_config = MagicNumberConfig::default();
+    let mut trace = ConfigTrace::new(&config, &magic_config);
+    if no_default_whitelist {
+        magic_config.clear_default_whitelist();
+        trace.record(ConfigSource::Flag, &config, &magic_config);
+    }
+
+    let config_path = match explicit {
+        Some(path) => Some(path.to_path_buf()),
+        None => ConfigFile::discover(&env::current_dir()?),
+    };
+    if let Some(path) = config_path {
+        let file = ConfigFile::load(&path)?;
+        file.apply_to(&mut config)?;
+        file.apply_magic_to(&mut magic_config);
+        trace.record(ConfigSource::File, &config, &magic_config);
+    }
+
+    config.apply_env();
+    magic_config.apply_env();
+    trace.record(ConfigSource::Env, &config, &magic_config);
+
+    Ok((config, magic_config, trace))
+}
+
+fn scan_all(opts: &ScanOptions, config: &DetectConfig) -> Result<bool> {
+    let files = files_to_scan(opts)?;
+    run_scan(
+        &files,
+        opts,
+        |_| false,
+        |file_path| Ok(scan_file(file_path, config)?),
+    )
+}
+
+/// Scan each file in turn, streaming JSON Lines as results come in and
+/// collecting everything else for a single report at the end
+fn run_scan<W, F>(files: &[PathBuf], opts: &ScanOptions, whitelisted: W, scan: F) -> Result<bool>
+where
+    W: Fn(&Path) -> bool,
+    F: Fn(&Path) -> Result<Vec<BullshitAlert>>,
+{
+    let mut collected = collect_alerts(files, opts, whitelisted, scan)?;
+    list_gitignored(opts, &mut collected)?;
+
+    if !print_summary_view(&collected.alerts, opts) && opts.format != "jsonl" {
+        if opts.list_scanned && opts.format == "json" {
+            print_json_listing(
+                serde_json::json!({ "alerts": collected.alerts }),
+                &collected,
+            )?;
+        } else {
+            output_results(&collected.alerts, opts)?;
+        }
+    }
+
+    finish_scan(opts, collected)
+}
 
-    let files = find_rust_files(&path)?;
-    let mut total_alerts = Vec::new();
+/// Scan each file, streaming JSON Lines straight out as it goes.
+/// With `since`, alerts on lines committed before it are dropped, and
+/// baselined alerts are dropped too. `whitelisted` files are only listed as
+/// skipped, since the scan mostly passes over them.
+fn collect_alerts<W, F>(
+    files: &[PathBuf],
+    opts: &ScanOptions,
+    whitelisted: W,
+    scan: F,
+) -> Result<Collected>
+where
+    W: Fn(&Path) -> bool,
+    F: Fn(&Path) -> Result<Vec<BullshitAlert>>,
+{
+    let mut collected = Collected::default();
+    let stdout = io::stdout();
+    let mut progress = Progress::stderr(files.len(), opts.quiet);
+
+    let report_root = opts.report_root.as_deref().unwrap_or(&opts.root);
 
     for file_path in files {
-        let code = fs::read_to_string(&file_path)?;
-        let mut alerts = scan_code(&code, &config)?;
-        
-        // Filter for magic numbers only
-        alerts.retain(|a| matches!(a.issue_type, bullshitdetector::BullshitType::MagicNumber | bullshitdetector::BullshitType::HardcodedThreshold));
-        
+        progress.tick(file_path);
+        let reported_path = normalize_path(file_path, report_root, opts.path_mode);
+        let mut alerts = match scan(file_path) {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                let reason = match e.downcast_ref() {
+                    Some(DetectError::NonUtf8 { .. }) => SkipReason::NonUtf8,
+                    Some(DetectError::Timeout { .. }) => SkipReason::Timeout,
+                    Some(DetectError::Generated { .. }) => SkipReason::Generated,
+                    _ => return Err(e),
+                };
+                // Generated files are skipped by design, not worth a warning
+                if reason != SkipReason::Generated {
+                    progress.clear();
+                    eprintln!("warning: skipping {}: {}", file_path.display(), e);
+                }
+                if opts.list_scanned {
+                    collected.skipped.push(SkippedFile {
+                        path: reported_path,
+                        reason,
+                    });
+                }
+                continue;
+            }
+        };
+        collected.scanned += 1;
+        if opts.list_scanned {
+            if whitelisted(file_path) {
+                collected.skipped.push(SkippedFile {
+                    path: reported_path.clone(),
+                    reason: SkipReason::Whitelisted,
+                });
+            } else {
+                collected.scanned_files.push(reported_path.clone());
+            }
+        }
+
+        if let Some(cutoff) = opts.since {
+            if !alerts.is_empty() {
+                retain_since(&mut alerts, &blame_file(file_path)?, cutoff);
+            }
+        }
+
         for alert in &mut alerts {
-            alert.context_snippet = format!("{}:{}", file_path.display(), alert.context_snippet);
+            alert.file_path = Some(reported_path.clone());
+        }
+
+        if !opts.ignore_fingerprints.is_empty() {
+            alerts.retain(|a| !opts.ignore_fingerprints.contains(&fingerprint(a)));
+        }
+        if let Some(baseline) = &opts.baseline {
+            collected
+                .fingerprints
+                .extend(alerts.iter().map(fingerprint));
+            baseline.suppress(&mut alerts);
+        }
+        if !opts.only_category.is_empty() {
+            alerts.retain(|a| opts.only_category.contains(&a.issue_type.category()));
+        }
+        if let Some(lines) = &opts.lines {
+            alerts.retain(|a| lines.contains(&a.location.line));
+        }
+
+        if opts.format == "jsonl" && !opts.summary_view() {
+            write_jsonl(&mut stdout.lock(), &alerts)?;
+        }
+        let failed = opts.fail_fast && alerts.iter().any(|alert| opts.gate.fails(alert));
+        collected.alerts.extend(alerts);
+        if failed {
+            collected.stopped = true;
+            break;
         }
-        
-        total_alerts.extend(alerts);
     }
+    progress.clear();
+
+    sort_alerts(&mut collected.alerts);
+    Ok(collected)
+}
 
-    output_results(&total_alerts, output_format)?;
+/// With `--list-scanned`, add the files the walk of the root left out
+/// because git ignores them to the skipped list
+fn list_gitignored(opts: &ScanOptions, collected: &mut Collected) -> Result<()> {
+    if !opts.list_scanned || !opts.respect_gitignore || opts.files_from.is_some() {
+        return Ok(());
+    }
 
+    let report_root = opts.report_root.as_deref().unwrap_or(&opts.root);
+    for file_path in find_gitignored_rust_files(&opts.root)? {
+        collected.skipped.push(SkippedFile {
+            path: normalize_path(&file_path, report_root, opts.path_mode),
+            reason: SkipReason::Gitignored,
+        });
+    }
     Ok(())
 }
 
-fn scan_all(path: PathBuf, output_format: &str) -> Result<()> {
-    let config = DetectConfig::default();
-    let files = find_rust_files(&path)?;
-    let mut total_alerts = Vec::new();
+/// Print `output` (a JSON object) with the `--list-scanned` file lists added
+fn print_json_listing(mut output: serde_json::Value, collected: &Collected) -> Result<()> {
+    output["scanned"] = serde_json::to_value(&collected.scanned_files)?;
+    output["skipped"] = serde_json::to_value(&collected.skipped)?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
 
-    for file_path in files {
-        let code = fs::read_to_string(&file_path)?;
-        let mut alerts = scan_code(&code, &config)?;
-        
-        for alert in &mut alerts {
-            alert.context_snippet = format!("{}:{}", file_path.display(), alert.context_snippet);
+/// Write the `--report-file` summary and the `--update-baseline` file, if asked
+/// for, and return whether any alert fails the gate
+fn finish_scan(opts: &ScanOptions, collected: Collected) -> Result<bool> {
+    if let Some(path) = &opts.report_file {
+        let report = ScanReport {
+            scanned: collected.scanned_files,
+            skipped: collected.skipped,
+            ..ScanReport::from_alerts(&collected.alerts, collected.scanned)
+        };
+        write_report_file(path, &report)?;
+    }
+
+    if let (Some(path), Some(baseline)) = (&opts.update_baseline, &opts.baseline) {
+        let mut baseline = baseline.clone();
+        let update = baseline.update(&collected.fingerprints);
+        baseline.save_as(path, opts.baseline_format)?;
+
+        eprintln!(
+            "baseline {}: {} added, {} kept, {} stale",
+            path.display(),
+            update.added.len(),
+            update.kept,
+            update.stale.len()
+        );
+        for stale in &update.stale {
+            eprintln!("  stale: {}", stale);
         }
-        
-        total_alerts.extend(alerts);
     }
 
-    output_results(&total_alerts, output_format)?;
+    Ok(collected.alerts.iter().any(|alert| opts.gate.fails(alert)))
+}
 
-    Ok(())
+fn scan_workspace_all(opts: &ScanOptions, config: &DetectConfig) -> Result<bool> {
+    let mut groups = Vec::new();
+    let mut total = Collected::default();
+
+    for member in discover_workspace(&opts.root)? {
+        let member_config = member.detect_config(config)?;
+        let files = find_rust_files_with(&member.src, opts.respect_gitignore)?;
+        let collected = collect_alerts(
+            &files,
+            opts,
+            |_| false,
+            |file_path| Ok(scan_file(file_path, &member_config)?),
+        )?;
+        total.scanned += collected.scanned;
+        total.fingerprints.extend(collected.fingerprints);
+        total.scanned_files.extend(collected.scanned_files);
+        total.skipped.extend(collected.skipped);
+        groups.push((member.name, collected.alerts));
+        if collected.stopped {
+            break;
+        }
+    }
+    list_gitignored(opts, &mut total)?;
+
+    total.alerts = groups
+        .iter()
+        .flat_map(|(_, alerts)| alerts.clone())
+        .collect();
+    if !print_summary_view(&total.alerts, opts) {
+        output_grouped(&groups, &total, opts)?;
+    }
+    finish_scan(opts, total)
 }
 
-fn find_rust_files(path: &PathBuf) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
+fn scan_workspace_magic(opts: &ScanOptions, config: &MagicNumberConfig) -> Result<bool> {
+    let mut groups = Vec::new();
+    let mut total = Collected::default();
 
-    if path.is_file() {
-        files.push(path.clone());
-    } else if path.is_dir() {
-        let pattern = format!("{}/**/*.rs", path.display());
-        for entry in glob(&pattern)? {
-            if let Ok(file_path) = entry {
-                // Skip test files and target directory
-                let path_str = file_path.to_string_lossy();
-                if !path_str.contains("/target/") && !path_str.contains("/tests/") {
-                    files.push(file_path);
-                }
+    for member in discover_workspace(&opts.root)? {
+        // --threshold always wins over member files
+        let member_config = MagicNumberConfig {
+            confidence_threshold: config.confidence_threshold,
+            ..member.magic_config(config)?
+        };
+        let files = find_rust_files_with(&member.src, opts.respect_gitignore)?;
+        let whitelisted =
+            |file_path: &Path| is_path_whitelisted(&file_path.to_string_lossy(), &member_config);
+        let collected = collect_alerts(&files, opts, whitelisted, |file_path| {
+            let code = read_scannable(file_path, opts.include_generated)?;
+            Ok(scan_for_magic_numbers(
+                &code,
+                &file_path.to_string_lossy(),
+                &member_config,
+            )?)
+        })?;
+        total.scanned += collected.scanned;
+        total.fingerprints.extend(collected.fingerprints);
+        total.scanned_files.extend(collected.scanned_files);
+        total.skipped.extend(collected.skipped);
+        groups.push((member.name, collected.alerts));
+        if collected.stopped {
+            break;
+        }
+    }
+    list_gitignored(opts, &mut total)?;
+
+    total.alerts = groups
+        .iter()
+        .flat_map(|(_, alerts)| alerts.clone())
+        .collect();
+    if !print_summary_view(&total.alerts, opts) {
+        output_grouped(&groups, &total, opts)?;
+    }
+    finish_scan(opts, total)
+}
+
+/// Output workspace results grouped by crate
+fn output_grouped(
+    groups: &[(String, Vec<BullshitAlert>)],
+    total: &Collected,
+    opts: &ScanOptions,
+) -> Result<()> {
+    match opts.format.as_str() {
+        // Already streamed per file
+        "jsonl" => {}
+        "json" => {
+            let grouped: Vec<_> = groups
+                .iter()
+                .map(|(name, alerts)| serde_json::json!({ "crate": name, "alerts": alerts }))
+                .collect();
+            if opts.list_scanned {
+                print_json_listing(serde_json::json!({ "crates": grouped }), total)?;
+            } else {
+                println!("{}", serde_json::to_string_pretty(&grouped)?);
+            }
+        }
+        // One table with a single header row, or one XML or SARIF document
+        "csv" | "junit" | "sarif" => {
+            let alerts: Vec<BullshitAlert> = groups
+                .iter()
+                .flat_map(|(_, alerts)| alerts.clone())
+                .collect();
+            output_results(&alerts, opts)?;
+        }
+        "github" | "template" => {
+            for (_, alerts) in groups {
+                output_results(alerts, opts)?;
+            }
+        }
+        _ => {
+            for (name, alerts) in groups {
+                println!("\n📦 {}", name);
+                output_results(alerts, opts)?;
             }
         }
     }
 
-    Ok(files)
+    Ok(())
 }
 
-fn output_results(alerts: &[BullshitAlert], format: &str) -> Result<()> {
+/// Pick the output format: `template` if a template was given, else
+/// annotations when running under GitHub Actions
+fn resolve_output_format(requested: Option<String>, has_template: bool) -> Result<String> {
+    let format = requested.unwrap_or_else(|| {
+        if has_template {
+            "template".to_string()
+        } else if env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true") {
+            "github".to_string()
+        } else {
+            "text".to_string()
+        }
+    });
+    if format == "template" && !has_template {
+        anyhow::bail!("--output template needs a --template");
+    }
+    Ok(format)
+}
+
+fn output_results(alerts: &[BullshitAlert], opts: &ScanOptions) -> Result<()> {
+    let format = opts.format.as_str();
     if format == "json" {
         let json = serde_json::to_string_pretty(alerts)?;
         println!("{}", json);
+    } else if format == "github" {
+        for alert in alerts {
+            println!("{}", format_github_annotation(alert));
+        }
+    } else if format == "csv" {
+        write_csv(&mut io::stdout().lock(), alerts)?;
+    } else if format == "junit" {
+        print!("{}", render_junit(alerts));
+    } else if format == "sarif" {
+        println!("{}", render_sarif(alerts));
+    } else if let (Some(template), "template") = (&opts.template, format) {
+        for alert in alerts {
+            println!("{}", render_template(template, alert));
+        }
     } else {
         // Text output
         println!("\n🚨 Bullshitdetector Results\n");
         println!("Found {} issues:\n", alerts.len());
 
         // Group by severity
-        let critical: Vec<_> = alerts.iter().filter(|a| a.severity >= 0.9).collect();
-        let high: Vec<_> = alerts.iter().filter(|a| a.severity >= 0.75 && a.severity < 0.9).collect();
-        let medium: Vec<_> = alerts.iter().filter(|a| a.severity < 0.75).collect();
+        let critical: Vec<_> = alerts
+            .iter()
+            .filter(|a| a.severity_level() == SeverityLevel::Critical)
+            .collect();
+        let high: Vec<_> = alerts
+            .iter()
+            .filter(|a| a.severity_level() == SeverityLevel::High)
+            .collect();
+        let medium: Vec<_> = alerts
+            .iter()
+            .filter(|a| a.severity_level() == SeverityLevel::Medium)
+            .collect();
 
         if !critical.is_empty() {
             println!("🔴 CRITICAL ({} issues):", critical.len());
             for alert in critical {
-                print_alert(alert);
+                print_alert(alert, opts.show_fingerprints, opts.explain);
             }
             println!();
         }
@@ -152,7 +1062,7 @@ fn output_results(alerts: &[BullshitAlert], format: &str) -> Result<()> {
         if !high.is_empty() {
             println!("🟠 HIGH ({} issues):", high.len());
             for alert in high {
-                print_alert(alert);
+                print_alert(alert, opts.show_fingerprints, opts.explain);
             }
             println!();
         }
@@ -160,7 +1070,7 @@ fn output_results(alerts: &[BullshitAlert], format: &str) -> Result<()> {
         if !medium.is_empty() {
             println!("🟡 MEDIUM ({} issues):", medium.len());
             for alert in medium {
-                print_alert(alert);
+                print_alert(alert, opts.show_fingerprints, opts.explain);
             }
         }
 
@@ -170,11 +1080,78 @@ fn output_results(alerts: &[BullshitAlert], format: &str) -> Result<()> {
     Ok(())
 }
 
-fn print_alert(alert: &BullshitAlert) {
-    println!("  {} at line {}", alert.issue_type, alert.location.0);
+/// Print the `--top-files` or `--suggestions-summary` view of `alerts`, if
+/// one was asked for in place of the alerts themselves
+fn print_summary_view(alerts: &[BullshitAlert], opts: &ScanOptions) -> bool {
+    if let Some(n) = opts.top_files {
+        print_top_files(&rank_files(alerts), n);
+    } else if opts.suggestions_summary {
+        for (i, (suggestion, count)) in summarize_suggestions(alerts).iter().enumerate() {
+            let noun = if *count == 1 {
+                "occurrence"
+            } else {
+                "occurrences"
+            };
+            println!("{:>3}. {} ({} {})", i + 1, suggestion, count, noun);
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Ranked table of the `n` worst files
+fn print_top_files(ranked: &[FileScore], n: usize) {
+    println!(
+        "{:>4}  {:>7}  {:>6}  {:<22}  File",
+        "Rank", "Score", "Alerts", "Top issue"
+    );
+    for (rank, file) in ranked.iter().take(n).enumerate() {
+        println!(
+            "{:>4}  {:>7.2}  {:>6}  {:<22}  {}",
+            rank + 1,
+            file.score,
+            file.alerts,
+            file.dominant_type.to_string(),
+            file.file
+        );
+    }
+}
+
+/// Counts, then the added alerts in full
+fn print_diff(report: &DiffReport, show_fingerprints: bool) {
+    println!(
+        "+{} added, -{} removed, {} unchanged",
+        report.added.len(),
+        report.removed.len(),
+        report.common.len()
+    );
+    if !report.added.is_empty() {
+        println!("\nAdded:\n");
+        for alert in &report.added {
+            // Loaded from JSON, which doesn't keep score breakdowns
+            print_alert(alert, show_fingerprints, false);
+        }
+    }
+}
+
+fn print_alert(alert: &BullshitAlert, show_fingerprint: bool, explain: bool) {
+    match &alert.file_path {
+        Some(file_path) => println!(
+            "  {} at {}:{}",
+            alert.issue_type, file_path, alert.location.line
+        ),
+        None => println!("  {} at line {}", alert.issue_type, alert.location.line),
+    }
     println!("    {}", alert.context_snippet.lines().next().unwrap_or(""));
     println!("    Why: {}", alert.why_bs);
     println!("    Fix: {}", alert.sug);
     println!("    Confidence: {:.0}%", alert.confidence * 100.0);
+    if explain && !alert.breakdown.contributions.is_empty() {
+        println!("    Score: {}", alert.breakdown);
+    }
+    if show_fingerprint {
+        println!("    Fingerprint: {}", fingerprint(alert));
+    }
     println!();
 }
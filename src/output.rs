@@ -0,0 +1,567 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Output formatters for scan results
+
+use crate::baseline::fingerprint;
+use crate::error::{DetectError, Result};
+use crate::{generate_suggestion, BullshitAlert, SeverityLevel};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/// Placeholders understood by [`render_template`]
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "file",
+    "line",
+    "col",
+    "type",
+    "category",
+    "confidence",
+    "severity",
+    "why",
+    "suggestion",
+    "snippet",
+];
+
+/// Write alerts as JSON Lines - one compact object per line, flushed per alert
+/// so the output can be consumed while a scan is still running
+pub fn write_jsonl<W: Write>(writer: &mut W, alerts: &[BullshitAlert]) -> io::Result<()> {
+    for alert in alerts {
+        serde_json::to_writer(&mut *writer, alert)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Columns written by [`write_csv`]
+pub const CSV_HEADER: &[&str] = &[
+    "file",
+    "line",
+    "column",
+    "type",
+    "severity",
+    "confidence",
+    "why",
+    "suggestion",
+    "snippet",
+];
+
+/// Write alerts as RFC 4180 CSV with a header row, for spreadsheets
+///
+/// Snippets are collapsed onto one line; other fields are quoted as needed.
+pub fn write_csv<W: Write>(writer: &mut W, alerts: &[BullshitAlert]) -> io::Result<()> {
+    writeln!(writer, "{}", CSV_HEADER.join(","))?;
+    for alert in alerts {
+        let fields = [
+            alert.file_path.clone().unwrap_or_default(),
+            alert.location.line.to_string(),
+            alert.location.column.to_string(),
+            alert.issue_type.to_string(),
+            alert.severity_level().to_string(),
+            format!("{:.2}", alert.confidence),
+            alert.why_bs.clone(),
+            alert.sug.clone(),
+            single_line(&alert.context_snippet),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| escape_csv_field(field)).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Format an alert as a GitHub Actions workflow command
+///
+/// Example: `::warning file=src/lib.rs,line=12,col=4,title=MagicNumber::Magic number 0.7`
+pub fn format_github_annotation(alert: &BullshitAlert) -> String {
+    let command = match alert.severity_level() {
+        SeverityLevel::Critical => "error",
+        SeverityLevel::High => "warning",
+        SeverityLevel::Medium => "notice",
+    };
+
+    let mut properties = Vec::new();
+    if let Some(file_path) = &alert.file_path {
+        properties.push(format!("file={}", escape_github_property(file_path)));
+    }
+    properties.push(format!("line={}", alert.location.line));
+    properties.push(format!("col={}", alert.location.column));
+    properties.push(format!(
+        "title={}",
+        escape_github_property(&alert.issue_type.to_string())
+    ));
+
+    format!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        escape_github_data(&format!("{} ({})", alert.why_bs, alert.sug))
+    )
+}
+
+/// Render alerts as JUnit XML, for CI systems that display test reports
+///
+/// Each file is a `<testsuite>` and each alert a failing `<testcase>`, named
+/// after its type and location, with the severity in its classname.
+pub fn render_junit(alerts: &[BullshitAlert]) -> String {
+    // Group by file, keeping the order files first appear in
+    let mut files: Vec<(&str, Vec<&BullshitAlert>)> = Vec::new();
+    for alert in alerts {
+        let file = alert.file_path.as_deref().unwrap_or("<unknown>");
+        match files.iter_mut().find(|(name, _)| *name == file) {
+            Some((_, group)) => group.push(alert),
+            None => files.push((file, vec![alert])),
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"bullshitdetector\" tests=\"{0}\" failures=\"{0}\">\n",
+        alerts.len()
+    ));
+    for (file, group) in files {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{1}\" failures=\"{1}\">\n",
+            escape_xml(file),
+            group.len()
+        ));
+        for alert in group {
+            let name = format!(
+                "{} at line {}, column {}",
+                alert.issue_type, alert.location.line, alert.location.column
+            );
+            let classname = format!(
+                "bullshitdetector.{}",
+                alert.severity_level().to_string().to_lowercase()
+            );
+            let details = format!(
+                "{}\nFix: {}\n\n{}",
+                alert.why_bs, alert.sug, alert.context_snippet
+            );
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\">\n",
+                escape_xml(&name),
+                escape_xml(&classname)
+            ));
+            xml.push_str(&format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                escape_xml(&single_line(&alert.why_bs)),
+                alert.issue_type,
+                escape_xml(&details)
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+
+    xml
+}
+
+/// Render alerts as a SARIF 2.1.0 log, for GitHub code scanning and other viewers
+///
+/// Each result carries the alert's [`fingerprint`] as
+/// `partialFingerprints.primaryLocationLineHash`. It ignores line numbers, so
+/// code scanning keeps tracking an alert as code moves between commits.
+pub fn render_sarif(alerts: &[BullshitAlert]) -> String {
+    // One rule per reported type, ordered by id so the log is reproducible
+    let rules: BTreeMap<String, String> = alerts
+        .iter()
+        .map(|a| (a.issue_type.to_string(), generate_suggestion(&a.issue_type)))
+        .collect();
+    let rules: Vec<serde_json::Value> = rules
+        .into_iter()
+        .map(|(id, help)| {
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": id },
+                "help": { "text": help },
+            })
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = alerts
+        .iter()
+        .map(|alert| {
+            let level = match alert.severity_level() {
+                SeverityLevel::Critical => "error",
+                SeverityLevel::High => "warning",
+                SeverityLevel::Medium => "note",
+            };
+            serde_json::json!({
+                "ruleId": alert.issue_type.to_string(),
+                "level": level,
+                "message": { "text": format!("{} ({})", alert.why_bs, alert.sug) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": alert.file_path.as_deref().unwrap_or_default(),
+                        },
+                        "region": {
+                            "startLine": alert.location.line,
+                            "startColumn": alert.location.column,
+                            "snippet": { "text": alert.context_snippet },
+                        },
+                    },
+                }],
+                "partialFingerprints": { "primaryLocationLineHash": fingerprint(alert) },
+            })
+        })
+        .collect();
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&log).expect("SARIF log serializes")
+}
+
+/// Check a template for unknown placeholders and unmatched braces
+pub fn validate_template(template: &str) -> Result<()> {
+    for part in template_parts(template)? {
+        if let TemplatePart::Placeholder(name) = part {
+            if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+                return Err(DetectError::InvalidConfig(format!(
+                    "Unknown template placeholder {{{}}} (expected one of {})",
+                    name,
+                    TEMPLATE_PLACEHOLDERS.join(", ")
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Render an alert through a template such as `{file}:{line}:{col}: {type} {why}`
+///
+/// `{{` and `}}` produce literal braces. Check templates with
+/// [`validate_template`] first: an invalid one renders as written.
+pub fn render_template(template: &str, alert: &BullshitAlert) -> String {
+    let Ok(parts) = template_parts(template) else {
+        return template.to_string();
+    };
+
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            TemplatePart::Literal(text) => out.push_str(text),
+            TemplatePart::Placeholder(name) => match name {
+                "file" => out.push_str(alert.file_path.as_deref().unwrap_or_default()),
+                "line" => out.push_str(&alert.location.line.to_string()),
+                "col" => out.push_str(&alert.location.column.to_string()),
+                "type" => out.push_str(&alert.issue_type.to_string()),
+                "category" => out.push_str(&alert.issue_type.category().to_string()),
+                "confidence" => out.push_str(&format!("{:.2}", alert.confidence)),
+                "severity" => out.push_str(&alert.severity_level().to_string()),
+                "why" => out.push_str(&alert.why_bs),
+                "suggestion" => out.push_str(&alert.sug),
+                // Multi-line snippets would break line-oriented output
+                "snippet" => out.push_str(&single_line(&alert.context_snippet)),
+                other => {
+                    out.push('{');
+                    out.push_str(other);
+                    out.push('}');
+                }
+            },
+        }
+    }
+    out
+}
+
+enum TemplatePart<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into literal text and `{name}` placeholders
+fn template_parts(template: &str) -> Result<Vec<TemplatePart<'_>>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        parts.push(TemplatePart::Literal(&rest[..i]));
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            parts.push(TemplatePart::Literal(&tail[..1]));
+            rest = &tail[2..];
+        } else if tail.starts_with('{') {
+            let close = tail.find('}').ok_or_else(|| {
+                DetectError::InvalidConfig(format!("Unclosed {{ in template: {}", template))
+            })?;
+            parts.push(TemplatePart::Placeholder(&tail[1..close]));
+            rest = &tail[close + 1..];
+        } else {
+            return Err(DetectError::InvalidConfig(format!(
+                "Unmatched }} in template (use }}}} for a literal brace): {}",
+                template
+            )));
+        }
+    }
+    parts.push(TemplatePart::Literal(rest));
+
+    Ok(parts)
+}
+
+/// Collapse all whitespace runs, newlines included, to single spaces
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Quote a CSV field if it holds a delimiter, quote or line break
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escape text for an XML attribute or element, dropping control characters
+/// XML 1.0 can't represent
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a workflow command message
+fn escape_github_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value (also reserves `:` and `,`)
+fn escape_github_property(value: &str) -> String {
+    escape_github_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BullshitType, SourceLocation};
+
+    fn sample_alert() -> BullshitAlert {
+        BullshitAlert {
+            issue_type: BullshitType::HardcodedThreshold,
+            confidence: 0.8,
+            location: SourceLocation::new(12, 4),
+            context_snippet: "if x > 0.5 {".to_string(),
+            why_bs: "Threshold 50% too strict,\nreally".to_string(),
+            sug: "Move to config".to_string(),
+            severity: 0.8,
+            file_path: Some("src/a,b:c.rs".to_string()),
+            breakdown: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_github_annotation_escaping() {
+        let annotation = format_github_annotation(&sample_alert());
+
+        assert_eq!(
+            annotation,
+            "::warning file=src/a%2Cb%3Ac.rs,line=12,col=4,title=HardcodedThreshold::\
+             Threshold 50%25 too strict,%0Areally (Move to config)"
+        );
+    }
+
+    #[test]
+    fn test_github_annotation_severity_mapping() {
+        let mut alert = sample_alert();
+
+        alert.severity = 0.95;
+        assert!(format_github_annotation(&alert).starts_with("::error "));
+
+        alert.severity = 0.6;
+        assert!(format_github_annotation(&alert).starts_with("::notice "));
+    }
+
+    #[test]
+    fn test_jsonl_one_object_per_line_round_trips() {
+        let mut second = sample_alert();
+        second.location = SourceLocation::new(20, 1);
+        second.context_snippet = "let x = 0.7;\nlet y = 0.8;".to_string();
+        let alerts = vec![sample_alert(), second];
+
+        let mut buffer = Vec::new();
+        write_jsonl(&mut buffer, &alerts).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let parsed: Vec<BullshitAlert> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].location, SourceLocation::new(20, 1));
+        assert_eq!(parsed[1].context_snippet, alerts[1].context_snippet);
+        assert_eq!(parsed[0].file_path, alerts[0].file_path);
+    }
+
+    #[test]
+    fn test_json_location_has_named_fields() {
+        let json = serde_json::to_value(sample_alert()).unwrap();
+
+        assert_eq!(json["location"]["line"], 12);
+        assert_eq!(json["location"]["column"], 4);
+    }
+
+    #[test]
+    fn test_csv_quotes_fields_and_parses_back() {
+        let mut second = sample_alert();
+        second.context_snippet = "let (a, b) = (\"x\", 0.7);\nnext();".to_string();
+        let alerts = vec![sample_alert(), second];
+
+        let mut buffer = Vec::new();
+        write_csv(&mut buffer, &alerts).unwrap();
+
+        let mut reader = csv::Reader::from_reader(buffer.as_slice());
+        assert_eq!(reader.headers().unwrap(), CSV_HEADER);
+        let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(&records[0][0], "src/a,b:c.rs");
+        assert_eq!(&records[0][6], alerts[0].why_bs);
+        assert_eq!(&records[1][8], "let (a, b) = (\"x\", 0.7); next();");
+    }
+
+    #[test]
+    fn test_render_template_placeholders() {
+        let alert = sample_alert();
+
+        assert_eq!(
+            render_template(
+                "{file}:{line}:{col}: {type} ({confidence}) {severity}",
+                &alert
+            ),
+            "src/a,b:c.rs:12:4: HardcodedThreshold (0.80) HIGH"
+        );
+        assert_eq!(
+            render_template("{suggestion} <- {snippet}", &alert),
+            "Move to config <- if x > 0.5 {"
+        );
+    }
+
+    #[test]
+    fn test_render_template_brace_escaping() {
+        let alert = sample_alert();
+
+        assert_eq!(
+            render_template("{{\"line\": {line}}}", &alert),
+            "{\"line\": 12}"
+        );
+        assert!(validate_template("{{{type}}}").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_templates_rejected() {
+        let unknown = validate_template("{file}: {message}").unwrap_err();
+        assert!(unknown.to_string().contains("{message}"));
+
+        assert!(validate_template("{file").is_err());
+        assert!(validate_template("line}").is_err());
+    }
+
+    #[test]
+    fn test_junit_is_well_formed_with_one_testcase_per_alert() {
+        let mut second = sample_alert();
+        second.location = SourceLocation::new(30, 1);
+        second.context_snippet = "if a < b && c > \"d\" {\u{1}".to_string();
+        let mut other_file = sample_alert();
+        other_file.file_path = Some("src/other.rs".to_string());
+        other_file.severity = 0.95;
+        let alerts = vec![sample_alert(), second, other_file];
+
+        let xml = render_junit(&alerts);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+
+        let suites: Vec<_> = doc
+            .descendants()
+            .filter(|n| n.has_tag_name("testsuite"))
+            .collect();
+        assert_eq!(suites.len(), 2);
+        assert_eq!(suites[0].attribute("name"), Some("src/a,b:c.rs"));
+        assert_eq!(suites[0].attribute("tests"), Some("2"));
+
+        let cases: Vec<_> = doc
+            .descendants()
+            .filter(|n| n.has_tag_name("testcase"))
+            .collect();
+        assert_eq!(cases.len(), alerts.len());
+        assert_eq!(
+            cases[1].attribute("name"),
+            Some("HardcodedThreshold at line 30, column 1")
+        );
+        assert_eq!(
+            cases[2].attribute("classname"),
+            Some("bullshitdetector.critical")
+        );
+
+        let failure = cases[1].first_element_child().unwrap();
+        assert_eq!(
+            failure.attribute("message"),
+            Some("Threshold 50% too strict, really")
+        );
+        assert!(failure.text().unwrap().ends_with("if a < b && c > \"d\" {"));
+    }
+
+    #[test]
+    fn test_sarif_fingerprint_ignores_line() {
+        let mut moved = sample_alert();
+        moved.location = SourceLocation::new(40, 4);
+        let mut other = sample_alert();
+        other.context_snippet = "if y > 0.9 {".to_string();
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&render_sarif(&[sample_alert(), moved, other])).unwrap();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        let hash = |i: usize| {
+            results[i]["partialFingerprints"]["primaryLocationLineHash"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(hash(0), hash(1));
+        assert_ne!(hash(0), hash(2));
+        assert_eq!(hash(0), fingerprint(&sample_alert()));
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            40
+        );
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "HardcodedThreshold"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Files-scanned counter for long scans
+//!
+//! Written to stderr so machine-readable stdout stays clean. A disabled
+//! counter writes nothing at all.
+
+use std::io::{self, IsTerminal, Stderr, Write};
+use std::path::Path;
+
+/// A single-line `[done/total] path` counter redrawn in place
+pub struct Progress<W: Write> {
+    out: Option<W>,
+    total: usize,
+    done: usize,
+}
+
+impl Progress<Stderr> {
+    /// Counter on stderr, enabled only when stderr is a terminal and not `quiet`
+    pub fn stderr(total: usize, quiet: bool) -> Self {
+        let stderr = io::stderr();
+        let enabled = !quiet && stderr.is_terminal();
+        Self::new(enabled.then_some(stderr), total)
+    }
+}
+
+impl<W: Write> Progress<W> {
+    /// Counter writing to `out`, or a no-op when `out` is `None`
+    pub fn new(out: Option<W>, total: usize) -> Self {
+        Self {
+            out,
+            total,
+            done: 0,
+        }
+    }
+
+    /// Record that `path` is being scanned
+    pub fn tick(&mut self, path: &Path) {
+        self.done += 1;
+        if let Some(out) = &mut self.out {
+            // Progress is best effort; a broken stderr shouldn't fail the scan
+            let _ = write!(
+                out,
+                "\r\x1b[2K[{}/{}] {}",
+                self.done,
+                self.total,
+                path.display()
+            );
+            let _ = out.flush();
+        }
+    }
+
+    /// Erase the counter line so warnings and results print on a clean line
+    pub fn clear(&mut self) {
+        if let Some(out) = &mut self.out {
+            let _ = write!(out, "\r\x1b[2K");
+            let _ = out.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_progress_writes_nothing() {
+        let mut progress: Progress<Vec<u8>> = Progress::new(None, 2);
+        progress.tick(Path::new("src/a.rs"));
+        progress.clear();
+
+        assert!(progress.out.is_none());
+        assert_eq!(progress.done, 1);
+    }
+
+    #[test]
+    fn test_progress_counts_files() {
+        let mut progress = Progress::new(Some(Vec::new()), 2);
+        progress.tick(Path::new("src/a.rs"));
+        progress.tick(Path::new("src/b.rs"));
+
+        let written = String::from_utf8(progress.out.take().unwrap()).unwrap();
+        assert!(written.contains("[1/2] src/a.rs"));
+        assert!(written.ends_with("[2/2] src/b.rs"));
+    }
+}
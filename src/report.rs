@@ -0,0 +1,227 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Aggregate counts for a whole scan, for dashboards and trend tracking
+//!
+//! Unlike the alert output this carries no snippets or locations, so it
+//! stays small and only changes when the counts do.
+
+use crate::error::Result;
+use crate::{BullshitAlert, BullshitType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Alert counts for one scan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanReport {
+    /// Number of alerts
+    pub total: usize,
+    /// Alert counts keyed by `BullshitType` name
+    pub by_type: BTreeMap<String, usize>,
+    /// Alert counts keyed by severity bucket (`CRITICAL`, `HIGH`, `MEDIUM`)
+    pub by_severity: BTreeMap<String, usize>,
+    /// Files that were read and scanned (skipped files don't count)
+    pub files_scanned: usize,
+    /// Reported paths of the scanned files (only listed with `--list-scanned`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scanned: Vec<String>,
+    /// Files left out of the scan and why (only listed with `--list-scanned`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// Why a file wasn't scanned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// Matched a magic number `whitelist_paths` entry
+    Whitelisted,
+    /// Excluded by `.gitignore` or the git excludes
+    Gitignored,
+    /// Neither UTF-8 nor BOM-marked UTF-16
+    NonUtf8,
+    /// Took longer than the scan timeout
+    Timeout,
+    /// Has a generated-code header (`// @generated`, ...)
+    Generated,
+}
+
+/// A file left out of the scan, for `--list-scanned`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    /// Reported path
+    pub path: String,
+    pub reason: SkipReason,
+}
+
+impl ScanReport {
+    /// Count `alerts` from a scan of `files_scanned` files
+    pub fn from_alerts(alerts: &[BullshitAlert], files_scanned: usize) -> Self {
+        let mut report = Self {
+            files_scanned,
+            ..Self::default()
+        };
+
+        for alert in alerts {
+            report.total += 1;
+            *report
+                .by_type
+                .entry(alert.issue_type.to_string())
+                .or_default() += 1;
+            *report
+                .by_severity
+                .entry(alert.severity_level().to_string())
+                .or_default() += 1;
+        }
+
+        report
+    }
+}
+
+/// How bad one file's alerts are in total, for [`rank_files`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileScore {
+    /// Reported path (empty for alerts with none)
+    pub file: String,
+    /// Sum of each alert's severity weighted by its confidence
+    pub score: f32,
+    /// Number of alerts
+    pub alerts: usize,
+    /// Type contributing the most to `score`
+    pub dominant_type: BullshitType,
+}
+
+/// Files ranked worst first by [`FileScore::score`], ties broken by path
+pub fn rank_files(alerts: &[BullshitAlert]) -> Vec<FileScore> {
+    let mut by_file: BTreeMap<&str, HashMap<&BullshitType, (f32, usize)>> = BTreeMap::new();
+    for alert in alerts {
+        let file = alert.file_path.as_deref().unwrap_or_default();
+        let entry = by_file
+            .entry(file)
+            .or_default()
+            .entry(&alert.issue_type)
+            .or_default();
+        entry.0 += alert.severity * alert.confidence;
+        entry.1 += 1;
+    }
+
+    let mut ranked: Vec<FileScore> = by_file
+        .into_iter()
+        .filter_map(|(file, types)| {
+            let (dominant_type, _) = types
+                .iter()
+                .max_by(|a, b| a.1 .0.total_cmp(&b.1 .0).then_with(|| b.0.cmp(a.0)))?;
+            Some(FileScore {
+                file: file.to_string(),
+                score: types.values().map(|(score, _)| score).sum(),
+                alerts: types.values().map(|(_, count)| count).sum(),
+                dominant_type: (*dominant_type).clone(),
+            })
+        })
+        .collect();
+
+    // by_file is keyed by path, so a stable sort leaves ties in path order
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
+
+/// Distinct suggestions with how many alerts each would resolve, most
+/// common first (ties alphabetical)
+pub fn summarize_suggestions(alerts: &[BullshitAlert]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for alert in alerts {
+        *counts.entry(alert.sug.as_str()).or_default() += 1;
+    }
+
+    let mut summary: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(suggestion, count)| (suggestion.to_string(), count))
+        .collect();
+    // counts is sorted by suggestion, so a stable sort keeps ties alphabetical
+    summary.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    summary
+}
+
+/// Write `report` to `path` as pretty-printed JSON
+pub fn write_report_file(path: &Path, report: &ScanReport) -> Result<()> {
+    let json = serde_json::to_string_pretty(report).map_err(io::Error::from)?;
+    fs::write(path, json + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scan_code, DetectConfig};
+
+    #[test]
+    fn test_report_file_matches_scan() {
+        let code = r#"
+        fn load(path: &str) -> Config {
+            let raw = std::fs::read_to_string(path).unwrap();
+            let copy = raw.clone();
+            parse(&copy).unwrap()
+        }
+        "#;
+        let alerts = scan_code(code, &DetectConfig::default()).unwrap();
+        let report = ScanReport::from_alerts(&alerts, 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("summary.json");
+        write_report_file(&path, &report).unwrap();
+
+        let written: ScanReport =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, report);
+        assert_eq!(written.total, alerts.len());
+        assert_eq!(written.files_scanned, 1);
+        assert_eq!(written.by_type["UnwrapAbuse"], 2);
+        assert_eq!(written.by_type["CloneAbuse"], 1);
+        assert_eq!(written.by_severity.values().sum::<usize>(), written.total);
+    }
+
+    #[test]
+    fn test_rank_files_by_total_weighted_severity() {
+        let scan = |file: &str, code: &str| -> Vec<BullshitAlert> {
+            let mut alerts = scan_code(code, &DetectConfig::default()).unwrap();
+            for alert in &mut alerts {
+                alert.file_path = Some(file.to_string());
+            }
+            alerts
+        };
+        let mut alerts = scan("src/few.rs", "fn f() {\n    let a = x.clone();\n}\n");
+        alerts.extend(scan(
+            "src/worst.rs",
+            "fn g() {\n    a.unwrap();\n    b.unwrap();\n    c.unwrap();\n    let d = y.clone();\n}\n",
+        ));
+        alerts.extend(scan(
+            "src/middle.rs",
+            "fn h() {\n    a.unwrap();\n    let b = z.clone();\n}\n",
+        ));
+
+        let ranked = rank_files(&alerts);
+        let files: Vec<&str> = ranked.iter().map(|f| f.file.as_str()).collect();
+
+        assert_eq!(files, ["src/worst.rs", "src/middle.rs", "src/few.rs"]);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(ranked[0].alerts, 4);
+        assert_eq!(ranked[0].dominant_type, BullshitType::UnwrapAbuse);
+        assert_eq!(ranked[2].dominant_type, BullshitType::CloneAbuse);
+    }
+
+    #[test]
+    fn test_summarize_suggestions_counts_each_fix() {
+        let code = "fn f() {\n    a.unwrap();\n    b.unwrap();\n    c.unwrap();\n    let d = x.clone();\n}\n";
+        let alerts = scan_code(code, &DetectConfig::default()).unwrap();
+        let unwrap_fix = crate::generate_suggestion(&BullshitType::UnwrapAbuse);
+        let clone_fix = crate::generate_suggestion(&BullshitType::CloneAbuse);
+
+        let summary = summarize_suggestions(&alerts);
+
+        assert_eq!(summary, vec![(unwrap_fix, 3), (clone_fix, 1)]);
+    }
+}
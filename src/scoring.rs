@@ -0,0 +1,398 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Confidence scoring shared by every scanner
+//!
+//! Scanners extract [`ScoreFeatures`] from what they matched and call [`score`];
+//! all the weights live here so tuning one scanner can't silently drift from
+//! the others. [`score_explained`] also returns the terms the confidence was
+//! added up from.
+
+use crate::{BullshitType, Category};
+use std::fmt;
+
+/// Keywords in a conditional that suggest a behavioral threshold
+pub const THRESHOLD_KEYWORDS: &[&str] = &[
+    "threshold",
+    "limit",
+    "bound",
+    "min",
+    "max",
+    "tolerance",
+    "entropy",
+    "yawn",
+    "healing",
+    "spectral",
+    "knot",
+    "persistence",
+    "quality",
+    "gate",
+    "circuit",
+    "similarity",
+    "cosine",
+];
+
+/// Variable name fragments that suggest a config value
+pub const CONFIG_NAME_KEYWORDS: &[&str] = &[
+    "threshold",
+    "limit",
+    "bound",
+    "weight",
+    "ratio",
+    "factor",
+    "radius",
+    "width",
+    "height",
+    "size",
+    "count",
+    "max",
+    "min",
+    "alpha",
+    "beta",
+    "gamma",
+    "epsilon",
+    "delta",
+];
+
+/// Extra confidence (and severity) for thresholds that form a function's whole answer
+const POLICY_BOUNDARY_BOOST: f32 = 0.15;
+
+/// Highest confidence a heuristic score can reach
+const MAX_HEURISTIC_CONFIDENCE: f32 = 0.95;
+
+/// Severity added to security findings, which cost more than they look
+const SECURITY_SEVERITY_BOOST: f32 = 0.1;
+
+/// Severity taken off style findings, which never change behavior
+const STYLE_SEVERITY_DISCOUNT: f32 = 0.1;
+
+/// What is being scored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreKind<'a> {
+    /// A plain regex pattern hit from `scan_code`
+    Pattern(&'a BullshitType),
+    /// A literal compared against in an `if`/`while`/guard
+    ConditionalThreshold,
+    /// A literal compared against as a function's tail expression
+    PolicyBoundary,
+    /// A float literal compared with `==` or `!=`
+    FloatEquality,
+    /// A literal bound with `let` or assigned to a variable
+    AssignmentLiteral,
+    /// A call passing several literal arguments
+    LiteralArguments,
+    /// A retry count, backoff multiplier or timeout duration
+    RetryPolicy,
+    /// A literal inside a compound `const`/`static` initializer
+    ConstComponent,
+    /// A bare literal pattern in a `match` arm
+    MatchLiteral,
+    /// A literal `with_capacity` hint or buffer length
+    CapacityLiteral,
+    /// An explicit enum discriminant with no comment
+    EnumDiscriminant,
+    /// A hand-typed approximation of a `std::f64::consts` value
+    MathConstant,
+    /// Seconds or milliseconds in a time unit, like `86400` or `60 * 60`
+    TimeConstant,
+    /// A float literal with more significant digits than anyone tunes by hand
+    PreciseFloat,
+}
+
+/// Evidence extracted from the matched code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScoreFeatures {
+    /// Distinct config-ish keywords found near the literal
+    pub keyword_hits: usize,
+    /// The literal lies strictly between 0 and 1
+    pub value_in_unit_range: bool,
+    /// The literal carries an `f32`/`f64` suffix
+    pub type_suffix: bool,
+    /// The literal sits inside a function body rather than at module level
+    pub in_function_scope: bool,
+}
+
+impl ScoreFeatures {
+    /// Features of a threshold literal `value` compared in `snippet`
+    pub fn for_threshold(snippet: &str, value: &str) -> Self {
+        Self {
+            keyword_hits: count_keywords(snippet, THRESHOLD_KEYWORDS),
+            value_in_unit_range: in_unit_range(value),
+            ..Self::default()
+        }
+    }
+
+    /// Features of `value` assigned to `var_name` on the untrimmed source `line`
+    pub fn for_assignment(var_name: &str, value: &str, line: &str) -> Self {
+        Self {
+            keyword_hits: count_keywords(var_name, CONFIG_NAME_KEYWORDS),
+            value_in_unit_range: in_unit_range(value),
+            type_suffix: value.ends_with("f32") || value.ends_with("f64"),
+            in_function_scope: line.starts_with("    ") || line.starts_with('\t'),
+        }
+    }
+}
+
+/// The terms a confidence was added up from, in the order they were applied
+///
+/// The first term is the base; the rest are feature contributions and any
+/// cap, so [`ScoreBreakdown::total`] is the confidence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    pub contributions: Vec<(String, f32)>,
+}
+
+impl ScoreBreakdown {
+    /// A breakdown that is just a fixed base score
+    pub fn base(confidence: f32) -> Self {
+        Self {
+            contributions: vec![("base".to_string(), confidence)],
+        }
+    }
+
+    /// Sum of all contributions
+    pub fn total(&self) -> f32 {
+        self.contributions.iter().map(|(_, amount)| amount).sum()
+    }
+
+    fn add(&mut self, reason: impl Into<String>, amount: f32) {
+        self.contributions.push((reason.into(), amount));
+    }
+
+    /// Record the cut that keeps the total at or below `max`
+    fn cap(&mut self, max: f32) {
+        let over = self.total() - max;
+        if over > 0.0 {
+            self.add(format!("capped at {:.2}", max), -over);
+        }
+    }
+}
+
+/// e.g. `base 0.50, 1 threshold keyword +0.15, value in (0, 1) +0.20`
+impl fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (reason, amount)) in self.contributions.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            if i == 0 {
+                write!(f, "{} {:.2}", reason, amount)?;
+            } else {
+                write!(f, "{} {:+.2}", reason, amount)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Score a finding, returning `(confidence, severity)`
+pub fn score(kind: ScoreKind, features: ScoreFeatures) -> (f32, f32) {
+    let (confidence, severity, _) = score_explained(kind, features);
+    (confidence, severity)
+}
+
+/// Like [`score`], also returning the breakdown the confidence is the total of
+pub fn score_explained(kind: ScoreKind, features: ScoreFeatures) -> (f32, f32, ScoreBreakdown) {
+    let fixed =
+        |confidence: f32, severity: f32| (confidence, severity, ScoreBreakdown::base(confidence));
+    match kind {
+        ScoreKind::Pattern(bs_type) => {
+            let confidence = match bs_type {
+                BullshitType::OverEngineering => 0.8,
+                BullshitType::SleepAbuse => 0.75,
+                BullshitType::MagicNumber => 0.9,
+                BullshitType::HardcodedThreshold => 0.85,
+                _ => 0.7,
+            };
+            fixed(confidence, confidence)
+        }
+        ScoreKind::ConditionalThreshold => {
+            let breakdown = threshold_breakdown(features);
+            let confidence = breakdown.total();
+            (confidence, confidence, breakdown)
+        }
+        ScoreKind::PolicyBoundary => {
+            let mut breakdown = threshold_breakdown(features);
+            breakdown.add("policy boundary", POLICY_BOUNDARY_BOOST);
+            breakdown.cap(MAX_HEURISTIC_CONFIDENCE);
+            let confidence = breakdown.total();
+            (
+                confidence,
+                (confidence + POLICY_BOUNDARY_BOOST).min(1.0),
+                breakdown,
+            )
+        }
+        // Exact float comparison is wrong whatever the context, so it
+        // outranks any ordering threshold
+        ScoreKind::FloatEquality => fixed(0.85, 0.95),
+        ScoreKind::AssignmentLiteral => {
+            let mut breakdown = ScoreBreakdown::base(0.4);
+            if features.keyword_hits > 0 {
+                breakdown.add(
+                    keyword_reason(features.keyword_hits, "config name keyword"),
+                    0.25 * features.keyword_hits as f32,
+                );
+            }
+            if features.type_suffix {
+                breakdown.add("float type suffix", 0.15);
+            }
+            if features.in_function_scope {
+                breakdown.add("inside a function", 0.15);
+            }
+            breakdown.cap(MAX_HEURISTIC_CONFIDENCE);
+            let confidence = breakdown.total();
+            (confidence, confidence, breakdown)
+        }
+        // Several literals in one call is a strong signal on its own
+        ScoreKind::LiteralArguments => fixed(0.75, 0.75),
+        // Retry and timeout policy is config by nature, whatever its value
+        ScoreKind::RetryPolicy => fixed(0.8, 0.8),
+        // The const is already named, so its components are a milder smell
+        ScoreKind::ConstComponent => fixed(0.7, 0.7),
+        // Status and opcode tables are usually protocol constants without a name
+        ScoreKind::MatchLiteral => fixed(0.75, 0.75),
+        // A wrong capacity only costs memory or a reallocation, never correctness
+        ScoreKind::CapacityLiteral => fixed(0.7, 0.6),
+        // Often a wire or FFI value that only needs a comment saying so
+        ScoreKind::EnumDiscriminant => fixed(0.7, 0.6),
+        // Nearly always a copy of a std constant, but rarely a real bug
+        ScoreKind::MathConstant => fixed(0.85, 0.6),
+        // Unmistakably time math, and a units mix-up there is a real bug
+        ScoreKind::TimeConstant => fixed(0.8, 0.65),
+        // Almost always the output of a formula, but only sometimes a known one
+        ScoreKind::PreciseFloat => fixed(0.75, 0.6),
+    }
+}
+
+/// Severity for an alert whose scanner only judged its confidence: the
+/// confidence, adjusted by how much the type's [`Category`] tends to matter
+pub fn default_severity(issue_type: &BullshitType, confidence: f32) -> f32 {
+    match issue_type.category() {
+        Category::Security => (confidence + SECURITY_SEVERITY_BOOST).min(1.0),
+        Category::Style => (confidence - STYLE_SEVERITY_DISCOUNT).max(0.0),
+        _ => confidence,
+    }
+}
+
+/// Base score for a compared literal
+fn threshold_breakdown(features: ScoreFeatures) -> ScoreBreakdown {
+    let mut breakdown = ScoreBreakdown::base(0.5);
+    if features.keyword_hits > 0 {
+        breakdown.add(
+            keyword_reason(features.keyword_hits, "threshold keyword"),
+            0.15 * features.keyword_hits as f32,
+        );
+    }
+    if features.value_in_unit_range {
+        breakdown.add("value in (0, 1)", 0.2);
+    }
+    breakdown.cap(MAX_HEURISTIC_CONFIDENCE);
+    breakdown
+}
+
+/// e.g. "1 threshold keyword", "2 threshold keywords"
+fn keyword_reason(hits: usize, noun: &str) -> String {
+    format!("{} {}{}", hits, noun, if hits == 1 { "" } else { "s" })
+}
+
+/// Number of `keywords` contained in `text`, case-insensitively
+fn count_keywords(text: &str, keywords: &[&str]) -> usize {
+    let text = text.to_lowercase();
+    keywords.iter().filter(|k| text.contains(*k)).count()
+}
+
+/// Whether a numeric literal (suffix allowed) lies strictly between 0 and 1
+fn in_unit_range(value: &str) -> bool {
+    let digits = value.trim_end_matches("f32").trim_end_matches("f64");
+    digits
+        .parse::<f64>()
+        .map(|v| v > 0.0 && v < 1.0)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_score(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-6 && (actual.1 - expected.1).abs() < 1e-6,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_threshold_scores_are_pinned() {
+        let plain = ScoreFeatures::for_threshold("if x > 5", "5");
+        assert_score(score(ScoreKind::ConditionalThreshold, plain), (0.5, 0.5));
+
+        let entropy = ScoreFeatures::for_threshold("if entropy > 0.4", "0.4");
+        assert_eq!(entropy.keyword_hits, 1);
+        assert!(entropy.value_in_unit_range);
+        assert_score(
+            score(ScoreKind::ConditionalThreshold, entropy),
+            (0.85, 0.85),
+        );
+        assert_score(score(ScoreKind::PolicyBoundary, entropy), (0.95, 1.0));
+    }
+
+    #[test]
+    fn test_assignment_scores_are_pinned() {
+        let bare = ScoreFeatures::for_assignment("x", "42", "let x = 42;");
+        assert_score(score(ScoreKind::AssignmentLiteral, bare), (0.4, 0.4));
+
+        let typed = ScoreFeatures::for_assignment("radius", "5.0f32", "    let radius = 5.0f32;");
+        assert!(typed.type_suffix && typed.in_function_scope);
+        assert_score(score(ScoreKind::AssignmentLiteral, typed), (0.95, 0.95));
+    }
+
+    #[test]
+    fn test_pattern_scores_use_type_base() {
+        let features = ScoreFeatures::default();
+
+        assert_score(
+            score(ScoreKind::Pattern(&BullshitType::MagicNumber), features),
+            (0.9, 0.9),
+        );
+        assert_score(
+            score(ScoreKind::Pattern(&BullshitType::CloneAbuse), features),
+            (0.7, 0.7),
+        );
+    }
+
+    #[test]
+    fn test_breakdown_sums_to_confidence() {
+        let cases = [
+            (
+                ScoreKind::ConditionalThreshold,
+                ScoreFeatures::for_threshold("if entropy > 0.4", "0.4"),
+            ),
+            (
+                ScoreKind::PolicyBoundary,
+                ScoreFeatures::for_threshold("score >= max_limit * 0.4", "0.4"),
+            ),
+            (
+                ScoreKind::AssignmentLiteral,
+                ScoreFeatures::for_assignment("radius", "5.0f32", "    let radius = 5.0f32;"),
+            ),
+            (ScoreKind::MatchLiteral, ScoreFeatures::default()),
+        ];
+        for (kind, features) in cases {
+            let (confidence, _, breakdown) = score_explained(kind, features);
+            assert!((breakdown.total() - confidence).abs() < 1e-6, "{:?}", kind);
+            assert_eq!(score(kind, features).0, confidence);
+        }
+
+        let (_, _, breakdown) = score_explained(
+            ScoreKind::ConditionalThreshold,
+            ScoreFeatures::for_threshold("if entropy > 0.4", "0.4"),
+        );
+        assert_eq!(
+            breakdown.to_string(),
+            "base 0.50, 1 threshold keyword +0.15, value in (0, 1) +0.20"
+        );
+    }
+}
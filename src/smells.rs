@@ -0,0 +1,3481 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Structural code smell detection
+//!
+//! Detectors here need more context than a single regex match - scope
+//! tracking, brace matching or counting across lines.
+
+use crate::error::Result;
+use crate::limits::compile_regex;
+use crate::syntax::{
+    closing_delimiter, find_functions, line_at, matching_brace, parameter_list, split_top_level,
+    string_literal_ranges, test_code_ranges, FnSpan,
+};
+use crate::{
+    byte_offset_to_line_col, floor_char_boundary, BullshitAlert, BullshitType, DetectConfig,
+    SourceLocation,
+};
+use std::collections::{HashMap, HashSet};
+
+/// A name may be bound this many times in one scope before it's flagged
+/// (the original binding plus two shadows)
+const MAX_BINDINGS_PER_SCOPE: usize = 3;
+
+/// Identifier fragments that mark the assigned literal as a credential
+const SECRET_KEYWORDS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "api_key",
+    "apikey",
+    "token",
+    "credential",
+    "private_key",
+];
+
+/// Shortest literal that counts as a secret when bound to a secret-ish name
+const MIN_NAMED_SECRET_LEN: usize = 8;
+
+/// Entropy (bits per char) a secret-ish name's literal needs, so `"changeme"` passes
+/// but `"aaaaaaaa"` placeholders don't
+const MIN_NAMED_SECRET_ENTROPY: f64 = 2.5;
+
+/// Shortest literal flagged on entropy alone
+const MIN_TOKEN_LEN: usize = 20;
+
+/// Entropy (bits per char) a literal needs to be flagged without a keyword
+const MIN_TOKEN_ENTROPY: f64 = 3.5;
+
+/// Code lines above an `unsafe` (its own line included) searched for a
+/// `SAFETY:` comment, so a multi-line statement can carry one; comment and
+/// attribute lines don't count
+const SAFETY_LOOKBACK_LINES: usize = 3;
+
+/// Scan for names shadowed over and over within a single brace scope
+/// Example: `let x = a(); let x = b(x); let x = c(x); let x = d(x);`
+pub fn scan_shadowing(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let let_regex = compile_regex(r"\blet\s+(?:mut\s+)?([A-Za-z_]\w*)\s*[:=]")?;
+    let mut bindings = let_regex
+        .captures_iter(code)
+        .filter_map(|cap| cap.get(1))
+        .filter(|m| m.as_str() != "_")
+        .peekable();
+
+    // One map of name -> binding offsets per open brace scope
+    let mut scopes: Vec<HashMap<&str, Vec<usize>>> = vec![HashMap::new()];
+    let mut closed = Vec::new();
+
+    for (i, ch) in code.char_indices() {
+        while let Some(binding) = bindings.next_if(|m| m.start() < i) {
+            if let Some(scope) = scopes.last_mut() {
+                scope
+                    .entry(binding.as_str())
+                    .or_default()
+                    .push(binding.start());
+            }
+        }
+
+        match ch {
+            '{' => scopes.push(HashMap::new()),
+            '}' if scopes.len() > 1 => closed.extend(scopes.pop()),
+            _ => {}
+        }
+    }
+    for binding in bindings {
+        if let Some(scope) = scopes.last_mut() {
+            scope
+                .entry(binding.as_str())
+                .or_default()
+                .push(binding.start());
+        }
+    }
+    closed.extend(scopes);
+
+    let confidence = config.confidence_for(&BullshitType::ShadowAbuse, 0.65);
+
+    for scope in closed {
+        for (name, sites) in scope {
+            if sites.len() <= MAX_BINDINGS_PER_SCOPE {
+                continue;
+            }
+
+            let lines: Vec<String> = sites
+                .iter()
+                .map(|&pos| byte_offset_to_line_col(code, pos).line.to_string())
+                .collect();
+
+            alerts.push(
+                BullshitAlert::builder(BullshitType::ShadowAbuse)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, sites[0]))
+                    .snippet(line_at(code, sites[0]))
+                    .why(format!(
+                        "`{}` is bound {} times in one scope (lines {})",
+                        name,
+                        sites.len(),
+                        lines.join(", ")
+                    ))
+                    .suggestion(format!(
+                        "Give each stage of `{}` a descriptive name instead of shadowing",
+                        name
+                    ))
+                    .build(),
+            );
+        }
+    }
+
+    alerts.sort_by_key(|a| a.location);
+
+    Ok(alerts)
+}
+
+/// Scan for `fn` signatures taking more than `config.max_params` parameters
+/// Signatures may span multiple lines; `self` receivers don't count.
+pub fn scan_long_parameter_lists(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let fn_regex = compile_regex(r"\bfn\s+(\w+)")?;
+    let confidence = config.confidence_for(&BullshitType::LongParameterList, 0.7);
+
+    for cap in fn_regex.captures_iter(code) {
+        let Some(name_match) = cap.get(1) else {
+            continue;
+        };
+        let Some((start, end)) = parameter_list(code, name_match.end()) else {
+            continue;
+        };
+
+        let param_count = split_top_level(&code[start..end], ',')
+            .into_iter()
+            .map(str::trim)
+            .filter(|p| !p.is_empty() && !is_self_param(p))
+            .count();
+
+        if param_count > config.max_params {
+            let pos = name_match.start();
+            alerts.push(
+                BullshitAlert::builder(BullshitType::LongParameterList)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "Function `{}` takes {} parameters (max {})",
+                        name_match.as_str(),
+                        param_count,
+                        config.max_params
+                    ))
+                    .suggestion("Group related parameters into a struct or builder")
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// `self`, `&self`, `&mut self`, `&'a self`, `mut self`, `self: Box<Self>`...
+fn is_self_param(param: &str) -> bool {
+    let receiver = param.split(':').next().unwrap_or_default();
+    receiver
+        .trim_start_matches('&')
+        .split_whitespace()
+        .filter(|token| *token != "mut" && !token.starts_with('\''))
+        .eq(["self"])
+}
+
+/// Scan for parameters borrowing an owned container: `&Vec<T>`, `&String`
+/// or `&Box<T>`, where `&[T]`, `&str` or `&T` accepts more callers
+pub fn scan_borrowed_owned_params(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let fn_regex = compile_regex(r"\bfn\s+(\w+)")?;
+    let confidence = config.confidence_for(&BullshitType::BorrowedOwnedType, 0.7);
+
+    for cap in fn_regex.captures_iter(code) {
+        let Some(name_match) = cap.get(1) else {
+            continue;
+        };
+        let Some((start, end)) = parameter_list(code, name_match.end()) else {
+            continue;
+        };
+
+        for param in split_top_level(&code[start..end], ',') {
+            let Some((_, ty)) = param.split_once(':') else {
+                continue;
+            };
+            let Some((borrowed, preferred)) = preferred_borrow(ty.trim()) else {
+                continue;
+            };
+
+            // `param` is a slice of `code`
+            let trimmed = param.trim_start();
+            let pos = trimmed.as_ptr() as usize - code.as_ptr() as usize;
+            alerts.push(
+                BullshitAlert::builder(BullshitType::BorrowedOwnedType)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "`{}` takes `{}`, which forces callers to own one",
+                        name_match.as_str(),
+                        borrowed
+                    ))
+                    .suggestion(format!("Take `{}` instead", preferred))
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for C-style out-parameters: a `&mut` parameter named like an output
+/// on a function that returns `()`
+/// Example: `fn compute(&self, out: &mut Vec<u8>)`
+///
+/// `&mut [T]` buffers are skipped - filling caller-owned storage is the point.
+pub fn scan_out_params(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let fn_regex = compile_regex(r"\bfn\s+(\w+)")?;
+    let confidence = config.confidence_for(&BullshitType::OutParameter, 0.65);
+
+    for cap in fn_regex.captures_iter(code) {
+        let Some(name_match) = cap.get(1) else {
+            continue;
+        };
+        let line_start = code[..name_match.start()].rfind('\n').map_or(0, |i| i + 1);
+        if code[line_start..name_match.start()].contains("//") {
+            continue;
+        }
+        let Some((start, end)) = parameter_list(code, name_match.end()) else {
+            continue;
+        };
+        // Everything between `)` and the body or `;` is the return type and bounds
+        let rest = &code[end + 1..];
+        let signature_end = rest.find(['{', ';']).unwrap_or(rest.len());
+        let returns = rest[..signature_end]
+            .split("where")
+            .next()
+            .unwrap_or_default();
+        if returns
+            .trim()
+            .strip_prefix("->")
+            .is_some_and(|ty| ty.trim() != "()")
+        {
+            continue;
+        }
+
+        for param in split_top_level(&code[start..end], ',') {
+            let Some((param_name, ty)) = param.split_once(':') else {
+                continue;
+            };
+            let param_name = param_name.trim().trim_start_matches("mut ").trim();
+            let Some(target) = ty.trim().strip_prefix("&mut") else {
+                continue;
+            };
+            if !is_out_param_name(param_name) || target.trim_start().starts_with('[') {
+                continue;
+            }
+
+            // `param` is a slice of `code`
+            let trimmed = param.trim_start();
+            let pos = trimmed.as_ptr() as usize - code.as_ptr() as usize;
+            alerts.push(
+                BullshitAlert::builder(BullshitType::OutParameter)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "`{}` returns `()` and hands its result back through `{}: {}`",
+                        name_match.as_str(),
+                        param_name,
+                        ty.trim()
+                    ))
+                    .suggestion(format!(
+                        "Return `{}` from `{}` instead of writing into an out-parameter",
+                        target.trim(),
+                        name_match.as_str()
+                    ))
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// `out`, `result`, `buf`, `dest` and friends, or `out_*`/`*_out`
+fn is_out_param_name(name: &str) -> bool {
+    matches!(
+        name,
+        "out" | "output" | "result" | "results" | "buf" | "buffer" | "dest" | "dst"
+    ) || name.starts_with("out_")
+        || name.ends_with("_out")
+}
+
+/// For `&Vec<T>`, `&String` or `&Box<T>` (any lifetime, not `&mut`), the
+/// type as written and the borrow to take instead
+fn preferred_borrow(ty: &str) -> Option<(String, String)> {
+    let mut inner = ty.strip_prefix('&')?.trim_start();
+    if inner.starts_with('\'') {
+        inner = inner.split_once(char::is_whitespace)?.1.trim_start();
+    }
+    let inner = inner.strip_prefix("std::vec::").unwrap_or(inner);
+    let inner = inner.strip_prefix("std::string::").unwrap_or(inner);
+    let inner = inner.strip_prefix("std::boxed::").unwrap_or(inner);
+
+    if inner == "String" {
+        return Some((ty.to_string(), "&str".to_string()));
+    }
+    for (container, open, close) in [("Vec", "&[", "]"), ("Box", "&", "")] {
+        let Some(generics) = inner.strip_prefix(container) else {
+            continue;
+        };
+        if !generics.starts_with('<') {
+            continue;
+        }
+        let end = closing_delimiter(generics)?;
+        if !generics[end + 1..].trim().is_empty() {
+            return None;
+        }
+        let element = generics[1..end].trim();
+        return Some((ty.to_string(), format!("{}{}{}", open, element, close)));
+    }
+
+    None
+}
+
+/// Scan for functions whose blocks nest deeper than `config.max_nesting`
+/// Depth is counted in braces, so tabs vs spaces don't matter.
+pub fn scan_deep_nesting(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let functions = find_functions(code);
+    let confidence = config.confidence_for(&BullshitType::DeepNesting, 0.7);
+
+    for function in &functions {
+        // Nested fns are covered by their outermost function
+        let is_nested = functions.iter().any(|outer| {
+            outer.body_open < function.body_open && function.body_close < outer.body_close
+        });
+        if is_nested {
+            continue;
+        }
+
+        let mut depth = 0usize;
+        let mut max_depth = 0usize;
+        let mut deepest_at = 0usize;
+        let mut first_exceeded = None;
+
+        for (i, ch) in function.body(code).char_indices() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    let pos = function.body_open + 1 + i;
+                    if depth > max_depth {
+                        max_depth = depth;
+                        deepest_at = pos;
+                    }
+                    if depth > config.max_nesting && first_exceeded.is_none() {
+                        first_exceeded = Some(pos);
+                    }
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        if let Some(pos) = first_exceeded {
+            alerts.push(
+                BullshitAlert::builder(BullshitType::DeepNesting)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "Function `{}` nests {} levels deep (max {}), deepest at line {}",
+                        function.name,
+                        max_depth,
+                        config.max_nesting,
+                        byte_offset_to_line_col(code, deepest_at).line
+                    ))
+                    .suggestion("Flatten with early returns or extract helper functions")
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for functions whose bodies span more than `config.max_function_lines`
+/// Only body lines count - not the signature, braces or trailing blank lines.
+pub fn scan_long_functions(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let confidence = config.confidence_for(&BullshitType::LongFunction, 0.7);
+
+    for function in find_functions(code) {
+        let body_lines: Vec<&str> = function.body(code).lines().collect();
+        // The first entry is the rest of the `{` line, the last the text before `}`
+        let inner = match body_lines.len() {
+            0..=2 => &[][..],
+            n => &body_lines[1..n - 1],
+        };
+        let trailing_blank = inner
+            .iter()
+            .rev()
+            .take_while(|line| line.trim().is_empty())
+            .count();
+        let line_count = inner.len() - trailing_blank;
+
+        if line_count > config.max_function_lines {
+            alerts.push(
+                BullshitAlert::builder(BullshitType::LongFunction)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, function.start))
+                    .snippet(line_at(code, function.start))
+                    .why(format!(
+                        "Function `{}` body spans {} lines (max {})",
+                        function.name, line_count, config.max_function_lines
+                    ))
+                    .suggestion("Split the function into smaller, focused helpers")
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for blocks of `config.duplicate_window` non-trivial lines repeated
+/// within the file (whitespace-normalized, brace-only lines ignored)
+pub fn scan_duplicate_code(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let window = config.duplicate_window;
+    let confidence = config.confidence_for(&BullshitType::DuplicateCode, 0.7);
+
+    // (line number, normalized text) of every line worth comparing
+    let lines: Vec<(usize, String)> = code
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split_whitespace().collect::<Vec<_>>().join(" ")))
+        .filter(|(_, line)| line.chars().any(|c| c.is_alphanumeric()))
+        .collect();
+    if window == 0 || lines.len() < window * 2 {
+        return Ok(alerts);
+    }
+    // Real byte offsets: `lines()` drops the `\r` of CRLF endings
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut occurrences: HashMap<Vec<&str>, Vec<usize>> = HashMap::new();
+    for start in 0..=lines.len() - window {
+        let key = lines[start..start + window]
+            .iter()
+            .map(|(_, line)| line.as_str())
+            .collect();
+        occurrences.entry(key).or_default().push(start);
+    }
+
+    // Starts of each window's non-overlapping repeats, in window order
+    let mut repeats: Vec<Vec<usize>> = Vec::new();
+    for start in 0..=lines.len() - window {
+        let key: Vec<&str> = lines[start..start + window]
+            .iter()
+            .map(|(_, line)| line.as_str())
+            .collect();
+        let mut kept: Vec<usize> = Vec::new();
+        for &other in &occurrences[&key] {
+            if kept.last().is_none_or(|&last| other >= last + window) {
+                kept.push(other);
+            }
+        }
+        repeats.push(kept);
+    }
+
+    for (start, kept) in repeats.iter().enumerate() {
+        // Only the first window of each repeated run is reported, and only once
+        let continues_run = start > 0
+            && repeats[start - 1].len() == kept.len()
+            && repeats[start - 1]
+                .iter()
+                .zip(kept)
+                .all(|(prev, cur)| prev + 1 == *cur);
+        if kept.len() < 2 || kept[0] != start || continues_run {
+            continue;
+        }
+
+        let line_numbers: Vec<String> = kept.iter().map(|&i| lines[i].0.to_string()).collect();
+        let pos = line_starts[lines[start].0 - 1];
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::DuplicateCode)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "Block of {} lines repeated {} times, at lines {}",
+                    window,
+                    kept.len(),
+                    line_numbers.join(", ")
+                ))
+                .suggestion("Extract the repeated block into a shared function")
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Flag a file declaring more than `config.max_public_items` public items,
+/// with one alert at the top of the file - a coarse sign of a god module
+pub fn scan_public_items(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let item_regex = compile_regex(
+        r"(?m)^\s*pub(?:\([^)]*\))?\s+(?:(?:const|async|unsafe)\s+)*(?:fn|struct|enum|trait|type|const|static|mod|union)\b",
+    )?;
+
+    let count = item_regex.find_iter(code).count();
+    if count <= config.max_public_items {
+        return Ok(Vec::new());
+    }
+
+    let confidence = config.confidence_for(&BullshitType::OverEngineering, 0.7);
+    Ok(vec![BullshitAlert::builder(BullshitType::OverEngineering)
+        .confidence(confidence)
+        .location(SourceLocation::new(1, 1))
+        .snippet(code.lines().next().unwrap_or_default().trim().to_string())
+        .why(format!(
+            "File declares {} public items (max {}) - it's doing too many jobs",
+            count, config.max_public_items
+        ))
+        .suggestion("Split it into focused modules and re-export what callers need")
+        .build()])
+}
+
+/// Flag `impl` blocks defining more than `config.max_impl_methods` methods
+/// Associated consts and types, and functions nested in methods, don't count.
+pub fn scan_impl_sizes(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let impl_regex = compile_regex(r"(?m)^[ \t]*(?:(?:unsafe|default)\s+)?impl\b([^{;]*)\{")?;
+    let functions = find_functions(code);
+    let confidence = config.confidence_for(&BullshitType::OverEngineering, 0.7);
+
+    for cap in impl_regex.captures_iter(code) {
+        let (Some(whole), Some(header)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let open = whole.end() - 1;
+        let Some(close) = matching_brace(code, open) else {
+            continue;
+        };
+
+        let inside: Vec<&FnSpan> = functions
+            .iter()
+            .filter(|f| f.start > open && f.body_close < close)
+            .collect();
+        let methods = inside
+            .iter()
+            .filter(|f| {
+                !inside
+                    .iter()
+                    .any(|outer| outer.body_open < f.start && f.body_close < outer.body_close)
+            })
+            .count();
+        if methods <= config.max_impl_methods {
+            continue;
+        }
+
+        let pos = whole.start() + (whole.len() - whole.as_str().trim_start().len());
+        alerts.push(
+            BullshitAlert::builder(BullshitType::OverEngineering)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "impl for `{}` defines {} methods (max {}) - the type has too many jobs",
+                    impl_target(header.as_str()),
+                    methods,
+                    config.max_impl_methods
+                ))
+                .suggestion("Split the type, or move groups of methods into traits or helper types")
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// The implementing type named in an impl header (the part between `impl`
+/// and `{`): `<T> Display for Wrapper<T> where ...` gives `Wrapper<T>`
+fn impl_target(header: &str) -> String {
+    let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut rest = header.as_str();
+    if rest.starts_with('<') {
+        if let Some(close) = closing_delimiter(rest) {
+            rest = &rest[close + 1..];
+        }
+    }
+    let rest = rest.split(" where ").next().unwrap_or(rest);
+    let rest = rest.strip_suffix(" where").unwrap_or(rest);
+    rest.rsplit(" for ")
+        .next()
+        .unwrap_or(rest)
+        .trim()
+        .to_string()
+}
+
+/// Scan for over-generic declarations: a struct, enum, trait or impl with more
+/// than `config.max_type_params` type parameters, or a struct with no generics
+/// at all carrying a `PhantomData` marker
+/// Examples: `struct Pipeline<A, B, C, D, E>`, `struct Id { _m: PhantomData<u32> }`
+///
+/// Lifetimes and const generics don't count as type parameters.
+pub fn scan_generic_params(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let decl_regex = compile_regex(r"\b(struct|enum|union|trait|impl)\b")?;
+    let confidence = config.confidence_for(&BullshitType::OverEngineering, 0.65);
+    let strings = string_literal_ranges(code);
+
+    for cap in decl_regex.captures_iter(code) {
+        let (Some(whole), Some(kind)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let pos = whole.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos)) || code[line_start..pos].contains("//") {
+            continue;
+        }
+
+        // `impl<...>` carries its generics straight after the keyword,
+        // everything else after its name
+        let mut offset = whole.end();
+        if kind.as_str() != "impl" {
+            let name = code[offset..].trim_start();
+            let name_len = name
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(name.len());
+            if name_len == 0 {
+                continue;
+            }
+            offset = code.len() - name.len() + name_len;
+        }
+        let after = code[offset..].trim_start();
+        let generics_start = code.len() - after.len();
+        let params = if after.starts_with('<') {
+            let Some(close) = closing_delimiter(after) else {
+                continue;
+            };
+            split_top_level(&after[1..close], ',')
+        } else {
+            Vec::new()
+        };
+        let params: Vec<&str> = params
+            .into_iter()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        let type_params = params
+            .iter()
+            .filter(|p| !p.starts_with('\'') && !p.starts_with("const "))
+            .count();
+
+        let header_end = code[offset..]
+            .find(['{', ';'])
+            .map_or(code.len(), |i| offset + i);
+        let label = match kind.as_str() {
+            "impl" => format!("impl for `{}`", impl_target(&code[whole.end()..header_end])),
+            kind => format!("{} `{}`", kind, code[whole.end()..offset].trim()),
+        };
+
+        if type_params > config.max_type_params {
+            alerts.push(
+                BullshitAlert::builder(BullshitType::OverEngineering)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "{} takes {} type parameters (max {}) - generic machinery few callers need",
+                        label, type_params, config.max_type_params
+                    ))
+                    .suggestion(
+                        "Fix the parameters that only ever have one type, or group them \
+                         behind a trait with associated types",
+                    )
+                    .build(),
+            );
+        } else if kind.as_str() == "struct" && params.is_empty() {
+            // Braced or tuple body; unit structs end at `;`
+            let rest = &code[generics_start..];
+            let Some(open) = rest.find(['{', '(', ';']) else {
+                continue;
+            };
+            if rest[open..].starts_with(';') {
+                continue;
+            }
+            let Some(close) = closing_delimiter(&rest[open..]) else {
+                continue;
+            };
+            let Some(marker) = rest[open..open + close].find("PhantomData") else {
+                continue;
+            };
+
+            let marker_pos = generics_start + open + marker;
+            alerts.push(
+                BullshitAlert::builder(BullshitType::OverEngineering)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, marker_pos))
+                    .snippet(line_at(code, marker_pos))
+                    .why(format!(
+                        "{} has no type parameters for its `PhantomData` to mark",
+                        label
+                    ))
+                    .suggestion("Drop the `PhantomData` field, or make the struct generic over what it marks")
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for string literals longer than `config.max_string_literal_len` characters
+/// Example: a page of SQL or HTML pasted inline as a `&str`
+///
+/// Raw and multi-line strings count in full. Test code is skipped, where
+/// inline fixtures are the norm.
+pub fn scan_long_string_literals(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let confidence = config.confidence_for(&BullshitType::LongStringLiteral, 0.7);
+    let test_ranges = test_code_ranges(code);
+
+    for range in string_literal_ranges(code) {
+        let literal = &code[range.clone()];
+        // Contents sit between the first quote and the last one plus any `#`s
+        let (Some(open), Some(close)) = (literal.find('"'), literal.rfind('"')) else {
+            continue;
+        };
+        if open == close {
+            continue;
+        }
+        let len = literal[open + 1..close].chars().count();
+        let line_start = code[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        if len <= config.max_string_literal_len
+            || code[line_start..range.start].contains("//")
+            || test_ranges.iter().any(|r| r.contains(&range.start))
+        {
+            continue;
+        }
+
+        let lines = literal.lines().count();
+        // The literal's own line can be most of the file
+        let mut snippet = line_at(code, range.start);
+        if snippet.len() > config.max_snippet_length {
+            snippet.truncate(floor_char_boundary(&snippet, config.max_snippet_length));
+            snippet.push_str("...");
+        }
+        alerts.push(
+            BullshitAlert::builder(BullshitType::LongStringLiteral)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, range.start))
+                .snippet(snippet)
+                .why(format!(
+                    "String literal of {} characters over {} line{} (max {}) embedded in source",
+                    len,
+                    lines,
+                    if lines == 1 { "" } else { "s" },
+                    config.max_string_literal_len
+                ))
+                .suggestion(
+                    "Move the text to its own file and load it with `include_str!(\"...\")`",
+                )
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for allocations that a borrow or a cheaper call would replace
+/// Examples: `"x".to_string()`, `format!("{}", x)`, `.iter().cloned().collect()`,
+/// `let mut v = Vec::new();` filled by a `for` loop of `v.push(...)`, and a
+/// Vec collected only to be iterated again
+pub fn scan_needless_allocations(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    // Some of these are intentional, so keep confidence modest
+    let confidence = config.confidence_for(&BullshitType::NeedlessAllocation, 0.65);
+
+    let patterns = [
+        (
+            r#""(?:[^"\\]|\\.)*"\.to_string\(\)"#,
+            "String literal converted with .to_string()",
+            "Use String::from(\"...\") or keep it as a &str if ownership isn't needed",
+        ),
+        (
+            r#"format!\(\s*"\{\}"\s*,\s*[^,()]+(?:\([^()]*\))?\s*\)"#,
+            "format!(\"{}\", x) used just to stringify a single value",
+            "Use x.to_string() instead of format!(\"{}\", x)",
+        ),
+        (
+            r"\.iter\(\)\s*\.cloned\(\)\s*\.collect",
+            ".iter().cloned().collect() copies a whole collection",
+            "Use .to_vec(), or borrow the original collection",
+        ),
+    ];
+
+    for (pattern, why, sug) in patterns {
+        let regex = compile_regex(pattern)?;
+        for mat in regex.find_iter(code) {
+            alerts.push(
+                BullshitAlert::builder(BullshitType::NeedlessAllocation)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, mat.start()))
+                    .snippet(line_at(code, mat.start()))
+                    .why(format!("{}: `{}`", why, mat.as_str()))
+                    .suggestion(sug.to_string())
+                    .build(),
+            );
+        }
+    }
+
+    alerts.extend(scan_push_loops(code, confidence)?);
+    alerts.extend(scan_collect_then_iterate(code, config)?);
+    Ok(alerts)
+}
+
+/// Find a Vec collected and then iterated straight away, either in the same
+/// expression (`.collect::<Vec<_>>().iter()`) or through a binding whose one
+/// and only use is to iterate it - the second is likelier to be deliberate
+fn scan_collect_then_iterate(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let suggestion = "Keep the iterator lazy: chain on without collecting";
+
+    let inline_regex =
+        compile_regex(r"\.collect::<\s*Vec<[^>]*>\s*>\(\)\s*\.(?:iter|into_iter|iter_mut)\(\)")?;
+    let inline_confidence = config.confidence_for(&BullshitType::NeedlessAllocation, 0.8);
+    for mat in inline_regex.find_iter(code) {
+        alerts.push(
+            BullshitAlert::builder(BullshitType::NeedlessAllocation)
+                .confidence(inline_confidence)
+                .location(byte_offset_to_line_col(code, mat.start()))
+                .snippet(line_at(code, mat.start()))
+                .why("Collects into a Vec only to iterate it again")
+                .suggestion(suggestion)
+                .build(),
+        );
+    }
+
+    let binding_regex = compile_regex(
+        r"\blet\s+(\w+)\s*(?::\s*Vec<[^=;]*>\s*)?=[^;]*\.collect(?:::<\s*Vec<[^;]*>\s*>)?\(\)\s*;",
+    )?;
+    let binding_confidence = config.confidence_for(&BullshitType::NeedlessAllocation, 0.65);
+    let functions = find_functions(code);
+    for cap in binding_regex.captures_iter(code) {
+        let (Some(whole), Some(name)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        // A plain `.collect()` only counts when the binding says it's a Vec
+        if !whole.as_str().contains("Vec<") {
+            continue;
+        }
+        let scope_end = functions
+            .iter()
+            .filter(|f| f.body_open < whole.start() && whole.end() < f.body_close)
+            .map(|f| f.body_close)
+            .min()
+            .unwrap_or(code.len());
+        let rest = &code[whole.end()..scope_end];
+
+        let use_regex = compile_regex(&format!(r"\b{}\b", regex::escape(name.as_str())))?;
+        let uses: Vec<_> = use_regex.find_iter(rest).collect();
+        let [only] = uses.as_slice() else {
+            continue;
+        };
+        let after = rest[only.end()..].trim_start();
+        let before = rest[..only.start()].trim_end();
+        let iterated = after.starts_with(".iter()")
+            || after.starts_with(".into_iter()")
+            || before.ends_with(" in")
+            || before.ends_with(" in &");
+        if !iterated {
+            continue;
+        }
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::NeedlessAllocation)
+                .confidence(binding_confidence)
+                .location(byte_offset_to_line_col(code, whole.start()))
+                .snippet(line_at(code, whole.start()))
+                .why(format!(
+                    "`{}` is collected into a Vec and only ever iterated",
+                    name.as_str()
+                ))
+                .suggestion(suggestion)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Find an empty collection immediately filled by a `for` loop whose whole
+/// body is one `push`/`insert` into it - anything more and the loop may be
+/// doing real work, so it's left alone
+fn scan_push_loops(code: &str, confidence: f32) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let binding_regex = compile_regex(
+        r"\blet\s+mut\s+(\w+)\s*(?::[^=;]+)?=\s*(Vec|HashMap|HashSet)(?:::<[^;]*>)?::new\(\)\s*;",
+    )?;
+    let loop_regex = compile_regex(r"^\s*for\s+[^{;]+\{")?;
+
+    for cap in binding_regex.captures_iter(code) {
+        let (Some(whole), Some(name), Some(kind)) = (cap.get(0), cap.get(1), cap.get(2)) else {
+            continue;
+        };
+        let Some(for_loop) = loop_regex.find(&code[whole.end()..]) else {
+            continue;
+        };
+        let open = whole.end() + for_loop.end() - 1;
+        let Some(close) = matching_brace(code, open) else {
+            continue;
+        };
+
+        let body = code[open + 1..close].trim();
+        let method = if kind.as_str() == "Vec" {
+            "push"
+        } else {
+            "insert"
+        };
+        let fill = format!("{}.{}(", name.as_str(), method);
+        let single_statement = body.trim_end_matches(';').matches(';').count() == 0;
+        if !body.starts_with(&fill) || !single_statement || body.contains('{') {
+            continue;
+        }
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::NeedlessAllocation)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, whole.start()))
+                .snippet(line_at(code, whole.start()))
+                .why(format!(
+                    "`{}` starts as an empty {} and is filled one `{}` at a time by the loop below",
+                    name.as_str(),
+                    kind.as_str(),
+                    method
+                ))
+                .suggestion(format!(
+                    "Build it with .map(...).collect(), or use {}::with_capacity(n) if the loop must stay",
+                    kind.as_str()
+                ))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// A lock acquisition unwrapped on the spot, e.g. `m.lock().unwrap()`
+pub(crate) struct LockUnwrap {
+    /// Offset of the `.lock()`/`.read()`/`.write()` call
+    pub start: usize,
+    /// Offset of the `.unwrap()` that the generic unwrap pattern also matches
+    pub unwrap_at: usize,
+    /// `MutexAbuse` for `.lock()`, `RwLockAbuse` for `.read()`/`.write()`
+    pub issue_type: BullshitType,
+}
+
+/// Find every `.lock()`, `.read()` or `.write()` immediately followed by `.unwrap()`
+pub(crate) fn find_lock_unwraps(code: &str) -> Result<Vec<LockUnwrap>> {
+    let regex = compile_regex(r"\.(lock|read|write)\(\)\s*(\.unwrap\(\))")?;
+
+    Ok(regex
+        .captures_iter(code)
+        .filter_map(|cap| {
+            let (whole, method, unwrap) = (cap.get(0)?, cap.get(1)?, cap.get(2)?);
+            let issue_type = if method.as_str() == "lock" {
+                BullshitType::MutexAbuse
+            } else {
+                BullshitType::RwLockAbuse
+            };
+            Some(LockUnwrap {
+                start: whole.start(),
+                unwrap_at: unwrap.start(),
+                issue_type,
+            })
+        })
+        .collect())
+}
+
+/// Scan for lock guards unwrapped without handling poisoning
+/// Examples: `m.lock().unwrap()`, `cache.write().unwrap()`
+///
+/// `scan_code` drops the generic `UnwrapAbuse` alert on the same `.unwrap()`.
+pub fn scan_lock_unwraps(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    for lock in find_lock_unwraps(code)? {
+        let confidence = config.confidence_for(&lock.issue_type, 0.75);
+        let guard = if lock.issue_type == BullshitType::MutexAbuse {
+            "Mutex"
+        } else {
+            "RwLock"
+        };
+        alerts.push(
+            BullshitAlert::builder(lock.issue_type)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, lock.start))
+                .snippet(line_at(code, lock.start))
+                .why(format!(
+                    "{} guard unwrapped - a panic in any other holder poisons the lock and this panics too",
+                    guard
+                ))
+                .suggestion(
+                    "Handle poisoning: propagate the PoisonError, recover with \
+                     `.unwrap_or_else(PoisonError::into_inner)`, or use a non-poisoning lock",
+                )
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// A `.clone()`/`.to_owned()` on a value that is obviously `Copy`
+pub(crate) struct CopyClone {
+    /// Offset of the receiver
+    pub start: usize,
+    /// Offset of the `.clone()`/`.to_owned()` call
+    pub call_at: usize,
+    /// `clone` or `to_owned`
+    pub method: String,
+}
+
+/// Find clones of numeric, bool and char literals, parenthesized literal
+/// arithmetic, and `.len()`/`.count()` results
+///
+/// Without types, anything less obvious (a variable, a field) is left alone.
+pub(crate) fn find_copy_clones(code: &str) -> Result<Vec<CopyClone>> {
+    let regex = compile_regex(
+        r"(?x)
+        (?:
+            \b\d[\d_]*(?:\.\d[\d_]*)?(?:e[+-]?\d+)?(?:[iu](?:8|16|32|64|128|size)|f32|f64)?
+          | \b(?:true|false)
+          | '(?:[^'\\]|\\.)'
+          | \((?:\s*[\d_.]+\s*[-+*/%])+\s*[\d_.]+\s*\)
+          | \.(?:len|count)\(\)
+        )
+        (\.(clone|to_owned)\(\))",
+    )?;
+    let strings = string_literal_ranges(code);
+
+    Ok(regex
+        .captures_iter(code)
+        .filter_map(|cap| {
+            let (whole, call, method) = (cap.get(0)?, cap.get(1)?, cap.get(2)?);
+            // `self.0.clone()` clones a tuple field, not the literal 0
+            let after_dot =
+                code[..whole.start()].ends_with('.') && !whole.as_str().starts_with('.');
+            let in_string = strings
+                .iter()
+                .any(|r| r.contains(&whole.start()) && r.start != whole.start());
+            if after_dot || in_string {
+                return None;
+            }
+            Some(CopyClone {
+                start: whole.start(),
+                call_at: call.start(),
+                method: method.as_str().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Scan for `.clone()`/`.to_owned()` on values that are obviously `Copy`
+/// Examples: `5u32.clone()`, `true.clone()`, `(1 + 2).clone()`, `v.len().clone()`
+///
+/// `scan_code` drops the generic `CloneAbuse` alert on the same `.clone()`.
+pub fn scan_copy_clones(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let confidence = config.confidence_for(&BullshitType::CloneAbuse, 0.85);
+
+    for clone in find_copy_clones(code)? {
+        let receiver = &code[clone.start..clone.call_at];
+        alerts.push(
+            BullshitAlert::builder(BullshitType::CloneAbuse)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, clone.start))
+                .snippet(line_at(code, clone.start))
+                .why(format!(
+                    "`{}.{}()` on a Copy value - it is copied anyway",
+                    receiver, clone.method
+                ))
+                .suggestion(format!("Remove the `.{}()`", clone.method))
+                // Noise rather than a cost, but often a sign of copy-paste
+                .severity(0.5)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan non-test code for debug output left behind
+/// Examples: `dbg!(x)`, `println!("here")`, `eprintln!("{:?}", state)`
+///
+/// `dbg!` is almost never intentional; the print macros may be real CLI
+/// output, so they're weaker and skipped when `config.allow_print_macros`.
+pub fn scan_debug_leftovers(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let macro_regex = compile_regex(r"\b(dbg|println|eprintln|print|eprint)!\s*[(\[{]")?;
+    let skipped: Vec<_> = string_literal_ranges(code)
+        .into_iter()
+        .chain(test_code_ranges(code))
+        .collect();
+
+    for cap in macro_regex.captures_iter(code) {
+        let (Some(whole), Some(name)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let pos = whole.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if code[line_start..pos].contains("//") || skipped.iter().any(|r| r.contains(&pos)) {
+            continue;
+        }
+
+        let is_dbg = name.as_str() == "dbg";
+        if !is_dbg && config.allow_print_macros {
+            continue;
+        }
+        let (base, severity) = if is_dbg { (0.9, 0.8) } else { (0.65, 0.5) };
+        let confidence = config.confidence_for(&BullshitType::DebugLeftover, base);
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::DebugLeftover)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!("`{}!` left in non-test code", name.as_str()))
+                .suggestion(if is_dbg {
+                    "Remove the `dbg!` before merging".to_string()
+                } else {
+                    "Use `log`/`tracing`, or add the file to `print_allowed_paths` if it's CLI output"
+                        .to_string()
+                })
+                .severity(severity)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for `use path::*;` glob imports outside test code
+/// Example: `use crate::internal::*;` hides where names come from
+///
+/// Paths in `config.glob_import_allowlist` (preludes by default) are skipped.
+pub fn scan_glob_imports(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let glob_regex =
+        compile_regex(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+((?:::)?\w+(?:::\w+)*)::\*\s*;")?;
+    let test_ranges = test_code_ranges(code);
+    let confidence = config.confidence_for(&BullshitType::CargoCult, 0.7);
+
+    for cap in glob_regex.captures_iter(code) {
+        let Some(path) = cap.get(1) else { continue };
+        if test_ranges.iter().any(|r| r.contains(&path.start()))
+            || is_allowed_glob(path.as_str(), &config.glob_import_allowlist)
+        {
+            continue;
+        }
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::CargoCult)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, path.start()))
+                .snippet(line_at(code, path.start()))
+                .why(format!(
+                    "Glob import of `{}::*` pulls every public name into scope",
+                    path.as_str()
+                ))
+                .suggestion(format!(
+                    "Import the items you use: `use {}::{{...}};`",
+                    path.as_str()
+                ))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Whether `path` is covered by an allowlist entry: an exact path or parent
+/// of it, or a bare segment appearing anywhere in it
+fn is_allowed_glob(path: &str, allowlist: &[String]) -> bool {
+    let path = path.trim_start_matches("::");
+    allowlist.iter().any(|entry| {
+        let entry = entry.trim_start_matches("::");
+        if entry.contains("::") {
+            path == entry || path.starts_with(&format!("{}::", entry))
+        } else {
+            path.split("::").any(|segment| segment == entry)
+        }
+    })
+}
+
+/// Scan for functions returning stringly-typed errors
+/// Examples: `fn f() -> Result<u32, String>`, `fn g() -> Result<(), Box<dyn Error>>`
+///
+/// `Box<dyn Error>` is reasonable in application code, so it's reported at a
+/// lower confidence. `main` and test code are skipped.
+pub fn scan_stringly_errors(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let boxed_regex = compile_regex(r"Box<\s*dyn\s+(?:std::error::|core::error::)?Error\b")?;
+    let test_ranges = test_code_ranges(code);
+
+    for function in find_functions(code) {
+        if function.name == "main" || test_ranges.iter().any(|r| r.contains(&function.start)) {
+            continue;
+        }
+        let Some(return_type) = return_type(code, &function) else {
+            continue;
+        };
+
+        let (kind, base) = if let Some(error_type) = result_error_type(return_type)
+            .filter(|e| matches!(*e, "String" | "&str" | "&'static str"))
+        {
+            (error_type.to_string(), 0.75)
+        } else if boxed_regex.is_match(return_type) {
+            ("Box<dyn Error>".to_string(), 0.65)
+        } else {
+            continue;
+        };
+        let confidence = config.confidence_for(&BullshitType::StringlyTypedError, base);
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::StringlyTypedError)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, function.start))
+                .snippet(line_at(code, function.start))
+                .why(format!(
+                    "{}() returns `{}` errors - callers can only print them, not handle them",
+                    function.name, kind
+                ))
+                .suggestion("Return a concrete error enum (e.g. derived with thiserror)")
+                .severity(0.5)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// The return type text after `->` in `function`'s signature, up to any `where`
+fn return_type<'a>(code: &'a str, function: &FnSpan) -> Option<&'a str> {
+    let name_end =
+        function.start + code[function.start..].find(&function.name)? + function.name.len();
+    let (_, params_close) = parameter_list(code, name_end)?;
+    let rest = code[params_close + 1..function.body_open].trim_start();
+    let return_type = rest.strip_prefix("->")?;
+    let return_type = match return_type.find("where") {
+        Some(i) => &return_type[..i],
+        None => return_type,
+    };
+    Some(return_type.trim())
+}
+
+/// The error type of a `Result<T, E>` return type, bracket-balanced
+fn result_error_type(return_type: &str) -> Option<&str> {
+    let start = return_type.find("Result<")?;
+    // `std::result::Result<..>` but not `MyResult<..>`
+    let prefix = &return_type[..start];
+    if !(prefix.is_empty() || prefix.ends_with("::")) {
+        return None;
+    }
+    let open = start + "Result".len();
+    let close = open + closing_delimiter(&return_type[open..])?;
+    match split_top_level(&return_type[open + 1..close], ',')[..] {
+        [_, error_type] => Some(error_type.trim()),
+        _ => None,
+    }
+}
+
+/// Scan for method chains longer than `config.max_chain_len` calls, or
+/// unwrapping more than once
+/// Example: `a.b().c().unwrap().d().unwrap()`
+///
+/// Chains may span lines; field accesses and `?` continue a chain without
+/// counting as calls.
+pub fn scan_method_chains(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let call_regex = compile_regex(r"\.\s*\w+\s*(?:::\s*<[^>]*>\s*)?\(")?;
+    let strings = string_literal_ranges(code);
+    // Dots already counted as part of an earlier chain
+    let mut covered = HashSet::new();
+
+    for mat in call_regex.find_iter(code) {
+        let pos = mat.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if covered.contains(&pos)
+            || strings.iter().any(|r| r.contains(&pos))
+            || code[line_start..pos].contains("//")
+        {
+            continue;
+        }
+
+        let segments = chain_segments(code, pos);
+        covered.extend(segments.iter().map(|(dot, _)| *dot));
+        let calls: Vec<&str> = segments.iter().filter_map(|(_, call)| *call).collect();
+        let unwraps = calls.iter().filter(|name| **name == "unwrap").count();
+        if calls.len() <= config.max_chain_len && unwraps < 2 {
+            continue;
+        }
+
+        let base = if unwraps >= 2 { 0.75 } else { 0.65 };
+        let confidence = config.confidence_for(&BullshitType::LongMethodChain, base);
+        let why_bs = if unwraps >= 2 {
+            format!(
+                "Chain of {} method calls unwraps {} times - any of them can panic",
+                calls.len(),
+                unwraps
+            )
+        } else {
+            format!(
+                "Chain of {} method calls (max {}) is hard to read and debug",
+                calls.len(),
+                config.max_chain_len
+            )
+        };
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::LongMethodChain)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(why_bs)
+                .suggestion(
+                    "Bind intermediate results to named variables and handle errors with `?`",
+                )
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// The `.name` segments of the chain starting at the `.` at `start`, as
+/// (offset of the dot, method name if it's a call)
+fn chain_segments(code: &str, start: usize) -> Vec<(usize, Option<&str>)> {
+    let mut segments = Vec::new();
+    let mut pos = start;
+
+    loop {
+        let dot = pos + (code[pos..].len() - code[pos..].trim_start().len());
+        let rest = &code[dot..];
+        if !rest.starts_with('.') || rest.starts_with("..") {
+            break;
+        }
+        let name_start = dot + 1 + (rest[1..].len() - rest[1..].trim_start().len());
+        let name_len = code[name_start..]
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(code.len() - name_start);
+        if name_len == 0 {
+            break;
+        }
+        let name = &code[name_start..name_start + name_len];
+        pos = name_start + name_len;
+
+        // Turbofish, then the argument list if this is a call
+        let after = code[pos..].trim_start();
+        let mut offset = code.len() - after.len();
+        if let Some(generics) = after.strip_prefix("::") {
+            let generics_start = code.len() - generics.trim_start().len();
+            match closing_delimiter(&code[generics_start..]) {
+                Some(close) => offset = generics_start + close + 1,
+                None => break,
+            }
+        }
+        let after = code[offset..].trim_start();
+        if after.starts_with('(') {
+            let open = code.len() - after.len();
+            let Some(close) = closing_delimiter(&code[open..]) else {
+                break;
+            };
+            segments.push((dot, Some(name)));
+            pos = open + close + 1;
+        } else {
+            segments.push((dot, None));
+        }
+        pos += code[pos..].len() - code[pos..].trim_start_matches('?').len();
+    }
+
+    segments
+}
+
+/// Scan for doubly-wrapped types
+/// Examples: `Option<Option<u32>>`, `Option<Result<T, E>>`, `Result<Result<T, E>, E>`
+///
+/// The inner wrapper must be the outer one's (first) type argument, so
+/// `Option<(Option<u8>, u8)>` or `Result<Vec<u8>, Option<E>>` don't count.
+/// `Result<Option<T>, E>` is a normal "fallible lookup" and isn't flagged.
+pub fn scan_nested_wrappers(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let wrapper_regex = compile_regex(r"\b(Option|Result)\s*<")?;
+    let confidence = config.confidence_for(&BullshitType::NestedWrapper, 0.75);
+    let strings = string_literal_ranges(code);
+    // End of the last flagged type, so `Option<Option<Option<T>>>` is one alert
+    let mut flagged_until = 0;
+
+    for cap in wrapper_regex.captures_iter(code) {
+        let (Some(whole), Some(outer)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let pos = whole.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if pos < flagged_until
+            || strings.iter().any(|r| r.contains(&pos))
+            || code[line_start..pos].contains("//")
+        {
+            continue;
+        }
+
+        let open = whole.end() - 1;
+        let Some(close) = closing_delimiter(&code[open..]).map(|c| open + c) else {
+            continue;
+        };
+        let args = split_top_level(&code[open + 1..close], ',');
+        let Some(inner) = args.first().map(|arg| arg.trim()) else {
+            continue;
+        };
+        let inner_name = ["Option", "Result"].into_iter().find(|name| {
+            inner
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with('<'))
+        });
+        let Some(inner_name) = inner_name else {
+            continue;
+        };
+        if outer.as_str() == "Result" && inner_name == "Option" {
+            continue;
+        }
+
+        flagged_until = close;
+        let nested = &code[pos..=close];
+        alerts.push(
+            BullshitAlert::builder(BullshitType::NestedWrapper)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "`{}` nests {} inside {} - callers must unwrap two layers whose meanings blur",
+                    nested.split_whitespace().collect::<Vec<_>>().join(" "),
+                    inner_name,
+                    outer.as_str()
+                ))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for `.len()` compared with zero where `.is_empty()` says it directly
+/// Examples: `v.len() == 0`, `v.len() != 0`, `v.len() > 0`, `v.len() < 1`
+pub fn scan_len_zero_comparisons(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let len_regex =
+        compile_regex(r"\b((?:[A-Za-z_]\w*\.)*[A-Za-z_]\w*)\.len\(\)\s*(==|!=|>=|>|<)\s*([01])\b")?;
+    let confidence = config.confidence_for(&BullshitType::LenZeroComparison, 0.8);
+    let strings = string_literal_ranges(code);
+
+    for cap in len_regex.captures_iter(code) {
+        let (Some(whole), Some(receiver), Some(op), Some(bound)) =
+            (cap.get(0), cap.get(1), cap.get(2), cap.get(3))
+        else {
+            continue;
+        };
+        let pos = whole.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos)) || code[line_start..pos].contains("//") {
+            continue;
+        }
+        let empty = match (op.as_str(), bound.as_str()) {
+            ("==", "0") | ("<", "1") => true,
+            ("!=", "0") | (">", "0") | (">=", "1") => false,
+            _ => continue,
+        };
+
+        let replacement = format!(
+            "{}{}.is_empty()",
+            if empty { "" } else { "!" },
+            receiver.as_str()
+        );
+        alerts.push(
+            BullshitAlert::builder(BullshitType::LenZeroComparison)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "`{}` checks for emptiness the long way",
+                    whole.as_str()
+                ))
+                .suggestion(format!("Use `{}`", replacement))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for `unwrap_or`-style fallbacks given a call, which runs even when
+/// the fallback isn't used
+/// Examples: `opt.unwrap_or(make_default())`, `res.ok_or(Error::new("missing"))`
+pub fn scan_eager_fallbacks(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let fallback_regex = compile_regex(r"\.(unwrap_or|ok_or|map_or)\(")?;
+    let constructor_regex = compile_regex(r"^(?:[a-z_]\w*::)*[A-Z]\w*\(")?;
+    let accessor_regex = compile_regex(r"\.[a-z_]\w*\(\)")?;
+    let confidence = config.confidence_for(&BullshitType::EagerFallback, 0.7);
+    let strings = string_literal_ranges(code);
+
+    for cap in fallback_regex.captures_iter(code) {
+        let (Some(whole), Some(method)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let pos = whole.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos)) || code[line_start..pos].contains("//") {
+            continue;
+        }
+        // Only the first argument is the fallback (`map_or` takes a closure second)
+        let Some(fallback) = first_argument(code, whole.end() - 1) else {
+            continue;
+        };
+        // A call, but not a tuple struct or variant like `Some(0)`, nor only
+        // cheap accessors like `code.len()`
+        if !accessor_regex.replace_all(fallback, "").contains('(')
+            || constructor_regex.is_match(fallback)
+        {
+            continue;
+        }
+
+        let method = method.as_str();
+        alerts.push(
+            BullshitAlert::builder(BullshitType::EagerFallback)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos + 1))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "`{}` is evaluated even when `{}` doesn't need the fallback",
+                    fallback, method
+                ))
+                .suggestion(format!(
+                    "Use `{}_else(|| {}{})` so it only runs when needed",
+                    method,
+                    fallback,
+                    if method == "map_or" { ", ..." } else { "" }
+                ))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// The trimmed first argument of the call whose `(` is at `open`
+fn first_argument(code: &str, open: usize) -> Option<&str> {
+    let mut depth = 0usize;
+
+    for (i, ch) in code[open..].char_indices() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(code[open + 1..open + i].trim());
+                }
+            }
+            ',' if depth == 1 => return Some(code[open + 1..open + i].trim()),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Scan for integer `as` casts that can wrap or truncate
+/// Example: `big_u64 as usize` (truncates on 32-bit targets), `offset_i32 as u32`
+///
+/// Without type inference the source type comes from a literal suffix, a
+/// `name: type` declaration in the same code, or a `_u64`-style name suffix;
+/// casts from anything else are left alone.
+pub fn scan_lossy_casts(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let cast_regex = compile_regex(
+        r"\b((?:[A-Za-z_]\w*\.)*[A-Za-z_]\w*|\d[\d_]*(?:[iu](?:8|16|32|64|128|size)))\s+as\s+([iu](?:8|16|32|64|128|size))\b",
+    )?;
+    let declared_regex = compile_regex(
+        r"\b([A-Za-z_]\w*)\s*:\s*(?:&\s*)?(?:mut\s+)?([iu](?:8|16|32|64|128|size))\b",
+    )?;
+    let confidence = config.confidence_for(&BullshitType::LossyCast, 0.7);
+    let strings = string_literal_ranges(code);
+
+    // First `name: type` declaration of each name
+    let mut declared = HashMap::new();
+    for cap in declared_regex.captures_iter(code) {
+        if let (Some(name), Some(ty)) = (cap.get(1), cap.get(2)) {
+            declared.entry(name.as_str()).or_insert(ty.as_str());
+        }
+    }
+
+    for cap in cast_regex.captures_iter(code) {
+        let (Some(expr), Some(target)) = (cap.get(1), cap.get(2)) else {
+            continue;
+        };
+        let pos = expr.start();
+        let line_start = code[..pos].rfind('\n').map_or(0, |i| i + 1);
+        if strings.iter().any(|r| r.contains(&pos)) || code[line_start..pos].contains("//") {
+            continue;
+        }
+        let Some(source) = integer_source_type(&declared, expr.as_str()) else {
+            continue;
+        };
+        let target = target.as_str();
+        if !cast_can_overflow(&source, target) {
+            continue;
+        }
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::LossyCast)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, pos))
+                .snippet(line_at(code, pos))
+                .why(format!(
+                    "`{} as {}` silently wraps when the {} doesn't fit",
+                    expr.as_str(),
+                    target,
+                    source
+                ))
+                .suggestion(format!(
+                    "Use `{}::try_from({})?` so out-of-range values are an error",
+                    target,
+                    expr.as_str()
+                ))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Rust's primitive integer types
+const INTEGER_TYPES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// The integer type of a cast operand, when the code says what it is
+///
+/// `declared` maps names to the type of their `name: type` declaration.
+fn integer_source_type(declared: &HashMap<&str, &str>, expr: &str) -> Option<String> {
+    if expr.starts_with(|c: char| c.is_ascii_digit()) {
+        let suffix = INTEGER_TYPES.iter().find(|ty| expr.ends_with(*ty));
+        return suffix.map(|ty| ty.to_string());
+    }
+
+    let name = expr.rsplit('.').next().unwrap_or(expr);
+    if let Some(ty) = declared.get(name) {
+        return Some(ty.to_string());
+    }
+
+    let suffix = INTEGER_TYPES.iter().find(|ty| {
+        name.strip_suffix(*ty)
+            .is_some_and(|rest| rest.ends_with('_'))
+    });
+    suffix.map(|ty| ty.to_string())
+}
+
+/// Whether some `source` value doesn't fit in `target` on some platform
+/// (`usize`/`isize` may be 32 or 64 bits)
+fn cast_can_overflow(source: &str, target: &str) -> bool {
+    // (signed, narrowest width, widest width)
+    let shape = |ty: &str| -> (bool, u32, u32) {
+        let signed = ty.starts_with('i');
+        match &ty[1..] {
+            "size" => (signed, 32, 64),
+            bits => {
+                let bits = bits.parse().unwrap_or(128);
+                (signed, bits, bits)
+            }
+        }
+    };
+    let (source_signed, _, source_max) = shape(source);
+    let (target_signed, target_min, _) = shape(target);
+
+    match (source_signed, target_signed) {
+        (true, false) => true,
+        (false, true) => source_max >= target_min,
+        _ => source_max > target_min,
+    }
+}
+
+/// Scan for `static mut` declarations, and `unsafe` blocks that touch them
+/// Example: `static mut COUNTER: u32 = 0;` ... `unsafe { COUNTER += 1 }`
+pub fn scan_mutable_statics(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let static_regex = compile_regex(r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?\s+)?static\s+mut\s+(\w+)")?;
+    let confidence = config.confidence_for(&BullshitType::MutableStatic, 0.9);
+    let mut names = Vec::new();
+
+    for cap in static_regex.captures_iter(code) {
+        let Some(name) = cap.get(1) else {
+            continue;
+        };
+        names.push(name.as_str());
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MutableStatic)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, name.start()))
+                .snippet(line_at(code, name.start()))
+                .why(format!(
+                    "`static mut {}` can be read and written from any thread with no synchronization",
+                    name.as_str()
+                ))
+                .suggestion("Use an atomic, a Mutex/RwLock, or OnceLock/LazyLock for one-time init")
+                .severity(0.9)
+                .build(),
+        );
+    }
+    if names.is_empty() {
+        return Ok(alerts);
+    }
+
+    let unsafe_regex = compile_regex(r"\bunsafe\s*\{")?;
+    let access_confidence = config.confidence_for(&BullshitType::MutableStatic, 0.7);
+    for mat in unsafe_regex.find_iter(code) {
+        let Some(close) = matching_brace(code, mat.end() - 1) else {
+            continue;
+        };
+        let body = &code[mat.end()..close];
+        let touched: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| {
+                body.match_indices(name).any(|(i, _)| {
+                    let before = body[..i].chars().next_back();
+                    let after = body[i + name.len()..].chars().next();
+                    !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+                        && !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+                })
+            })
+            .collect();
+        if touched.is_empty() {
+            continue;
+        }
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MutableStatic)
+                .confidence(access_confidence)
+                .location(byte_offset_to_line_col(code, mat.start()))
+                .snippet(line_at(code, mat.start()))
+                .why(format!(
+                    "unsafe block accesses static mut {}",
+                    touched.join(", ")
+                ))
+                .suggestion("Replace the static mut so this access needs no unsafe")
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan `async fn` bodies for calls that block the executor thread
+/// Examples: `std::thread::sleep(d)`, `std::fs::read_to_string(p)`, a sync
+/// `m.lock()` (one followed by `.await` is an async mutex and fine)
+///
+/// Functions nested in the body and `spawn_blocking(...)` arguments are
+/// skipped, since they don't run on the executor.
+pub fn scan_blocking_in_async(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let blocking_regex = compile_regex(
+        r"\b(?:std::)?thread::sleep\s*\(|\b(?:std::)?fs::\w+\s*\(|\bFile::(?:open|create)\s*\(|\.lock\(\)|\breqwest::blocking::",
+    )?;
+    let offload_regex = compile_regex(r"\bspawn_blocking\s*\(")?;
+    let confidence = config.confidence_for(&BullshitType::BlockingInAsync, 0.8);
+    let functions = find_functions(code);
+
+    for function in functions.iter().filter(|f| is_async_fn(code, f)) {
+        let body = function.body_open + 1..function.body_close;
+        let nested: Vec<std::ops::Range<usize>> = functions
+            .iter()
+            .filter(|f| body.contains(&f.start))
+            .map(|f| f.start..f.body_close)
+            .chain(
+                offload_regex
+                    .find_iter(&code[body.clone()])
+                    .filter_map(|m| {
+                        let open = body.start + m.end() - 1;
+                        closing_delimiter(&code[open..]).map(|close| open..open + close)
+                    }),
+            )
+            .collect();
+
+        for call in blocking_regex.find_iter(&code[body.clone()]) {
+            let pos = body.start + call.start();
+            // `tokio::fs::read(..)` and friends are the async versions
+            if nested.iter().any(|r| r.contains(&pos)) || code[..pos].ends_with("::") {
+                continue;
+            }
+            if call.as_str() == ".lock()" && code[body.start + call.end()..].starts_with(".await") {
+                continue;
+            }
+
+            let blocking = call
+                .as_str()
+                .trim_end_matches(['(', ' '])
+                .trim_start_matches('.');
+            alerts.push(
+                BullshitAlert::builder(BullshitType::BlockingInAsync)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, pos))
+                    .snippet(line_at(code, pos))
+                    .why(format!(
+                        "`{}` blocks the executor thread inside async fn `{}`",
+                        blocking, function.name
+                    ))
+                    .suggestion(
+                        "Use the async equivalent (tokio::time::sleep, tokio::fs, an async \
+                         mutex) or move the work into spawn_blocking",
+                    )
+                    // Stalls every task on the thread, not just this one
+                    .severity((confidence + 0.1).min(1.0))
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Whether the `fn` keyword of `function` is preceded by `async` (allowing
+/// `async unsafe fn` and `async extern "C" fn`-style qualifiers)
+fn is_async_fn(code: &str, function: &FnSpan) -> bool {
+    let line_start = code[..function.start].rfind('\n').map_or(0, |i| i + 1);
+    code[line_start..function.start]
+        .split_whitespace()
+        .any(|word| word == "async")
+}
+
+/// Scan for loops that poll a condition by sleeping between checks
+/// Examples: `loop { if done() { break; } sleep(d); }`, `while !ready { sleep(d); }`
+///
+/// A `loop` only counts if it can `break` or `return`; each sleep is
+/// attributed to the innermost loop around it.
+pub fn scan_polling_loops(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let loop_regex = compile_regex(r"\b(?:loop|while\b[^{;]*)\s*\{")?;
+    let sleep_regex = compile_regex(r"\bsleep\s*\(")?;
+    let exit_regex = compile_regex(r"\b(?:break|return)\b")?;
+    let confidence = config.confidence_for(&BullshitType::PollingLoop, 0.8);
+    let strings = string_literal_ranges(code);
+    let in_string = |pos: usize| strings.iter().any(|r| r.contains(&pos));
+
+    // (keyword offset, body range) of every loop
+    let loops: Vec<(usize, std::ops::Range<usize>)> = loop_regex
+        .find_iter(code)
+        .filter(|m| !in_string(m.start()))
+        .filter_map(|m| {
+            let open = m.end() - 1;
+            matching_brace(code, open).map(|close| (m.start(), open + 1..close))
+        })
+        .collect();
+
+    let mut polling = HashSet::new();
+    for sleep in sleep_regex.find_iter(code) {
+        if in_string(sleep.start()) {
+            continue;
+        }
+        let innermost = loops
+            .iter()
+            .filter(|(_, body)| body.contains(&sleep.start()))
+            .max_by_key(|(_, body)| body.start);
+        if let Some((start, body)) = innermost {
+            let is_while = code[*start..].starts_with("while");
+            if is_while || exit_regex.is_match(&code[body.clone()]) {
+                polling.insert(*start);
+            }
+        }
+    }
+
+    let mut starts: Vec<usize> = polling.into_iter().collect();
+    starts.sort_unstable();
+    for start in starts {
+        alerts.push(
+            BullshitAlert::builder(BullshitType::PollingLoop)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, start))
+                .snippet(line_at(code, start))
+                .why("Loop sleeps between checks of a condition - a busy-wait that adds latency and burns wakeups")
+                // Worse than a lone sleep: it repeats for as long as the wait lasts
+                .severity((confidence + 0.05).min(1.0))
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for functions with more `return` statements than `config.max_returns`
+///
+/// Opt-in (see [`DetectConfig::enabled_types`]) for codebases that prefer a
+/// single exit; returns in nested functions count towards those instead.
+pub fn scan_early_returns(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let return_regex = compile_regex(r"\breturn\b")?;
+    let confidence = config.confidence_for(&BullshitType::EarlyReturn, 0.62);
+    let strings = string_literal_ranges(code);
+    let functions = find_functions(code);
+
+    for function in &functions {
+        let body = function.body_open + 1..function.body_close;
+        let skipped: Vec<std::ops::Range<usize>> = functions
+            .iter()
+            .filter(|f| body.contains(&f.start))
+            .map(|f| f.start..f.body_close)
+            .chain(strings.iter().cloned())
+            .collect();
+        let returns = return_regex
+            .find_iter(&code[body.clone()])
+            .map(|m| body.start + m.start())
+            .filter(|pos| !skipped.iter().any(|r| r.contains(pos)))
+            .count();
+        if returns <= config.max_returns {
+            continue;
+        }
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::EarlyReturn)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, function.start))
+                .snippet(line_at(code, function.start))
+                .why(format!(
+                    "{}() has {} return statements (max {})",
+                    function.name, returns, config.max_returns
+                ))
+                // Stylistic: only a complexity signal
+                .severity(0.3)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// How often a function uses each error-handling style
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ErrorStyleCounts {
+    /// `?` propagation
+    pub question_marks: usize,
+    /// `.unwrap()` and `.expect(..)`
+    pub unwraps: usize,
+    /// `match` arms on `Err(..)`
+    pub result_matches: usize,
+}
+
+impl ErrorStyleCounts {
+    /// Number of distinct styles used at least once
+    pub fn styles(&self) -> usize {
+        [self.question_marks, self.unwraps, self.result_matches]
+            .iter()
+            .filter(|&&n| n > 0)
+            .count()
+    }
+
+    /// e.g. "3 `?`, 1 `.unwrap()`", leaving out unused styles
+    fn describe(&self) -> String {
+        [
+            (self.question_marks, "`?`"),
+            (self.unwraps, "`.unwrap()`"),
+            (self.result_matches, "`match` on Result"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, style)| format!("{} {}", n, style))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// Count the error-handling styles in each function body; nested
+/// functions are counted on their own
+pub(crate) fn error_style_counts(code: &str) -> Result<Vec<(FnSpan, ErrorStyleCounts)>> {
+    let question_regex = compile_regex(r"[\w)\]]\?")?;
+    let unwrap_regex = compile_regex(r"\.(?:unwrap|expect)\(")?;
+    let err_arm_regex = compile_regex(r"\bErr\s*\([^)]*\)\s*=>")?;
+    let strings = string_literal_ranges(code);
+    let functions = find_functions(code);
+
+    let mut counts = Vec::new();
+    for function in &functions {
+        let body = function.body_open + 1..function.body_close;
+        let nested: Vec<std::ops::Range<usize>> = functions
+            .iter()
+            .filter(|f| body.contains(&f.start))
+            .map(|f| f.start..f.body_close)
+            .collect();
+        let count = |regex: &regex::Regex| {
+            regex
+                .find_iter(&code[body.clone()])
+                .map(|m| body.start + m.start())
+                .filter(|pos| !strings.iter().chain(&nested).any(|r| r.contains(pos)))
+                .count()
+        };
+
+        counts.push((
+            function.clone(),
+            ErrorStyleCounts {
+                question_marks: count(&question_regex),
+                unwraps: count(&unwrap_regex),
+                result_matches: count(&err_arm_regex),
+            },
+        ));
+    }
+
+    Ok(counts)
+}
+
+/// Scan for functions that handle errors more than one way
+/// Examples: `?` on one call and `.unwrap()` on the next
+///
+/// Opt-in (see [`DetectConfig::enabled_types`]), since a deliberate
+/// `.expect("invariant")` next to `?` is often fine. Reported at a low
+/// severity, and test code is skipped.
+pub fn scan_mixed_error_handling(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let confidence = config.confidence_for(&BullshitType::MixedErrorHandling, 0.62);
+    let test_ranges = test_code_ranges(code);
+
+    for (function, counts) in error_style_counts(code)? {
+        if counts.styles() < 2 || test_ranges.iter().any(|r| r.contains(&function.start)) {
+            continue;
+        }
+        alerts.push(
+            BullshitAlert::builder(BullshitType::MixedErrorHandling)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, function.start))
+                .snippet(line_at(code, function.start))
+                .why(format!(
+                    "{}() mixes {} error-handling styles: {}",
+                    function.name,
+                    counts.styles(),
+                    counts.describe()
+                ))
+                // Informational: a consistency nudge, not a bug
+                .severity(0.3)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for errors that are silently discarded
+/// Examples: `let _ = risky();`, `risky().ok();`, `Err(_) => {}`
+pub fn scan_swallowed_errors(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    // An empty `Err` arm is unambiguous; the other shapes are sometimes deliberate
+    let patterns = [
+        (
+            r"Err\(\s*\w*\s*\)\s*=>\s*(?:\{\s*\}|\(\))",
+            0.9,
+            "Empty Err arm silently swallows the error",
+        ),
+        (
+            r"\blet\s+_\s*=\s*[^;]*\([^;]*\)\s*;",
+            0.7,
+            "Result discarded with `let _ =`",
+        ),
+        (
+            r"\.ok\(\)\s*;",
+            0.7,
+            "Result converted with .ok() and dropped",
+        ),
+    ];
+
+    for (pattern, base, why) in patterns {
+        let confidence = config.confidence_for(&BullshitType::SwallowedError, base);
+        let regex = compile_regex(pattern)?;
+        for mat in regex.find_iter(code) {
+            // `let _ = ();` has nothing to swallow
+            if mat.as_str().replace(char::is_whitespace, "") == "let_=();" {
+                continue;
+            }
+            alerts.push(
+                BullshitAlert::builder(BullshitType::SwallowedError)
+                    .confidence(confidence)
+                    .location(byte_offset_to_line_col(code, mat.start()))
+                    .snippet(line_at(code, mat.start()))
+                    .why(format!("{}: `{}`", why, mat.as_str()))
+                    .suggestion("Propagate the error with ? or at least log it")
+                    .build(),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Scan for `unsafe` blocks and functions with no `// SAFETY:` comment above
+/// them (a `# Safety` doc section also counts for functions)
+pub fn scan_undocumented_unsafe(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let confidence = config.confidence_for(&BullshitType::UndocumentedUnsafe, 0.8);
+
+    let unsafe_regex = compile_regex(r#"\bunsafe\s*(?:\{|(?:extern\s+"[^"]*"\s+)?fn\b)"#)?;
+    let lines: Vec<&str> = code.lines().collect();
+
+    for mat in unsafe_regex.find_iter(code) {
+        let line_start = code[..mat.start()].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &code[line_start..mat.start()];
+        if prefix.contains("//") || prefix.matches('"').count() % 2 == 1 {
+            continue;
+        }
+
+        let line = byte_offset_to_line_col(code, mat.start()).line;
+        if has_safety_comment(prefix, &lines[..line - 1]) {
+            continue;
+        }
+
+        let what = if mat.as_str().ends_with('{') {
+            "block"
+        } else {
+            "fn"
+        };
+        alerts.push(
+            BullshitAlert::builder(BullshitType::UndocumentedUnsafe)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, mat.start()))
+                .snippet(line_at(code, mat.start()))
+                .why(format!(
+                    "`unsafe` {} has no // SAFETY: comment saying why it's sound",
+                    what
+                ))
+                .suggestion("Add a // SAFETY: comment explaining why the invariants hold")
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Whether a `SAFETY:` comment sits on the `unsafe` line (before it) or
+/// within the lines `above` it, walking back over comments and attributes
+fn has_safety_comment(prefix: &str, above: &[&str]) -> bool {
+    let is_safety = |text: &str| text.contains("SAFETY:") || text.contains("# Safety");
+    if is_safety(prefix) {
+        return true;
+    }
+
+    let mut code_lines = 1;
+    for line in above.iter().rev() {
+        let trimmed = line.trim_start();
+        if is_safety(trimmed) {
+            return true;
+        }
+        let is_comment = trimmed.starts_with("//")
+            || trimmed.starts_with("/*")
+            || trimmed.starts_with('*')
+            || trimmed.starts_with("#[");
+        if !is_comment {
+            code_lines += 1;
+            if code_lines > SAFETY_LOOKBACK_LINES {
+                return false;
+            }
+        }
+    }
+
+    false
+}
+
+/// Scan for equal numeric literals spelled differently within a file,
+/// flagging the less common spelling
+/// Example: `1000000` next to `1_000_000`, or `0.5f32` next to `0.5_f32`
+pub fn scan_literal_style(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    // A style nit, not a bug
+    let confidence = config.confidence_for(&BullshitType::InconsistentLiteralStyle, 0.65);
+
+    let literal_regex = compile_regex(
+        r"\b\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d+)?(?:_*(?:[iu](?:8|16|32|64|128|size)|f32|f64))?\b",
+    )?;
+
+    let strings = string_literal_ranges(code);
+
+    // Value with separators removed -> spelling -> offsets, in source order
+    let mut values: HashMap<String, Vec<(&str, Vec<usize>)>> = HashMap::new();
+    for mat in literal_regex.find_iter(code) {
+        let before = code[..mat.start()].chars().next_back();
+        let after = code[mat.end()..].chars().next();
+        // Tuple fields (`t.0`) and hex/octal/binary prefixes aren't decimal literals
+        if before == Some('.') || after.is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+        let line_start = code[..mat.start()].rfind('\n').map_or(0, |i| i + 1);
+        let in_string = strings.iter().any(|range| range.contains(&mat.start()));
+        if in_string || code[line_start..mat.start()].contains("//") {
+            continue;
+        }
+
+        let spellings = values.entry(mat.as_str().replace('_', "")).or_default();
+        match spellings.iter_mut().find(|(text, _)| *text == mat.as_str()) {
+            Some((_, offsets)) => offsets.push(mat.start()),
+            None => spellings.push((mat.as_str(), vec![mat.start()])),
+        }
+    }
+
+    for spellings in values.values() {
+        if spellings.len() < 2 {
+            continue;
+        }
+        // The most used spelling wins; ties go to the one written first
+        let majority = spellings
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, (_, offsets))| (offsets.len(), std::cmp::Reverse(*i)))
+            .map(|(_, (text, _))| *text)
+            .unwrap_or_default();
+
+        for (text, offsets) in spellings.iter().filter(|(text, _)| *text != majority) {
+            for &offset in offsets {
+                alerts.push(
+                    BullshitAlert::builder(BullshitType::InconsistentLiteralStyle)
+                        .confidence(confidence)
+                        .location(byte_offset_to_line_col(code, offset))
+                        .snippet(line_at(code, offset))
+                        .why(format!(
+                            "`{}` is written `{}` elsewhere in this file",
+                            text, majority
+                        ))
+                        .suggestion(format!(
+                            "Write it as `{}` like the rest of the file",
+                            majority
+                        ))
+                        .severity(0.5)
+                        .build(),
+                );
+            }
+        }
+    }
+
+    alerts.sort_by_key(|alert| alert.location);
+    Ok(alerts)
+}
+
+/// Scan string literals for hardcoded filesystem paths
+/// Examples: `"/tmp/cache"`, `"C:\\Users\\me"`, `"./config.json"`
+pub fn scan_hardcoded_paths(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+    let confidence = config.confidence_for(&BullshitType::HardcodedPath, 0.7);
+
+    let literal_regex = compile_regex(r#""((?:[^"\\]|\\.)*)""#)?;
+    let drive_regex = compile_regex(r"^[A-Za-z]:(?:\\\\|/)")?;
+    let relative_regex = compile_regex(r"^(?:\.{1,2}|~)/|^[\w.-]+(?:/[\w.-]+)+\.\w{1,5}$")?;
+
+    for cap in literal_regex.captures_iter(code) {
+        let (Some(whole), Some(content)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let path = content.as_str();
+
+        // URLs and compile-time includes aren't runtime filesystem paths
+        if path.contains("://") || is_include_argument(code, whole.start()) {
+            continue;
+        }
+
+        let kind = if path.len() > 1 && path.starts_with('/') && !path.starts_with("//") {
+            "Absolute path"
+        } else if drive_regex.is_match(path) {
+            "Windows path"
+        } else if relative_regex.is_match(path) {
+            "Relative path"
+        } else {
+            continue;
+        };
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::HardcodedPath)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, whole.start()))
+                .snippet(line_at(code, whole.start()))
+                .why(format!("{} {} is hardcoded and won't travel between machines", kind, whole.as_str()))
+                .suggestion("Read it from config or std::env, or build it with PathBuf::from(env!(\"CARGO_MANIFEST_DIR\"))")
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Scan string literals for credentials: anything non-trivial bound to a
+/// secret-ish name, or long high-entropy tokens anywhere
+/// Example: `let api_key = "sk-live-4f9a8b7c6d5e";`
+pub fn scan_hardcoded_secrets(code: &str, config: &DetectConfig) -> Result<Vec<BullshitAlert>> {
+    let mut alerts = Vec::new();
+
+    let literal_regex = compile_regex(r#""((?:[^"\\]|\\.)*)""#)?;
+    // `name = "..."`, `name: &str = "..."` or a `name: "..."` field initializer
+    let binding_regex = compile_regex(r"([A-Za-z_]\w*)\s*(?::[^=]*=|:|=)\s*$")?;
+
+    for cap in literal_regex.captures_iter(code) {
+        let (Some(whole), Some(content)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let literal = content.as_str();
+        let line_start = code[..whole.start()].rfind('\n').map_or(0, |i| i + 1);
+        let lhs = &code[line_start..whole.start()];
+
+        // Raw strings are regexes far more often than keys, and comments
+        // aren't compiled in
+        let is_raw = lhs.ends_with('r') || lhs.ends_with('#');
+        if is_raw || lhs.trim_start().starts_with("//") || literal.contains("://") {
+            continue;
+        }
+        // Keys and tokens are a single run of base64/hex/url-safe characters
+        if !literal
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_+/=.".contains(c))
+        {
+            continue;
+        }
+
+        let bound_name = binding_regex
+            .captures(lhs)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_lowercase());
+        let keyword = bound_name
+            .as_deref()
+            .and_then(|name| SECRET_KEYWORDS.iter().find(|k| name.contains(*k)));
+        let entropy = shannon_entropy(literal);
+
+        let (base, why) = match keyword {
+            Some(keyword)
+                if literal.len() >= MIN_NAMED_SECRET_LEN && entropy >= MIN_NAMED_SECRET_ENTROPY =>
+            {
+                (
+                    0.9,
+                    format!(
+                        "Literal assigned to a `{}` looks like a hardcoded credential",
+                        keyword
+                    ),
+                )
+            }
+            _ if literal.len() >= MIN_TOKEN_LEN
+                && entropy >= MIN_TOKEN_ENTROPY
+                && literal.chars().any(|c| c.is_ascii_digit())
+                && literal.chars().any(|c| c.is_ascii_alphabetic()) =>
+            {
+                (
+                    0.75,
+                    format!(
+                        "High-entropy literal ({:.1} bits/char) looks like an API key or token",
+                        entropy
+                    ),
+                )
+            }
+            _ => continue,
+        };
+        let confidence = config.confidence_for(&BullshitType::HardcodedSecret, base);
+
+        alerts.push(
+            BullshitAlert::builder(BullshitType::HardcodedSecret)
+                .confidence(confidence)
+                .location(byte_offset_to_line_col(code, whole.start()))
+                .snippet(line_at(code, whole.start()))
+                .why(why)
+                .suggestion(
+                    "Load the secret from the environment or a secrets manager, and rotate it",
+                )
+                // A leaked credential is worse than any other smell
+                .severity(1.0)
+                .build(),
+        );
+    }
+
+    Ok(alerts)
+}
+
+/// Shannon entropy of `text` in bits per char
+fn shannon_entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for ch in text.chars() {
+        *counts.entry(ch).or_default() += 1;
+    }
+
+    let total = text.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Whether the literal at `pos` is the argument of `include_str!` and friends
+fn is_include_argument(code: &str, pos: usize) -> bool {
+    let before = code[..pos].trim_end();
+    ["include_str!(", "include_bytes!(", "include!("]
+        .iter()
+        .any(|m| before.ends_with(m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_shadows_are_fine() {
+        let code = r#"
+        fn parse(input: &str) -> u32 {
+            let x = input.trim();
+            let x = x.parse::<u32>().unwrap_or_default();
+            let x = x * 2;
+            x
+        }
+        "#;
+
+        let alerts = scan_shadowing(code, &DetectConfig::default()).unwrap();
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_four_shadows_flagged_with_all_sites() {
+        let code = r#"
+        fn parse(input: &str) -> u32 {
+            let x = input.trim();
+            let x = x.trim_start_matches('v');
+            let x = x.parse::<u32>().unwrap_or_default();
+            let mut x = x * 2;
+            let x: u32 = x + 1;
+            {
+                let x = 5;
+            }
+            x
+        }
+        "#;
+
+        let alerts = scan_shadowing(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::ShadowAbuse);
+        assert_eq!(alerts[0].location.line, 3);
+        assert!(alerts[0].why_bs.contains("bound 5 times"));
+        assert!(alerts[0].why_bs.contains("lines 3, 4, 5, 6, 7"));
+    }
+
+    #[test]
+    fn test_long_parameter_list_flagged() {
+        let code = r#"
+        fn configure(
+            &mut self,
+            host: &str,
+            port: u16,
+            retries: u32,
+            timeout: Duration,
+            verbose: bool,
+            callback: impl Fn(u32) -> Result<(), Error>,
+            extra: HashMap<String, Vec<(u8, u8)>>,
+        ) {}
+
+        fn small(&self, a: u32, b: u32, c: u32, d: u32) {}
+        "#;
+
+        let alerts = scan_long_parameter_lists(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::LongParameterList);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].why_bs.contains("`configure` takes 7 parameters"));
+    }
+
+    #[test]
+    fn test_deep_nesting_flagged() {
+        let code = r#"
+fn process(items: &[Item]) {
+    for item in items {
+        if item.active {
+            match item.kind {
+                Kind::A => {
+                    while item.pending() {
+                        item.run();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+"#;
+
+        let alerts = scan_deep_nesting(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::DeepNesting);
+        assert_eq!(alerts[0].location.line, 7);
+        assert!(alerts[0].why_bs.contains("nests 5 levels deep (max 4)"));
+    }
+
+    #[test]
+    fn test_shallow_nesting_ignored() {
+        let code = r#"
+fn process(items: &[Item]) {
+    for item in items {
+        if item.active {
+            if item.ready() {
+                item.run();
+            }
+        }
+    }
+}
+"#;
+
+        let alerts = scan_deep_nesting(code, &DetectConfig::default()).unwrap();
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_format_single_value_flagged() {
+        let code = r#"
+        let label = format!("{}", count);
+        let full = format!("{}: {}", key, value);
+        "#;
+
+        let alerts = scan_needless_allocations(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::NeedlessAllocation);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].sug.contains("to_string()"));
+    }
+
+    #[test]
+    fn test_literal_to_string_flagged() {
+        let code = r#"
+        let name = "default".to_string();
+        let owned = name.to_string();
+        "#;
+
+        let alerts = scan_needless_allocations(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].why_bs.contains(r#""default".to_string()"#));
+        assert!(alerts[0].sug.contains("String::from"));
+    }
+
+    #[test]
+    fn test_let_underscore_call_flagged() {
+        let code = r#"
+        let _ = foo();
+        let _guard = lock.acquire();
+        let _ = ();
+        "#;
+
+        let alerts = scan_swallowed_errors(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::SwallowedError);
+        assert_eq!(alerts[0].location.line, 2);
+    }
+
+    #[test]
+    fn test_empty_err_arm_highest_confidence() {
+        let code = r#"
+        match save(&doc) {
+            Ok(_) => {}
+            Err(_) => {}
+        }
+        write(&buf).ok();
+        "#;
+
+        let alerts = scan_swallowed_errors(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        let empty_arm = alerts.iter().find(|a| a.location.line == 4).unwrap();
+        let ok_drop = alerts.iter().find(|a| a.location.line == 6).unwrap();
+        assert!(empty_arm.confidence > ok_drop.confidence);
+    }
+
+    #[test]
+    fn test_absolute_and_windows_paths_flagged() {
+        let code = r#"
+        let cache = PathBuf::from("/tmp/cache");
+        let home = "C:\\Users\\me\\data";
+        "#;
+
+        let alerts = scan_hardcoded_paths(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts
+            .iter()
+            .all(|a| a.issue_type == BullshitType::HardcodedPath));
+        assert!(alerts[0].why_bs.starts_with("Absolute path"));
+        assert!(alerts[1].why_bs.starts_with("Windows path"));
+    }
+
+    #[test]
+    fn test_bare_filename_not_flagged() {
+        let code = r#"
+        let name = "config.json";
+        let mime = "application/json";
+        let url = "https://example.com/api/v1.json";
+        let data = include_str!("../data/words.txt");
+        "#;
+
+        let alerts = scan_hardcoded_paths(code, &DetectConfig::default()).unwrap();
+
+        assert!(alerts.is_empty(), "{:?}", alerts);
+    }
+
+    #[test]
+    fn test_long_function_flagged_short_ignored() {
+        let long_body = "    step();\n".repeat(70);
+        let short_body = "    step();\n".repeat(30);
+        let code = format!(
+            "fn long_one() {{\n{}\n\n}}\n\nfn short_one() {{\n{}}}\n",
+            long_body, short_body
+        );
+
+        let alerts = scan_long_functions(&code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::LongFunction);
+        assert_eq!(alerts[0].location, SourceLocation::new(1, 1));
+        assert!(alerts[0].why_bs.contains("`long_one` body spans 70 lines"));
+    }
+
+    #[test]
+    fn test_duplicated_block_flagged() {
+        let block = "    let a = load(x);\n    let b = a * 2;\n    save(b);\n    log(\"saved\");\n    if b > limit {\n        warn(b);\n    }\n";
+        let code = format!(
+            "fn first() {{\n{}}}\n\nfn second() {{\n    prepare();\n{}}}\n",
+            block, block
+        );
+
+        let alerts = scan_duplicate_code(&code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::DuplicateCode);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].why_bs.ends_with("at lines 2, 13"));
+    }
+
+    #[test]
+    fn test_duplicated_block_in_crlf_file_after_multibyte_text() {
+        let block = "    let a = load(x);\r\n    let b = a * 2;\r\n    save(b);\r\n    log(\"saved\");\r\n    if b > limit {\r\n        warn(b);\r\n    }\r\n";
+        let code = format!(
+            "// Loader — stage one\r\nfn first() {{\r\n{}}}\r\n\r\nfn second() {{\r\n    prepare();\r\n{}}}\r\n",
+            block, block
+        );
+
+        let alerts = scan_duplicate_code(&code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 3);
+        assert_eq!(alerts[0].context_snippet, "let a = load(x);");
+        assert!(alerts[0].why_bs.ends_with("at lines 3, 14"));
+    }
+
+    #[test]
+    fn test_distinct_lines_not_flagged() {
+        let code = (1..=20)
+            .map(|i| format!("    let v{} = step{}(v{});\n", i, i, i - 1))
+            .collect::<String>();
+
+        let alerts = scan_duplicate_code(&code, &DetectConfig::default()).unwrap();
+
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_high_entropy_token_flagged() {
+        let code = r#"
+        fn client() -> Client {
+            let api_key = "sk-live-9fA3kQ7zLm2XwP4t";
+            Client::new("ghp_8d7F2kL9mQ4xZr1VbN6tYw3")
+        }
+        "#;
+
+        let alerts = scan_hardcoded_secrets(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts
+            .iter()
+            .all(|a| a.issue_type == BullshitType::HardcodedSecret));
+        assert!(alerts[0].why_bs.contains("`api_key`"));
+        assert!(alerts[1].why_bs.contains("High-entropy"));
+    }
+
+    #[test]
+    fn test_short_word_not_flagged() {
+        let code = r#"
+        fn login() {
+            let token = "guest";
+            let password_label = "Enter your password";
+            let mode = "production_deployment_mode";
+        }
+        "#;
+
+        let alerts = scan_hardcoded_secrets(code, &DetectConfig::default()).unwrap();
+
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_unsafe_with_safety_comment_is_silent() {
+        let code = r#"
+        fn first(values: &[u32]) -> u32 {
+            assert!(!values.is_empty());
+            // SAFETY: the assert above guarantees index 0 exists
+            unsafe { *values.get_unchecked(0) }
+        }
+
+        /// Reads a byte without bounds checks
+        ///
+        /// # Safety
+        /// `index` must be less than `bytes.len()`
+        #[inline]
+        pub unsafe fn byte_at(bytes: &[u8], index: usize) -> u8 {
+            *bytes.get_unchecked(index)
+        }
+        "#;
+
+        let alerts = scan_undocumented_unsafe(code, &DetectConfig::default()).unwrap();
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_unsafe_without_safety_comment_flagged() {
+        let code = r#"
+        fn first(values: &[u32]) -> u32 {
+            // Fast path
+            let value = unsafe { *values.get_unchecked(0) };
+            value
+        }
+        "#;
+
+        let alerts = scan_undocumented_unsafe(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::UndocumentedUnsafe);
+        assert_eq!(alerts[0].location.line, 4);
+        assert!(alerts[0].why_bs.contains("`unsafe` block"));
+    }
+
+    #[test]
+    fn test_mixed_digit_separators_flagged_once() {
+        let code = r#"
+        const MAX_ROWS: usize = 1_000_000;
+        const MAX_BYTES: usize = 1_000_000 * 8;
+
+        fn clamp(rows: usize) -> usize {
+            let scale = 0.5f32;
+            rows.min(1000000)
+        }
+        "#;
+
+        let alerts = scan_literal_style(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::InconsistentLiteralStyle);
+        assert_eq!(alerts[0].location.line, 7);
+        assert_eq!(
+            alerts[0].why_bs,
+            "`1000000` is written `1_000_000` elsewhere in this file"
+        );
+    }
+
+    #[test]
+    fn test_push_loop_flagged_but_collect_is_clean() {
+        let code = r#"
+        fn squares(values: &[u32]) -> Vec<u32> {
+            let mut squares = Vec::new();
+            for v in values {
+                squares.push(v * v);
+            }
+            squares
+        }
+
+        fn doubled(values: &[u32]) -> Vec<u32> {
+            values.iter().map(|v| v * 2).collect()
+        }
+
+        fn checked(values: &[u32]) -> Vec<u32> {
+            let mut kept = Vec::new();
+            for v in values {
+                if *v > 0 {
+                    kept.push(*v);
+                }
+            }
+            kept
+        }
+        "#;
+
+        let alerts = scan_needless_allocations(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 3);
+        assert!(alerts[0]
+            .why_bs
+            .contains("`squares` starts as an empty Vec"));
+        assert!(alerts[0].sug.contains("Vec::with_capacity"));
+    }
+
+    #[test]
+    fn test_file_with_many_public_items_flagged() {
+        let big: String = (0..35)
+            .map(|i| format!("pub fn helper_{}() -> u32 {{ {} }}\n", i, i))
+            .collect();
+        let small = "pub struct Point { pub x: i32, pub y: i32 }\npub fn origin() -> Point { Point { x: 0, y: 0 } }\n";
+
+        let alerts = scan_public_items(&big, &DetectConfig::default()).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, BullshitType::OverEngineering);
+        assert_eq!(alerts[0].location, SourceLocation::new(1, 1));
+        assert!(alerts[0]
+            .why_bs
+            .starts_with("File declares 35 public items"));
+
+        assert!(scan_public_items(small, &DetectConfig::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_lock_unwrap_flagged() {
+        let code = "fn bump(m: &Mutex<u32>, cache: &RwLock<Vec<u8>>) {\n    *m.lock().unwrap() += 1;\n    cache.write().unwrap().clear();\n}\n";
+        let alerts = scan_lock_unwraps(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].issue_type, BullshitType::MutexAbuse);
+        assert_eq!(alerts[0].location, SourceLocation::new(2, 7));
+        assert_eq!(alerts[1].issue_type, BullshitType::RwLockAbuse);
+        assert!(alerts[0].sug.contains("PoisonError"));
+    }
+
+    #[test]
+    fn test_debug_leftovers_outside_tests_flagged() {
+        let code = r#"
+fn total(x: u32) -> u32 {
+    dbg!(x);
+    println!("total: {}", x);
+    // println!("old debugging");
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn prints() {
+        dbg!(super::total(1));
+    }
+}
+"#;
+        let alerts = scan_debug_leftovers(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].location.line, 3);
+        assert_eq!(alerts[0].severity_level(), crate::SeverityLevel::High);
+        assert!(alerts[0].confidence > alerts[1].confidence);
+    }
+
+    #[test]
+    fn test_println_allowed_in_bin_targets() {
+        let code = "fn main() {\n    println!(\"done\");\n    dbg!(1);\n}\n";
+        let config = DetectConfig::default();
+
+        for path in ["src/main.rs", "src/bin/tool.rs"] {
+            let bin = config.effective_config_for(std::path::Path::new(path));
+            let alerts = scan_debug_leftovers(code, &bin).unwrap();
+            assert_eq!(alerts.len(), 1, "{}", path);
+            assert!(alerts[0].why_bs.starts_with("`dbg!`"));
+        }
+
+        let lib = config.effective_config_for(std::path::Path::new("src/domain.rs"));
+        assert_eq!(scan_debug_leftovers(code, &lib).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_glob_imports_flagged_unless_allowed() {
+        let code = r#"
+use crate::internal::*;
+use std::prelude::v1::*;
+use std::io::{self, *};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
+"#;
+        let alerts = scan_glob_imports(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].why_bs.contains("`crate::internal::*`"));
+
+        let mut config = DetectConfig::default();
+        config
+            .glob_import_allowlist
+            .push("crate::internal".to_string());
+        assert!(scan_glob_imports(code, &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stringly_error_returns_flagged() {
+        let code = r#"
+fn parse(input: &str) -> Result<u32, String> {
+    input.parse().map_err(|e| format!("{}", e))
+}
+
+fn load(path: &Path) -> Result<Config, MyError> {
+    read(path)
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    Ok(())
+}
+"#;
+        let alerts = scan_stringly_errors(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].location.line, 2);
+        assert!(alerts[0].why_bs.starts_with("parse() returns `String`"));
+        assert_eq!(alerts[1].location.line, 10);
+        assert!(alerts[1].confidence < alerts[0].confidence);
+    }
+
+    #[test]
+    fn test_long_method_chains_flagged() {
+        let code = r#"
+fn names(users: &[User]) -> Vec<String> {
+    users
+        .iter()
+        .filter(|u| u.active)
+        .map(|u| u.profile.name.trim())
+        .filter(|n| !n.is_empty())
+        .map(str::to_lowercase)
+        .take(10)
+        .collect::<Vec<_>>()
+}
+
+fn short(v: &[u8]) -> usize {
+    v.iter().filter(|b| **b > 0).count()
+}
+
+fn nested(raw: &str) -> u32 {
+    parse(raw).unwrap().value().unwrap()
+}
+"#;
+        let alerts = scan_method_chains(code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].location.line, 4);
+        assert!(alerts[0]
+            .why_bs
+            .starts_with("Chain of 7 method calls (max 6)"));
+        assert_eq!(alerts[1].location.line, 18);
+        assert!(alerts[1].why_bs.contains("unwraps 2 times"));
+    }
+
+    #[test]
+    fn test_borrowed_owned_params() {
+        let code = r#"
+fn g(s: &String) -> usize { s.len() }
+fn h(v: &Vec<u8>, b: &'a Box<dyn Error>) {}
+fn fine(s: &str, v: &[u8], out: &mut Vec<u8>, owned: Vec<String>) {}
+"#;
+        let alerts = scan_borrowed_owned_params(code, &DetectConfig::default()).unwrap();
+        let fixes: Vec<&str> = alerts.iter().map(|a| a.sug.as_str()).collect();
+
+        assert_eq!(
+            fixes,
+            [
+                "Take `&str` instead",
+                "Take `&[u8]` instead",
+                "Take `&dyn Error` instead"
+            ]
+        );
+        assert_eq!(alerts[0].location.line, 2);
+        assert_eq!(alerts[2].location.column, 19);
+    }
+
+    #[test]
+    fn test_large_impl_blocks_flagged() {
+        let methods: String = (0..30)
+            .map(|i| {
+                format!(
+                    "    pub fn method_{}(&self) -> u32 {{\n        {}\n    }}\n",
+                    i, i
+                )
+            })
+            .collect();
+        let code = format!(
+            "impl<T> Widget<T>\nwhere\n    T: Clone,\n{{\n    const LIMIT: u32 = 3;\n    type Item = T;\n{}}}\n\n\
+             impl Small {{\n    fn a(&self) {{\n        fn inner() {{}}\n    }}\n    fn b(&self) {{}}\n    fn c(&self) {{}}\n    fn d(&self) {{}}\n    fn e(&self) {{}}\n}}\n",
+            methods
+        );
+        let alerts = scan_impl_sizes(&code, &DetectConfig::default()).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 1);
+        assert!(alerts[0]
+            .why_bs
+            .starts_with("impl for `Widget<T>` defines 30 methods (max 25)"));
+
+        let small = DetectConfig {
+            max_impl_methods: 4,
+            ..DetectConfig::default()
+        };
+        let alerts = scan_impl_sizes(&code, &small).unwrap();
+        assert!(alerts[1].why_bs.contains("`Small` defines 5 methods"));
+    }
+
+    #[test]
+    fn test_lossy_integer_casts_suggest_try_from() {
+        let code = r#"
+fn sizes(small: u32, offset: i32) -> usize {
+    let n = big_u64 as usize;
+    let widened = small as u64;
+    let index = offset as usize;
+    let literal = 300u16 as u8;
+    let unknown = count as usize;
+    n + widened as usize + index
+}
+"#;
+        let alerts = scan_lossy_casts(code, &DetectConfig::default()).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![3, 5, 6]);
+        assert_eq!(
+            alerts[0].sug,
+            "Use `usize::try_from(big_u64)?` so out-of-range values are an error"
+        );
+        assert!(alerts[1].why_bs.contains("`offset as usize`"));
+    }
+
+    #[test]
+    fn test_mutable_statics_and_their_unsafe_access() {
+        let code = r#"
+static mut COUNTER: u32 = 0;
+static LIMIT: u32 = 10;
+
+fn bump() -> u32 {
+    unsafe {
+        COUNTER += 1;
+        COUNTER
+    }
+}
+
+fn other(p: *const u8) -> u8 {
+    unsafe { *p }
+}
+"#;
+        let alerts = scan_mutable_statics(code, &DetectConfig::default()).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![2, 6]);
+        assert_eq!(alerts[0].severity, 0.9);
+        assert!(alerts[0].why_bs.starts_with("`static mut COUNTER`"));
+        assert_eq!(alerts[1].why_bs, "unsafe block accesses static mut COUNTER");
+    }
+
+    #[test]
+    fn test_collect_then_iterate_flagged() {
+        let code = r#"
+fn total(items: &[Item]) -> u32 {
+    let sum = items.iter().map(|i| i.cost).collect::<Vec<_>>().iter().sum();
+    let names: Vec<String> = items.iter().map(|i| i.name.clone()).collect();
+    for name in &names {
+        println!("{}", name);
+    }
+    let kept: Vec<u32> = items.iter().map(|i| i.id).collect();
+    report(&kept);
+    sum + kept.len() as u32
+}
+"#;
+        let alerts = scan_collect_then_iterate(code, &DetectConfig::default()).unwrap();
+        let found: Vec<(usize, f32)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.confidence))
+            .collect();
+
+        assert_eq!(found, vec![(3, 0.8), (4, 0.65)]);
+        assert!(alerts[1].why_bs.starts_with("`names` is collected"));
+    }
+
+    #[test]
+    fn test_blocking_calls_in_async_fn_flagged_above_sync() {
+        let code = r#"
+fn sync_wait() {
+    std::thread::sleep(Duration::from_millis(5));
+}
+
+async fn async_wait(state: &State) {
+    std::thread::sleep(Duration::from_millis(5));
+    let cached = state.cache.lock().await;
+    let data = tokio::task::spawn_blocking(|| std::fs::read("big.bin")).await;
+    let text = fs::read_to_string("config.toml").unwrap();
+    let bytes = tokio::fs::read("data.bin").await;
+}
+"#;
+        let blocking = scan_blocking_in_async(code, &DetectConfig::default()).unwrap();
+        let lines: Vec<usize> = blocking.iter().map(|a| a.location.line).collect();
+        assert_eq!(lines, vec![7, 10]);
+        assert!(blocking[0].why_bs.contains("inside async fn `async_wait`"));
+
+        let alerts = crate::scan_code(code, &DetectConfig::default()).unwrap();
+        let worst_on = |line: usize| {
+            alerts
+                .iter()
+                .filter(|a| a.location.line == line)
+                .map(|a| a.severity)
+                .fold(0.0, f32::max)
+        };
+        assert!(worst_on(7) > worst_on(3));
+    }
+
+    #[test]
+    fn test_mixed_error_handling_is_opt_in() {
+        let code = r#"
+fn load(path: &Path) -> Result<Config, Error> {
+    let text = fs::read_to_string(path)?;
+    let config = toml::from_str(&text).unwrap();
+    Ok(config)
+}
+
+fn parse(text: &str) -> Result<u32, Error> {
+    let n = text.trim().parse()?;
+    Ok(n + lookup(text)?)
+}
+"#;
+        let counts = error_style_counts(code).unwrap();
+        assert_eq!(counts[0].1.question_marks, 1);
+        assert_eq!(counts[0].1.unwraps, 1);
+        assert_eq!(counts[1].1.styles(), 1);
+
+        let alerts = scan_mixed_error_handling(code, &DetectConfig::default()).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].location.line, 2);
+        assert_eq!(
+            alerts[0].why_bs,
+            "load() mixes 2 error-handling styles: 1 `?`, 1 `.unwrap()`"
+        );
+
+        let mixed = |config: &DetectConfig| {
+            crate::scan_code(code, config)
+                .unwrap()
+                .iter()
+                .filter(|a| a.issue_type == BullshitType::MixedErrorHandling)
+                .count()
+        };
+        let mut config = DetectConfig::default();
+        assert_eq!(mixed(&config), 0);
+        config
+            .enabled_types
+            .insert(BullshitType::MixedErrorHandling);
+        assert_eq!(mixed(&config), 1);
+    }
+
+    #[test]
+    fn test_polling_loop_flagged_above_lone_sleep() {
+        let code = r#"
+fn wait_for(job: &Job) {
+    loop {
+        if job.is_done() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn backoff() {
+    std::thread::sleep(Duration::from_millis(50));
+}
+"#;
+        let polls = scan_polling_loops(code, &DetectConfig::default()).unwrap();
+        assert_eq!(polls.len(), 1);
+        assert_eq!(polls[0].location.line, 3);
+
+        let alerts = crate::scan_code(code, &DetectConfig::default()).unwrap();
+        let on_line = |line: usize| -> Vec<&BullshitAlert> {
+            alerts.iter().filter(|a| a.location.line == line).collect()
+        };
+        let lone = on_line(12);
+        assert_eq!(lone.len(), 1);
+        assert_eq!(lone[0].issue_type, BullshitType::SleepAbuse);
+        assert_eq!(on_line(3)[0].issue_type, BullshitType::PollingLoop);
+        assert!(on_line(3)[0].severity > lone[0].severity);
+    }
+
+    #[test]
+    fn test_early_returns_flagged_when_enabled() {
+        let code = r#"
+fn classify(n: i32) -> &'static str {
+    if n < 0 {
+        return "negative";
+    }
+    if n == 0 {
+        return "zero";
+    }
+    if n < 10 {
+        return "small";
+    }
+    return "large";
+}
+"#;
+        let alerts = scan_early_returns(code, &DetectConfig::default()).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            alerts[0].why_bs,
+            "classify() has 4 return statements (max 3)"
+        );
+
+        let early = |config: &DetectConfig| {
+            crate::scan_code(code, config)
+                .unwrap()
+                .iter()
+                .filter(|a| a.issue_type == BullshitType::EarlyReturn)
+                .count()
+        };
+        let mut config = DetectConfig::default();
+        assert_eq!(early(&config), 0);
+        config.enabled_types.insert("early-return".parse().unwrap());
+        assert_eq!(early(&config), 1);
+    }
+
+    #[test]
+    fn test_clone_of_copy_literal_flagged() {
+        let code = r#"
+fn copies(data: &Data, pair: &(u32, Vec<u8>)) {
+    let y = 5u32.clone();
+    let owned = data.clone();
+    let first = pair.0.clone();
+    let n = data.items.len().clone();
+}
+"#;
+        let clones = scan_copy_clones(code, &DetectConfig::default()).unwrap();
+        let found: Vec<(usize, &str)> = clones
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, "`5u32.clone()` on a Copy value - it is copied anyway"),
+                (6, "`.len().clone()` on a Copy value - it is copied anyway"),
+            ]
+        );
+
+        // The generic pattern still reports `data.clone()`, but not `5u32.clone()` twice
+        let alerts = crate::scan_code(code, &DetectConfig::default()).unwrap();
+        let clone_lines: Vec<usize> = alerts
+            .iter()
+            .filter(|a| a.issue_type == BullshitType::CloneAbuse)
+            .map(|a| a.location.line)
+            .collect();
+        assert_eq!(clone_lines, vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_nested_wrappers_need_true_nesting() {
+        let code = r#"
+struct Cache {
+    hit: Option<Option<u32>>,
+    items: Option<Vec<u32>>,
+    pair: Option<(Option<u8>, u8)>,
+}
+
+fn lookup(key: &str) -> Result<Option<u32>, Error> { todo!() }
+fn parse(raw: &str) -> Option<Result<u32, ParseIntError>> { todo!() }
+fn deep() -> Option<Option<Option<u8>>> { None }
+"#;
+        let alerts = scan_nested_wrappers(code, &DetectConfig::default()).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![3, 9, 10]);
+        assert!(alerts[0]
+            .why_bs
+            .starts_with("`Option<Option<u32>>` nests Option inside Option"));
+        assert!(alerts[1].why_bs.contains("nests Result inside Option"));
+    }
+
+    #[test]
+    fn test_len_zero_comparisons_suggest_is_empty() {
+        let code = r#"
+fn check(v: &[u8], queue: &Queue) {
+    if v.len() == 0 {
+        return;
+    }
+    if queue.items.len() > 0 {
+        drain(queue);
+    }
+    if v.len() > 10 {
+        split(v);
+    }
+}
+"#;
+        let alerts = scan_len_zero_comparisons(code, &DetectConfig::default()).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.sug.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (3, "Use `v.is_empty()`"),
+                (6, "Use `!queue.items.is_empty()`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eager_fallback_flags_calls_not_literals() {
+        let code = r#"
+fn settings(opt: Option<Config>, port: Option<u16>, name: Option<&str>) {
+    let config = opt.unwrap_or(make_default());
+    let port = port.unwrap_or(0);
+    let end = text.find('\n').unwrap_or(text.len());
+    let label = name.map_or(String::from("anon"), |n| n.to_string());
+    let wrapped = port.ok_or(Some(1));
+}
+"#;
+        let alerts = scan_eager_fallbacks(code, &DetectConfig::default()).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.sug.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    3,
+                    "Use `unwrap_or_else(|| make_default())` so it only runs when needed"
+                ),
+                (
+                    6,
+                    "Use `map_or_else(|| String::from(\"anon\"), ...)` so it only runs when needed"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_out_params_flagged_on_unit_functions() {
+        let code = r#"
+impl Encoder {
+    fn compute(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.header);
+    }
+    fn fill(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(&self.header);
+    }
+    fn append(&self, dest: &mut String) -> usize {
+        dest.len()
+    }
+    fn reset(&mut self, state: &mut State) {}
+}
+"#;
+        let alerts = scan_out_params(code, &DetectConfig::default()).unwrap();
+        let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+
+        assert_eq!(lines, vec![3]);
+        assert_eq!(
+            alerts[0].sug,
+            "Return `Vec<u8>` from `compute` instead of writing into an out-parameter"
+        );
+    }
+
+    #[test]
+    fn test_generic_params_and_stray_phantom_data() {
+        let code = r#"
+pub struct Pipeline<A, B, C, D, E> {
+    stages: (A, B, C, D, E),
+}
+struct Pair<'a, K, V, const N: usize> {
+    entries: [(&'a K, V); N],
+}
+struct Id {
+    raw: u64,
+    _marker: PhantomData<u32>,
+}
+struct Typed<T> {
+    raw: u64,
+    _marker: PhantomData<T>,
+}
+"#;
+        let alerts = scan_generic_params(code, &DetectConfig::default()).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    2,
+                    "struct `Pipeline` takes 5 type parameters (max 4) - generic machinery few callers need"
+                ),
+                (
+                    10,
+                    "struct `Id` has no type parameters for its `PhantomData` to mark"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_string_literals() {
+        let query = "x".repeat(600);
+        let page = "<p>line</p>\n".repeat(60);
+        let code = format!(
+            "const QUERY: &str = \"{}\";\nconst PAGE: &str = r#\"\n{}\"#;\nconst SHORT: &str = \"select 1\";\n",
+            query, page
+        );
+        let alerts = scan_long_string_literals(&code, &DetectConfig::default()).unwrap();
+        let found: Vec<(usize, &str)> = alerts
+            .iter()
+            .map(|a| (a.location.line, a.why_bs.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    1,
+                    "String literal of 600 characters over 1 line (max 500) embedded in source"
+                ),
+                (
+                    2,
+                    "String literal of 721 characters over 62 lines (max 500) embedded in source"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Lightweight structural helpers shared by the regex scanners.
+//!
+//! These are deliberately naive (no string/comment awareness) - good enough
+//! to find function bodies and brace scopes without a full parser. Scanners
+//! that need to skip string contents can use [`string_literal_ranges`], and
+//! test-only code [`test_code_ranges`].
+
+use crate::limits::compile_regex;
+use std::ops::Range;
+
+/// A `fn` item with a body located in the source
+#[derive(Debug, Clone)]
+pub(crate) struct FnSpan {
+    /// Function name
+    pub name: String,
+    /// Byte offset of the `fn` keyword
+    pub start: usize,
+    /// Byte offset of the opening `{` of the body
+    pub body_open: usize,
+    /// Byte offset of the closing `}` of the body
+    pub body_close: usize,
+}
+
+impl FnSpan {
+    /// Body text between (but excluding) the braces
+    pub fn body<'a>(&self, code: &'a str) -> &'a str {
+        &code[self.body_open + 1..self.body_close]
+    }
+}
+
+/// Find every `fn` item that has a body (trait method declarations are skipped)
+pub(crate) fn find_functions(code: &str) -> Vec<FnSpan> {
+    let mut functions = Vec::new();
+    let fn_regex = compile_regex(r"\bfn\s+(\w+)").expect("valid fn regex");
+
+    for cap in fn_regex.captures_iter(code) {
+        let whole = cap.get(0).expect("group 0 always matches");
+        let name = cap.get(1).map(|m| m.as_str()).unwrap_or_default();
+
+        // Walk the signature to the body, skipping over parameter parens
+        let mut paren_depth = 0usize;
+        let mut body_open = None;
+        for (i, ch) in code[whole.end()..].char_indices() {
+            match ch {
+                '(' => paren_depth += 1,
+                ')' => paren_depth = paren_depth.saturating_sub(1),
+                ';' if paren_depth == 0 => break,
+                '{' if paren_depth == 0 => {
+                    body_open = Some(whole.end() + i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let Some(body_open) = body_open else {
+            continue;
+        };
+        let Some(body_close) = matching_brace(code, body_open) else {
+            continue;
+        };
+
+        functions.push(FnSpan {
+            name: name.to_string(),
+            start: whole.start(),
+            body_open,
+            body_close,
+        });
+    }
+
+    functions
+}
+
+/// Find the `}` matching the `{` at `open`
+pub(crate) fn matching_brace(code: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (i, ch) in code[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The trimmed source line containing byte offset `pos`
+pub(crate) fn line_at(code: &str, pos: usize) -> String {
+    let line_start = code[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = code[pos..]
+        .find('\n')
+        .map(|i| pos + i)
+        .unwrap_or(code.len());
+    code[line_start..line_end].trim().to_string()
+}
+
+/// Byte range of the parameter list (inside the parens) of the `fn` whose
+/// name ends at `name_end`, skipping any generic parameter list first
+pub(crate) fn parameter_list(code: &str, name_end: usize) -> Option<(usize, usize)> {
+    let rest = &code[name_end..];
+    let mut offset = rest.len() - rest.trim_start().len();
+
+    if rest[offset..].starts_with('<') {
+        offset += closing_delimiter(&rest[offset..])? + 1;
+        offset += rest[offset..].len() - rest[offset..].trim_start().len();
+    }
+
+    if !rest[offset..].starts_with('(') {
+        return None;
+    }
+    let close = offset + closing_delimiter(&rest[offset..])?;
+
+    Some((name_end + offset + 1, name_end + close))
+}
+
+/// Offset of the delimiter closing the `(`, `[`, `{` or `<` at the start of
+/// `text`, treating all bracket kinds as nesting (and ignoring `->`)
+pub(crate) fn closing_delimiter(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut prev = '\0';
+
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == '-' => {}
+            ')' | ']' | '}' | '>' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        prev = ch;
+    }
+
+    None
+}
+
+/// Byte ranges of string and char literals, raw strings (up to `r###"`) included
+pub(crate) fn string_literal_ranges(code: &str) -> Vec<Range<usize>> {
+    let literal_regex = compile_regex(
+        r####"(?s)r###".*?"###|r##".*?"##|r#".*?"#|r"[^"]*"|"(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)'"####,
+    )
+    .expect("valid string literal regex");
+    literal_regex.find_iter(code).map(|m| m.range()).collect()
+}
+
+/// Byte ranges of test-only code: items under `#[cfg(test)]` and `#[test]` functions
+pub(crate) fn test_code_ranges(code: &str) -> Vec<Range<usize>> {
+    let attr_regex =
+        compile_regex(r"#\[(?:cfg\(test\)|test)\][^{;]*\{").expect("valid test attribute regex");
+    attr_regex
+        .find_iter(code)
+        .map(|m| {
+            let close = matching_brace(code, m.end() - 1).unwrap_or(code.len());
+            m.start()..close
+        })
+        .collect()
+}
+
+/// Split on `separator` where it isn't nested inside any brackets
+pub(crate) fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut prev = '\0';
+
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == '-' => {}
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+        prev = ch;
+    }
+    parts.push(&text[start..]);
+
+    parts
+}
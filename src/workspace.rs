@@ -0,0 +1,202 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+//! Cargo workspace discovery for scanning every member crate at once
+//!
+//! The root config applies to every member; a member's own
+//! `.bullshitdetector.toml`/`bsd.toml` is layered on top of it, with
+//! `NIODOO_*` environment overrides still applied last.
+
+use crate::config::ConfigFile;
+use crate::error::{DetectError, Result};
+use crate::files::scan_directory;
+use crate::magic_numbers::MagicNumberConfig;
+use crate::{BullshitAlert, DetectConfig};
+use glob::glob;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Paths for one crate in a workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CratePaths {
+    /// Package name from the member's `Cargo.toml` (directory name as a fallback)
+    pub name: String,
+    /// Directory holding the member's `Cargo.toml`
+    pub root: PathBuf,
+    /// The member's `src/` directory
+    pub src: PathBuf,
+    /// The member's own config file, if it has one
+    pub config: Option<PathBuf>,
+}
+
+impl CratePaths {
+    fn at(root: PathBuf, manifest: &toml::Value) -> Self {
+        let name = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                root.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+
+        Self {
+            name,
+            src: root.join("src"),
+            config: ConfigFile::discover(&root),
+            root,
+        }
+    }
+
+    /// `base` with this member's config file and then the environment applied
+    pub fn detect_config(&self, base: &DetectConfig) -> Result<DetectConfig> {
+        let mut config = base.clone();
+        if let Some(path) = &self.config {
+            ConfigFile::load(path)?.apply_to(&mut config)?;
+            config.apply_env();
+        }
+        Ok(config)
+    }
+
+    /// `base` with this member's `[magic]` section and then the environment applied
+    pub fn magic_config(&self, base: &MagicNumberConfig) -> Result<MagicNumberConfig> {
+        let mut config = base.clone();
+        if let Some(path) = &self.config {
+            ConfigFile::load(path)?.apply_magic_to(&mut config);
+            config.apply_env();
+        }
+        Ok(config)
+    }
+}
+
+/// Find the crates of the workspace rooted at `root`
+///
+/// Expands `[workspace] members` globs (minus `exclude`), and includes the
+/// root package itself if the manifest has one. A plain crate yields itself.
+pub fn discover_workspace(root: &Path) -> Result<Vec<CratePaths>> {
+    let manifest = read_manifest(&root.join("Cargo.toml"))?;
+    let mut crates = Vec::new();
+
+    if let Some(workspace) = manifest.get("workspace") {
+        let excluded: Vec<PathBuf> = string_list(workspace.get("exclude"))
+            .iter()
+            .map(|e| root.join(e))
+            .collect();
+
+        let mut members = Vec::new();
+        for pattern in string_list(workspace.get("members")) {
+            let full = root.join(pattern).display().to_string();
+            let entries = glob(&full).map_err(|e| {
+                DetectError::InvalidConfig(format!("Invalid workspace member {}: {}", pattern, e))
+            })?;
+            for dir in entries.flatten() {
+                if dir.join("Cargo.toml").is_file() && !excluded.contains(&dir) {
+                    members.push(dir);
+                }
+            }
+        }
+        members.sort();
+        members.dedup();
+
+        for dir in members {
+            let member_manifest = read_manifest(&dir.join("Cargo.toml"))?;
+            crates.push(CratePaths::at(dir, &member_manifest));
+        }
+    }
+
+    if manifest.get("package").is_some() {
+        crates.insert(0, CratePaths::at(root.to_path_buf(), &manifest));
+    }
+
+    Ok(crates)
+}
+
+/// Scan every crate in the workspace with its merged config
+pub fn scan_workspace(
+    root: &Path,
+    config: &DetectConfig,
+) -> Result<Vec<(CratePaths, Vec<BullshitAlert>)>> {
+    let mut results = Vec::new();
+
+    for member in discover_workspace(root)? {
+        let member_config = member.detect_config(config)?;
+        let alerts = scan_directory(&member.src, &member_config)?;
+        results.push((member, alerts));
+    }
+
+    Ok(results)
+}
+
+fn read_manifest(path: &Path) -> Result<toml::Value> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents)
+        .map_err(|e| DetectError::InvalidConfig(format!("{}: {}", path.display(), e)))
+}
+
+fn string_list(value: Option<&toml::Value>) -> Vec<&str> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BullshitType;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_two_member_workspace_scanned() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            &root.join("crates/alpha/Cargo.toml"),
+            "[package]\nname = \"alpha\"\n",
+        );
+        write(
+            &root.join("crates/alpha/src/lib.rs"),
+            "fn a() { let v = x.clone(); }\n",
+        );
+        write(
+            &root.join("crates/beta/Cargo.toml"),
+            "[package]\nname = \"beta\"\n",
+        );
+        write(
+            &root.join("crates/beta/src/lib.rs"),
+            "fn b() { let v = y.clone(); }\n",
+        );
+        // beta raises CloneAbuse above the threshold it keeps from the root
+        write(
+            &root.join("crates/beta/bsd.toml"),
+            "[detect.confidence_overrides]\nCloneAbuse = 0.95\n",
+        );
+
+        let crates = discover_workspace(root).unwrap();
+        let names: Vec<_> = crates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "beta"]);
+        assert!(crates[1].config.is_some());
+
+        let results = scan_workspace(root, &DetectConfig::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        for (member, alerts) in &results {
+            let clone = alerts
+                .iter()
+                .find(|a| a.issue_type == BullshitType::CloneAbuse)
+                .unwrap_or_else(|| panic!("{} was not scanned", member.name));
+            let expected = if member.name == "beta" { 0.95 } else { 0.7 };
+            assert_eq!(clone.confidence, expected);
+        }
+    }
+}
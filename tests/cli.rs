@@ -0,0 +1,475 @@
+// Copyright (c) 2025 Jason Van Pham (ruffian-l on GitHub) @ The Niodoo Collaborative
+// Licensed under the MIT License - See LICENSE file for details
+// Attribution required for all derivative works
+
+use bullshitdetector::baseline::fingerprint;
+use bullshitdetector::BullshitAlert;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn bullshitdetector(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bullshitdetector"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("GITHUB_ACTIONS")
+        .output()
+        .unwrap()
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(
+        dir.path().join("src/lib.rs"),
+        "fn f() {\n    let v = load().unwrap();\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("src/main.rs"),
+        "fn main() {\n    let w = x.clone();\n}\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn test_no_progress_on_non_tty_or_quiet() {
+    let dir = fixture();
+
+    for args in [
+        &["scan", ".", "--output", "json"][..],
+        &["--quiet", "scan", ".", "--output", "json"][..],
+    ] {
+        let output = bullshitdetector(args, dir.path());
+        assert!(output.status.success());
+        assert!(
+            output.stderr.is_empty(),
+            "unexpected stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let alerts: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(!alerts.as_array().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn test_report_file_written_alongside_jsonl() {
+    let dir = fixture();
+
+    let output = bullshitdetector(
+        &[
+            "scan",
+            ".",
+            "--output",
+            "jsonl",
+            "--report-file",
+            "summary.json",
+        ],
+        dir.path(),
+    );
+    assert!(output.status.success());
+
+    let streamed = String::from_utf8(output.stdout).unwrap().lines().count();
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("summary.json")).unwrap())
+            .unwrap();
+    assert_eq!(report["total"], streamed);
+    assert_eq!(report["files_scanned"], 2);
+    assert_eq!(report["by_type"]["UnwrapAbuse"], 1);
+    assert_eq!(report["by_type"]["CloneAbuse"], 1);
+}
+
+#[test]
+fn test_update_baseline_accepts_then_reports_stale() {
+    let dir = fixture();
+    let args = [
+        "scan",
+        ".",
+        "--output",
+        "json",
+        "--update-baseline",
+        "baseline.json",
+    ];
+
+    let first = bullshitdetector(&args, dir.path());
+    assert!(first.status.success());
+    let stderr = String::from_utf8_lossy(&first.stderr);
+    assert!(stderr.contains("2 added, 0 kept, 0 stale"), "{}", stderr);
+
+    // Fix one issue: the other is kept, the fixed one goes stale, nothing is new
+    fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+    let second = bullshitdetector(&args, dir.path());
+    let stderr = String::from_utf8_lossy(&second.stderr);
+    assert!(stderr.contains("0 added, 1 kept, 1 stale"), "{}", stderr);
+    let alerts: serde_json::Value = serde_json::from_slice(&second.stdout).unwrap();
+    assert_eq!(alerts, serde_json::json!([]));
+
+    let baseline: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("baseline.json")).unwrap())
+            .unwrap();
+    assert_eq!(baseline["fingerprints"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_template_output_and_startup_validation() {
+    let dir = fixture();
+
+    let output = bullshitdetector(
+        &["scan", ".", "--template", "{file}:{line}: {type} {{ok}}"],
+        dir.path(),
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("lib.rs:2: UnwrapAbuse {ok}"), "{}", stdout);
+
+    let bad = bullshitdetector(&["scan", ".", "--template", "{file} {nope}"], dir.path());
+    assert!(!bad.status.success());
+    assert!(bad.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("{nope}"));
+}
+
+#[test]
+fn test_files_from_scans_only_listed_files() {
+    let dir = fixture();
+    fs::write(
+        dir.path().join("changed.txt"),
+        "src/main.rs\nsrc/gone.rs\n\n",
+    )
+    .unwrap();
+
+    let output = bullshitdetector(
+        &[
+            "scan",
+            ".",
+            "--output",
+            "json",
+            "--files-from",
+            "changed.txt",
+        ],
+        dir.path(),
+    );
+    assert!(output.status.success());
+
+    let alerts: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(!alerts.is_empty());
+    assert!(alerts.iter().all(|a| a["file_path"] == "src/main.rs"));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("warning: skipping src/gone.rs: no such file"));
+}
+
+#[test]
+fn test_csv_output_parses_back() {
+    let dir = fixture();
+    fs::write(
+        dir.path().join("src/pair.rs"),
+        "fn pair() {\n    let (a, b) = split(x, y).unwrap();\n}\n",
+    )
+    .unwrap();
+
+    let output = bullshitdetector(&["scan", ".", "--output", "csv"], dir.path());
+    assert!(output.status.success());
+
+    let mut reader = csv::Reader::from_reader(output.stdout.as_slice());
+    assert_eq!(&reader.headers().unwrap()[0], "file");
+    let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 3);
+    let pair = records.iter().find(|r| &r[0] == "src/pair.rs").unwrap();
+    assert_eq!(&pair[3], "UnwrapAbuse");
+    assert!(pair[8].contains("let (a, b) = split(x, y).unwrap();"));
+}
+
+#[test]
+fn test_gitignored_files_skipped_unless_no_gitignore() {
+    let dir = fixture();
+    fs::create_dir(dir.path().join("src/generated")).unwrap();
+    fs::write(
+        dir.path().join("src/generated/bindings.rs"),
+        "fn g() {\n    let v = ffi().unwrap();\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join(".gitignore"), "src/generated/\n").unwrap();
+
+    let files = |args: &[&str]| -> Vec<String> {
+        let output = bullshitdetector(args, dir.path());
+        assert!(output.status.success());
+        let alerts: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+        alerts
+            .iter()
+            .map(|a| a["file_path"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let scanned = files(&["scan", ".", "--output", "json"]);
+    assert!(!scanned.is_empty());
+    assert!(!scanned.iter().any(|f| f.starts_with("src/generated")));
+
+    let everything = files(&["scan", ".", "--output", "json", "--no-gitignore"]);
+    assert!(everything.iter().any(|f| f == "src/generated/bindings.rs"));
+}
+
+#[test]
+fn test_root_sets_reported_path_base() {
+    let dir = fixture();
+    let src = dir.path().join("src").canonicalize().unwrap();
+    let src = src.to_str().unwrap();
+
+    let paths = |args: &[&str]| -> Vec<String> {
+        let output = bullshitdetector(args, dir.path());
+        assert!(output.status.success());
+        let alerts: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+        let mut paths: Vec<String> = alerts
+            .iter()
+            .map(|a| a["file_path"].as_str().unwrap().to_string())
+            .collect();
+        paths.dedup();
+        paths
+    };
+
+    assert!(paths(&["scan", src, "--output", "json"]).contains(&"lib.rs".to_string()));
+
+    let parent = dir.path().canonicalize().unwrap();
+    let rooted = paths(&[
+        "scan",
+        src,
+        "--output",
+        "json",
+        "--root",
+        parent.to_str().unwrap(),
+    ]);
+    assert!(rooted.contains(&"src/lib.rs".to_string()));
+    assert_eq!(
+        rooted,
+        paths(&["--root", ".", "scan", src, "--output", "json"])
+    );
+}
+
+#[test]
+fn test_ignore_fingerprint_drops_only_that_alert() {
+    let dir = fixture();
+    let scan = |extra: &[&str]| -> Vec<BullshitAlert> {
+        let mut args = vec!["scan", ".", "--output", "json"];
+        args.extend_from_slice(extra);
+        let output = bullshitdetector(&args, dir.path());
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let all = scan(&[]);
+    assert!(all.len() > 1);
+    let ignored = fingerprint(&all[0]);
+
+    let rest = scan(&["--ignore-fingerprint", &ignored]);
+    assert_eq!(rest.len(), all.len() - 1);
+    assert!(rest.iter().all(|a| fingerprint(a) != ignored));
+
+    let text = bullshitdetector(&["--show-fingerprints", "scan", "."], dir.path());
+    let text = String::from_utf8(text.stdout).unwrap();
+    assert!(text.contains(&format!("Fingerprint: {}", ignored)));
+}
+
+#[test]
+fn test_gating_fails_only_under_gated_paths() {
+    let config = "[gating]\n\"src/core\" = \"high\"\n\"experiments/\" = \"never\"\n";
+    let run = |subdir: &str, args: &[&str]| {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(subdir)).unwrap();
+        fs::write(dir.path().join("bsd.toml"), config).unwrap();
+        fs::write(
+            dir.path().join(subdir).join("engine.rs"),
+            "fn step(x: u32) -> u32 {\n    dbg!(x)\n}\n",
+        )
+        .unwrap();
+        bullshitdetector(&[&["scan", "."][..], args].concat(), dir.path())
+    };
+
+    let gated = run("src/core", &[]);
+    assert_eq!(gated.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&gated.stdout).contains("DebugLeftover"));
+
+    assert!(run("src/experiments", &[]).status.success());
+    // `never` wins over --fail-on for experiments, which still applies elsewhere
+    assert!(run("src/experiments", &["--fail-on", "medium"])
+        .status
+        .success());
+    assert_eq!(
+        run("src/other", &["--fail-on", "medium"]).status.code(),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_lines_drops_alerts_outside_range() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("lib.rs"),
+        "fn f() {\n    let a = load().unwrap();\n    let b = load().unwrap();\n    let c = load().unwrap();\n}\n",
+    )
+    .unwrap();
+
+    let output = bullshitdetector(
+        &["scan", "lib.rs", "--output", "json", "--lines", "3:3"],
+        dir.path(),
+    );
+    assert!(output.status.success());
+    let alerts: Vec<BullshitAlert> = serde_json::from_slice(&output.stdout).unwrap();
+    let lines: Vec<usize> = alerts.iter().map(|a| a.location.line).collect();
+    assert_eq!(lines, vec![3]);
+
+    // Only meaningful for a single file
+    let output = bullshitdetector(&["scan", ".", "--lines", "3:3"], dir.path());
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_list_scanned_reports_whitelisted_file() {
+    let dir = fixture();
+    fs::write(
+        dir.path().join("src/config.rs"),
+        "pub fn timeout() -> u64 {\n    let t = 4500;\n    t\n}\n",
+    )
+    .unwrap();
+
+    let output = bullshitdetector(
+        &[
+            "scan-magic",
+            ".",
+            "--output",
+            "json",
+            "--list-scanned",
+            "--report-file",
+            "summary.json",
+        ],
+        dir.path(),
+    );
+    assert!(output.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(listing["alerts"].is_array());
+    assert_eq!(
+        listing["scanned"],
+        serde_json::json!(["src/lib.rs", "src/main.rs"])
+    );
+    assert_eq!(
+        listing["skipped"],
+        serde_json::json!([{ "path": "src/config.rs", "reason": "whitelisted" }])
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("summary.json")).unwrap())
+            .unwrap();
+    assert_eq!(report["skipped"], listing["skipped"]);
+}
+
+#[test]
+fn test_fail_fast_stops_after_first_failing_file() {
+    let dir = fixture();
+    let args = [
+        "scan",
+        ".",
+        "--output",
+        "json",
+        "--list-scanned",
+        "--fail-on",
+        "medium",
+    ];
+
+    let output = bullshitdetector(&args, dir.path());
+    assert_eq!(output.status.code(), Some(1));
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["scanned"].as_array().unwrap().len(), 2);
+
+    let output = bullshitdetector(&[&args[..], &["--fail-fast"]].concat(), dir.path());
+    assert_eq!(output.status.code(), Some(1));
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["scanned"], serde_json::json!(["src/lib.rs"]));
+
+    // Nothing to fail on
+    let output = bullshitdetector(&["scan", ".", "--fail-fast"], dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--fail-on"));
+
+    // A partial scan would drop baseline entries for the files it never reached
+    let output = bullshitdetector(
+        &[
+            &args[..],
+            &["--fail-fast", "--update-baseline", "baseline.json"],
+        ]
+        .concat(),
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--update-baseline"));
+    assert!(!dir.path().join("baseline.json").exists());
+}
+
+#[test]
+fn test_generated_file_skipped_unless_included() {
+    let dir = fixture();
+    fs::write(
+        dir.path().join("src/schema.rs"),
+        "// @generated automatically by Diesel CLI.\nfn g() {\n    let s = read().unwrap();\n}\n",
+    )
+    .unwrap();
+    let args = ["scan", ".", "--output", "json", "--list-scanned"];
+
+    let output = bullshitdetector(&args, dir.path());
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        listing["skipped"],
+        serde_json::json!([{ "path": "src/schema.rs", "reason": "generated" }])
+    );
+    let alerts: Vec<BullshitAlert> = serde_json::from_value(listing["alerts"].clone()).unwrap();
+    assert!(alerts
+        .iter()
+        .all(|a| a.file_path.as_deref() != Some("src/schema.rs")));
+
+    let output = bullshitdetector(&[&args[..], &["--include-generated"]].concat(), dir.path());
+    assert!(output.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["scanned"].as_array().unwrap().len(), 3);
+    let alerts: Vec<BullshitAlert> = serde_json::from_value(listing["alerts"].clone()).unwrap();
+    assert!(alerts
+        .iter()
+        .any(|a| a.file_path.as_deref() == Some("src/schema.rs")));
+}
+
+#[test]
+fn test_explain_exit_prints_codes_without_scanning() {
+    let dir = fixture();
+
+    let output = bullshitdetector(
+        &["scan", ".", "--explain-exit", "--fail-on", "high"],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in [
+        "  0  clean        the run finished and no alert failed the gate",
+        "  1  gate-failed  an alert failed --fail-on or a [gating] rule",
+        "  2  error        the run couldn't finish (bad flags, config, baseline or path)",
+        "  anywhere             high",
+    ] {
+        assert!(stdout.contains(line), "missing {:?} in:\n{}", line, stdout);
+    }
+    assert!(!stdout.contains("unwrap"));
+
+    let output = bullshitdetector(&["scan", ".", "--explain-exit"], dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("never exits 1"));
+
+    // Usage errors get their own code
+    let output = bullshitdetector(&["scan", ".", "--lines", "1:2"], dir.path());
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_since_days_out_of_range_is_an_error() {
+    let dir = fixture();
+
+    for days in ["200000000000", "18446744073709551615"] {
+        let output = bullshitdetector(&["scan", ".", "--since-days", days], dir.path());
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--since-days"));
+    }
+}